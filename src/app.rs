@@ -1,19 +1,45 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use arboard::Clipboard;
 use crossterm::event::{self, Event, KeyEvent};
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
 use std::time::Duration;
 
 #[cfg(target_os = "linux")]
 use arboard::SetExtLinux;
 
-use crate::config::Config;
+use crate::completion::{self, SchemaCache};
+use crate::config::{Config, SavedQuery};
 use crate::db::{
-    mysql::MySQLConnection, sqlite::SQLiteConnection, ConnectionInfo, DatabaseConnection,
-    DatabaseType,
+    background::{
+        FinishedQuery, ImportEvent, ImportOutcome, RunningImport, RunningQuery, RunningScript,
+        ScriptEvent,
+    },
+    mysql::MySQLConnection,
+    postgres::PostgresConnection,
+    sqlite::SQLiteConnection,
+    CellValue, ConnectionInfo, DatabaseConnection, DatabaseType, RowSink,
+};
+use crate::dump::{self, DumpOptions, InsertWriter};
+use crate::export::{self, ExportFormat, ExportWriter};
+use crate::external_editor::{EditTarget, ExternalEdit};
+use crate::history::{HistoryEntry, QueryHistory};
+use crate::import::{self, ImportFile, ImportJob, ValueKind};
+use crate::sql::split_statements;
+use crate::ui::{
+    import_dialog::TargetColumn,
+    param_prompt,
+    source_dialog::{SourceState, StatementOutcome},
+    ChangeReview, CompletionPopup, ConnectionManager, DatabaseBrowser, HistoryPopup, ImportDialog,
+    MessageLog, ParamPrompt, QueryBuffers, QueryEditor, RegistersPopup, ResultsViewer,
+    SavedQueryPicker, SourceDialog,
+};
+use crate::vim::{
+    motion::{Operator, YankedText},
+    register::{is_clipboard_register, Registers},
+    VimCommand, VimMode, VimState,
 };
-use crate::ui::{ConnectionManager, DatabaseBrowser, QueryEditor, ResultsViewer};
-use crate::vim::{VimCommand, VimMode, VimState};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Pane {
@@ -24,17 +50,34 @@ pub enum Pane {
 
 pub struct App {
     pub should_quit: bool,
+    pub quit_prompt: Option<String>, // Shown while asking whether to quit with unsaved changes
+    pub external_edit: Option<ExternalEdit>, // Waiting for the main loop to open $EDITOR
     pub vim_state: VimState,
     pub active_pane: Pane,
     pub database_browser: DatabaseBrowser,
-    pub query_editor: QueryEditor,
+    pub query_editor: QueryEditor, // The active buffer
+    pub buffers: QueryBuffers,
     pub results_viewer: ResultsViewer,
     pub connection_manager: ConnectionManager,
+    pub change_review: ChangeReview,
+    pub history_popup: HistoryPopup,
+    pub saved_query_picker: SavedQueryPicker,
+    pub param_prompt: ParamPrompt,
+    pub import_dialog: ImportDialog,
+    pub source_dialog: SourceDialog,
+    pub completion: CompletionPopup,
+    pub messages: MessageLog,
+    pub registers_popup: RegistersPopup,
     pub config: Config,
+    pub history: QueryHistory,
     pub connections: HashMap<usize, Box<dyn DatabaseConnection>>,
     pub next_connection_id: usize,
-    pub clipboard: Option<String>,
+    pub registers: Registers,
     pub system_clipboard: Option<Clipboard>,
+    pub running_query: Option<RunningQuery>,
+    pub running_script: Option<RunningScript>,
+    pub import_job: Option<RunningImport>,
+    pub schema_cache: HashMap<usize, SchemaCache>, // Completion metadata per connection
 }
 
 impl App {
@@ -46,19 +89,43 @@ impl App {
 
         let mut app = Self {
             should_quit: false,
+            quit_prompt: None,
+            external_edit: None,
             vim_state: VimState::new(),
             active_pane: Pane::DatabaseBrowser,
             database_browser: DatabaseBrowser::new(),
             query_editor: QueryEditor::new(),
+            buffers: QueryBuffers::new(),
             results_viewer: ResultsViewer::new(),
             connection_manager: ConnectionManager::new(),
+            change_review: ChangeReview::new(),
+            history_popup: HistoryPopup::new(),
+            saved_query_picker: SavedQueryPicker::new(),
+            param_prompt: ParamPrompt::new(),
+            import_dialog: ImportDialog::new(),
+            source_dialog: SourceDialog::new(),
+            completion: CompletionPopup::new(),
+            messages: MessageLog::new(),
+            registers_popup: RegistersPopup::new(),
             config,
+            history: QueryHistory::default(),
             connections: HashMap::new(),
             next_connection_id: 0,
-            clipboard: None,
+            registers: Registers::new(),
             system_clipboard,
+            running_query: None,
+            running_script: None,
+            import_job: None,
+            schema_cache: HashMap::new(),
         };
 
+        app.results_viewer.page_size = app.config.page_size.max(1);
+
+        match QueryHistory::load() {
+            Ok(history) => app.history = history,
+            Err(err) => app.report_error(err.context("Failed to load query history")),
+        }
+
         // Update focused states
         app.update_focus();
 
@@ -71,16 +138,199 @@ impl App {
     pub fn handle_events(&mut self) -> Result<()> {
         if event::poll(Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
-                self.handle_key_event(key)?;
+                // Errors from a single action are reported, never fatal to the session
+                if let Err(err) = self.handle_key_event(key) {
+                    self.report_error(err);
+                }
             }
         }
+        self.poll_running_query();
+        self.poll_running_import();
+        self.poll_running_script();
         Ok(())
     }
 
+    pub fn report_error(&mut self, err: anyhow::Error) {
+        // {:#} keeps the whole context chain, including the driver message
+        self.messages.error(format!("{:#}", err));
+    }
+
     fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
         use crate::ui::connection_manager::ConnectionManagerMode;
         use crossterm::event::{KeyCode, KeyModifiers};
 
+        // Any key press dismisses the message currently shown on the status bar
+        self.messages.clear_current();
+
+        // Quit with unsaved changes: y quits, any other key cancels
+        if self.quit_prompt.take().is_some() {
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.should_quit = true;
+            }
+            return Ok(());
+        }
+
+        // Ctrl+C cancels a running query instead of quitting
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            if let Some(job) = self.running_query.as_mut() {
+                job.cancel().context("Failed to cancel query")?;
+                self.messages.info("Cancelling query...".to_string());
+                return Ok(());
+            }
+            if self.running_script.is_some() {
+                return self.cancel_script();
+            }
+            if self.import_job.is_some() {
+                return self.cancel_import();
+            }
+        }
+
+        // Messages popup captures keys while open
+        if self.messages.visible {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.messages.hide(),
+                KeyCode::Char('j') | KeyCode::Down => self.messages.scroll_down(),
+                KeyCode::Char('k') | KeyCode::Up => self.messages.scroll_up(),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if self.registers_popup.visible {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.registers_popup.hide(),
+                KeyCode::Char('j') | KeyCode::Down => self.registers_popup.scroll_down(),
+                KeyCode::Char('k') | KeyCode::Up => self.registers_popup.scroll_up(),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // Review popup: confirm or cancel the pending statements
+        if self.change_review.visible {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') => self.apply_reviewed_edits()?,
+                KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q') => self.change_review.hide(),
+                KeyCode::Char('j') | KeyCode::Down => self.change_review.scroll_down(),
+                KeyCode::Char('k') | KeyCode::Up => self.change_review.scroll_up(),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // History popup: typing searches, Enter loads the query into the editor
+        if self.history_popup.visible {
+            match key.code {
+                KeyCode::Esc => self.history_popup.hide(),
+                KeyCode::Enter => {
+                    if let Some(entry) = self.history_popup.confirm() {
+                        self.query_editor.set_content(&entry.query);
+                        self.active_pane = Pane::QueryEditor;
+                        self.update_focus();
+                    }
+                }
+                KeyCode::Up => self.history_popup.move_up(),
+                KeyCode::Down => self.history_popup.move_down(),
+                KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.history_popup.move_up()
+                }
+                KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.history_popup.move_down()
+                }
+                KeyCode::Backspace => self.history_popup.backspace(),
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.history_popup.insert_char(c)
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // Saved queries picker works like the history popup
+        if self.saved_query_picker.visible {
+            match key.code {
+                KeyCode::Esc => self.saved_query_picker.hide(),
+                KeyCode::Enter => {
+                    if let Some((_, saved)) = self.saved_query_picker.confirm() {
+                        self.query_editor.set_content(&saved.query);
+                        self.active_pane = Pane::QueryEditor;
+                        self.update_focus();
+                    }
+                }
+                KeyCode::Up => self.saved_query_picker.move_up(),
+                KeyCode::Down => self.saved_query_picker.move_down(),
+                KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.saved_query_picker.move_up()
+                }
+                KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.saved_query_picker.move_down()
+                }
+                KeyCode::Backspace => self.saved_query_picker.backspace(),
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.saved_query_picker.insert_char(c)
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // Placeholder prompt: one value per ${name}, then the query runs
+        if self.param_prompt.visible {
+            match key.code {
+                KeyCode::Esc => self.param_prompt.hide(),
+                KeyCode::Enter => {
+                    if let Some(query) = self.param_prompt.submit() {
+                        self.spawn_query(query)?;
+                    }
+                }
+                KeyCode::Backspace => self.param_prompt.backspace(),
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.param_prompt.insert_char(c)
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // Import popup: map columns and start; while running only Esc (cancel) is accepted
+        if self.import_dialog.visible {
+            if self.import_dialog.is_running() {
+                if key.code == KeyCode::Esc {
+                    self.cancel_import()?;
+                }
+                return Ok(());
+            }
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.import_dialog.hide(),
+                KeyCode::Enter => self.start_import()?,
+                KeyCode::Tab => self.import_dialog.toggle_target(),
+                KeyCode::Char('j') | KeyCode::Down => self.import_dialog.move_down(),
+                KeyCode::Char('k') | KeyCode::Up => self.import_dialog.move_up(),
+                KeyCode::Char('l') | KeyCode::Char(' ') | KeyCode::Right => {
+                    self.import_dialog.cycle_target(true)
+                }
+                KeyCode::Char('h') | KeyCode::Left => self.import_dialog.cycle_target(false),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // Script popup: choose how errors are handled, run, then review each statement
+        if self.source_dialog.visible {
+            match (self.source_dialog.state, key.code) {
+                (SourceState::Running, KeyCode::Esc) => self.cancel_script()?,
+                (SourceState::Running, _) => {}
+                (SourceState::Ready, KeyCode::Tab) => self.source_dialog.toggle_stop_on_error(),
+                (SourceState::Ready, KeyCode::Enter) => self.start_script()?,
+                (_, KeyCode::Esc | KeyCode::Char('q'))
+                | (SourceState::Finished, KeyCode::Enter) => self.source_dialog.hide(),
+                (_, KeyCode::Char('j') | KeyCode::Down) => self.source_dialog.move_down(),
+                (_, KeyCode::Char('k') | KeyCode::Up) => self.source_dialog.move_up(),
+                _ => {}
+            }
+            return Ok(());
+        }
+
         // If connection manager is visible, handle keys differently
         if self.connection_manager.visible {
             match key.code {
@@ -178,6 +428,41 @@ impl App {
             return Ok(());
         }
 
+        // Completion popup: pick with Ctrl+N/Ctrl+P or Tab, typing narrows the list
+        if self.completion.visible {
+            match key.code {
+                KeyCode::Down | KeyCode::Tab => self.completion.move_down(),
+                KeyCode::Up | KeyCode::BackTab => self.completion.move_up(),
+                KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.completion.move_down()
+                }
+                KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.completion.move_up()
+                }
+                KeyCode::Enter => {
+                    if let Some(item) = self.completion.selected_item() {
+                        let text = item.text.clone();
+                        self.query_editor
+                            .complete_word(self.completion.prefix_len, &text);
+                    }
+                    self.completion.hide();
+                }
+                KeyCode::Esc => self.completion.hide(),
+                _ => {
+                    let narrow = matches!(key.code, KeyCode::Char(_) | KeyCode::Backspace)
+                        && !key.modifiers.contains(KeyModifiers::CONTROL);
+                    self.completion.hide();
+                    if let Some(vim_command) = self.vim_state.handle_key(key) {
+                        self.execute_vim_command(vim_command)?;
+                    }
+                    if narrow && self.vim_state.mode == VimMode::Insert {
+                        self.open_completion(false)?;
+                    }
+                }
+            }
+            return Ok(());
+        }
+
         // Handle database browser search mode
         if self.active_pane == Pane::DatabaseBrowser && self.database_browser.search_mode {
             match key.code {
@@ -226,7 +511,7 @@ impl App {
             }
         }
 
-        // Handle tab switching in Results pane (1, 2, 3 keys)
+        // Handle tab switching in Results pane (1, 2, 3 keys, [ and ] for statement tabs)
         if self.active_pane == Pane::Results
             && !self.results_viewer.edit_mode
             && !self.results_viewer.insert_mode
@@ -246,6 +531,14 @@ impl App {
                     self.results_viewer.switch_to_indexes_tab();
                     return Ok(());
                 }
+                KeyCode::Char(']') => {
+                    self.results_viewer.next_statement_tab();
+                    return Ok(());
+                }
+                KeyCode::Char('[') => {
+                    self.results_viewer.previous_statement_tab();
+                    return Ok(());
+                }
                 _ => {}
             }
         }
@@ -316,10 +609,29 @@ impl App {
         Ok(())
     }
 
+    /// Run a command; edits it makes to the query editor are recorded as one undo step, or
+    /// as part of the insert session it starts or continues.
     fn execute_vim_command(&mut self, command: VimCommand) -> Result<()> {
+        let records_change = self.active_pane == Pane::QueryEditor
+            && !matches!(
+                command,
+                VimCommand::Undo | VimCommand::Redo | VimCommand::UndoOlder | VimCommand::UndoNewer
+            );
+        if records_change {
+            self.query_editor.begin_change();
+        }
+        let register = self.vim_state.take_register();
+        let result = self.apply_vim_command(command, register);
+        if self.vim_state.mode != VimMode::Insert {
+            self.query_editor.commit_change();
+        }
+        result
+    }
+
+    fn apply_vim_command(&mut self, command: VimCommand, register: Option<char>) -> Result<()> {
         match command {
             VimCommand::Quit => {
-                self.should_quit = true;
+                self.request_quit();
             }
             VimCommand::ExecuteCommand(cmd) => {
                 self.execute_command(&cmd)?;
@@ -330,106 +642,164 @@ impl App {
             VimCommand::PrevPane => {
                 self.prev_pane();
             }
-            VimCommand::Activate => {
-                if self.active_pane == Pane::DatabaseBrowser {
-                    self.load_selected_table_data()?;
-                }
+            VimCommand::NextBuffer(Some(number)) => {
+                self.switch_buffer(number.saturating_sub(1))?;
             }
-            VimCommand::ExecuteQueryUnderCursor => {
-                if self.active_pane == Pane::QueryEditor {
-                    self.execute_query_at_cursor()?;
-                }
+            VimCommand::NextBuffer(None) => {
+                self.switch_buffer(self.buffers.offset(1, true))?;
             }
-            VimCommand::ExecuteAllQueries => {
-                if self.active_pane == Pane::QueryEditor {
-                    self.execute_query()?;
-                }
+            VimCommand::PrevBuffer(count) => {
+                self.switch_buffer(self.buffers.offset(count, false))?;
             }
-            VimCommand::EnterInsertMode | VimCommand::EnterInsertModeAfter => {
-                if self.active_pane == Pane::QueryEditor {
-                    self.vim_state.enter_insert_mode();
-                }
+            VimCommand::Activate if self.active_pane == Pane::DatabaseBrowser => {
+                self.load_selected_table_data()?;
             }
-            VimCommand::ExitInsertMode => {
-                self.vim_state.enter_normal_mode();
-                // If we're in results viewer edit mode, save the current cell
-                if self.active_pane == Pane::Results && self.results_viewer.edit_mode {
-                    self.results_viewer.save_cell_edit();
-                }
-                // If we're in results viewer insert mode, save the current field
-                if self.active_pane == Pane::Results && self.results_viewer.insert_mode {
-                    self.results_viewer.save_insert_field();
-                }
+            VimCommand::ExecuteQueryUnderCursor if self.active_pane == Pane::QueryEditor => {
+                self.execute_query_at_cursor()?;
             }
-            VimCommand::EnterInsertRowMode => {
-                if self.active_pane == Pane::Results {
-                    if self.results_viewer.active_tab == crate::ui::results_viewer::TabMode::Schema
-                    {
-                        self.results_viewer.enter_schema_insert_mode();
-                        self.vim_state.enter_insert_mode();
-                    } else {
-                        self.results_viewer.enter_insert_mode();
-                        self.vim_state.enter_insert_mode();
-                    }
-                }
+            VimCommand::ExecuteAllQueries if self.active_pane == Pane::QueryEditor => {
+                self.execute_query()?;
             }
-            VimCommand::SaveInsertRow => {
-                if self.active_pane == Pane::Results {
-                    self.save_insert_row()?;
+            VimCommand::Undo | VimCommand::Redo | VimCommand::UndoOlder | VimCommand::UndoNewer
+                if self.active_pane == Pane::QueryEditor =>
+            {
+                let (moved, limit) = match command {
+                    VimCommand::Undo => (self.query_editor.undo(), "oldest"),
+                    VimCommand::Redo => (self.query_editor.redo(), "newest"),
+                    VimCommand::UndoOlder => (self.query_editor.undo_older(), "oldest"),
+                    _ => (self.query_editor.undo_newer(), "newest"),
+                };
+                if !moved {
+                    self.messages.info(format!("Already at {} change", limit));
                 }
             }
-            VimCommand::EnterEditMode => {
-                if self.active_pane == Pane::Results {
-                    if self.results_viewer.active_tab == crate::ui::results_viewer::TabMode::Schema
-                    {
-                        self.results_viewer.enter_schema_edit_mode();
-                        self.vim_state.enter_insert_mode();
-                    } else {
-                        self.results_viewer.enter_edit_mode();
+            VimCommand::Motion(motion, count) if self.active_pane == Pane::QueryEditor => {
+                self.query_editor.apply_motion(motion, count);
+            }
+            VimCommand::Operate {
+                operator,
+                target,
+                count,
+            } if self.active_pane == Pane::QueryEditor => {
+                if let Some(yanked) = self.query_editor.operate(operator, target, count) {
+                    self.store_register(register, yanked);
+                    if operator == Operator::Change {
                         self.vim_state.enter_insert_mode();
                     }
                 }
             }
-            VimCommand::ExitEditMode => {
-                if self.active_pane == Pane::Results {
-                    if self.results_viewer.schema_edit_mode {
-                        self.results_viewer.exit_schema_edit_mode();
-                        self.vim_state.enter_normal_mode();
-                    } else if self.results_viewer.edit_mode {
-                        self.results_viewer.exit_edit_mode();
-                        self.vim_state.enter_normal_mode();
-                    }
-                }
+            VimCommand::Complete if self.active_pane == Pane::QueryEditor => {
+                self.open_completion(true)?;
             }
-            VimCommand::MoveColumnLeft => {
-                if self.active_pane == Pane::Results && self.results_viewer.edit_mode {
-                    self.results_viewer.move_column_left();
+            VimCommand::EnterInsertMode | VimCommand::EnterInsertModeAfter
+                if self.active_pane == Pane::QueryEditor =>
+            {
+                self.vim_state.enter_insert_mode();
+            }
+            VimCommand::ExitInsertMode => {
+                self.completion.hide();
+                self.vim_state.enter_normal_mode();
+                // Save the cell or new-row field being edited; a bad value keeps it open
+                let saved = if self.active_pane != Pane::Results {
+                    Ok(())
+                } else if self.results_viewer.edit_mode {
+                    self.results_viewer.save_cell_edit()
+                } else if self.results_viewer.insert_mode {
+                    self.results_viewer.save_insert_field()
+                } else {
+                    Ok(())
+                };
+                if saved.is_err() {
+                    self.vim_state.enter_insert_mode();
                 }
+                saved?;
             }
-            VimCommand::MoveColumnRight => {
-                if self.active_pane == Pane::Results && self.results_viewer.edit_mode {
-                    self.results_viewer.move_column_right();
+            VimCommand::EnterInsertRowMode if self.active_pane == Pane::Results => {
+                if self.results_viewer.active_tab == crate::ui::results_viewer::TabMode::Schema {
+                    self.results_viewer.enter_schema_insert_mode();
+                    self.vim_state.enter_insert_mode();
+                } else {
+                    self.results_viewer.enter_insert_mode();
+                    self.vim_state.enter_insert_mode();
                 }
             }
-            VimCommand::SaveAllEdits => {
-                if self.active_pane == Pane::Results {
-                    if self.results_viewer.insert_mode {
-                        self.save_insert_row()?;
-                    } else {
-                        self.save_table_edits()?;
-                    }
+            VimCommand::SaveInsertRow if self.active_pane == Pane::Results => {
+                self.review_table_edits()?;
+            }
+            VimCommand::EnterEditMode if self.active_pane == Pane::Results => {
+                if self.results_viewer.active_tab == crate::ui::results_viewer::TabMode::Schema {
+                    self.results_viewer.enter_schema_edit_mode();
+                    self.vim_state.enter_insert_mode();
+                } else {
+                    self.results_viewer.enter_edit_mode();
+                    self.vim_state.enter_insert_mode();
                 }
             }
-            VimCommand::EnterVisualMode => {
-                if self.active_pane == Pane::QueryEditor {
-                    self.query_editor.start_visual_mode();
+            VimCommand::ExitEditMode if self.active_pane == Pane::Results => {
+                if self.results_viewer.schema_edit_mode {
+                    self.results_viewer.exit_schema_edit_mode();
+                    self.vim_state.enter_normal_mode();
+                } else if self.results_viewer.edit_mode {
+                    self.results_viewer.exit_edit_mode();
+                    self.vim_state.enter_normal_mode();
                 }
             }
-            VimCommand::ExitVisualMode => {
-                if self.active_pane == Pane::QueryEditor {
-                    self.query_editor.exit_visual_mode();
+            VimCommand::SetNull
+                if self.active_pane == Pane::Results
+                    && (self.results_viewer.edit_mode || self.results_viewer.insert_mode) =>
+            {
+                self.results_viewer.set_null();
+            }
+            VimCommand::MoveColumnLeft
+                if self.active_pane == Pane::Results && self.results_viewer.edit_mode =>
+            {
+                self.results_viewer.move_column_left();
+            }
+            VimCommand::MoveColumnRight
+                if self.active_pane == Pane::Results && self.results_viewer.edit_mode =>
+            {
+                self.results_viewer.move_column_right();
+            }
+            VimCommand::SaveAllEdits if self.active_pane == Pane::Results => {
+                self.review_table_edits()?;
+            }
+            VimCommand::EnterVisualMode if self.active_pane == Pane::QueryEditor => {
+                self.query_editor.start_visual_mode();
+            }
+            VimCommand::ExitVisualMode if self.active_pane == Pane::QueryEditor => {
+                self.query_editor.exit_visual_mode();
+            }
+            VimCommand::EnterVisualLineMode if self.active_pane == Pane::QueryEditor => {
+                self.query_editor.start_visual_mode();
+            }
+            VimCommand::EnterVisualLineMode if self.active_pane == Pane::Results => {
+                if self.can_mark_rows() {
+                    self.results_viewer.start_row_selection();
+                } else {
+                    self.vim_state.enter_normal_mode();
                 }
             }
+            VimCommand::ExtendUp
+                if self.active_pane == Pane::Results
+                    && self.results_viewer.row_selection_anchor.is_some() =>
+            {
+                self.results_viewer.move_up(1);
+            }
+            VimCommand::ExtendDown
+                if self.active_pane == Pane::Results
+                    && self.results_viewer.row_selection_anchor.is_some() =>
+            {
+                self.results_viewer.move_down(1);
+            }
+            VimCommand::DeleteSelection if self.active_pane == Pane::Results => {
+                self.results_viewer.mark_selection_for_deletion();
+            }
+            VimCommand::ExitVisualMode if self.active_pane == Pane::Results => {
+                self.results_viewer.cancel_row_selection();
+            }
+            VimCommand::DeleteLine if self.active_pane == Pane::Results && self.can_mark_rows() => {
+                self.results_viewer.toggle_row_deletion();
+            }
             VimCommand::MoveUp(count) => match self.active_pane {
                 Pane::DatabaseBrowser => {
                     for _ in 0..count {
@@ -445,6 +815,9 @@ impl App {
                         for _ in 0..count {
                             self.results_viewer.schema_move_up();
                         }
+                    } else if self.at_page_edge(false) {
+                        // Moving up from the first row continues on the previous page
+                        self.load_prev_page()?;
                     } else {
                         self.results_viewer.move_up(count);
                     }
@@ -465,6 +838,9 @@ impl App {
                         for _ in 0..count {
                             self.results_viewer.schema_move_down();
                         }
+                    } else if self.at_page_edge(true) {
+                        // Moving down from the last row continues on the next page
+                        self.load_next_page()?;
                     } else {
                         self.results_viewer.move_down(count);
                     }
@@ -475,12 +851,12 @@ impl App {
                     self.query_editor.move_left(count);
                 } else if self.active_pane == Pane::Results && self.results_viewer.edit_mode {
                     for _ in 0..count {
-                        self.results_viewer.save_cell_edit();
+                        self.results_viewer.save_cell_edit()?;
                         self.results_viewer.move_column_left();
                     }
                 } else if self.active_pane == Pane::Results && self.results_viewer.insert_mode {
                     for _ in 0..count {
-                        self.results_viewer.save_insert_field();
+                        self.results_viewer.save_insert_field()?;
                         self.results_viewer.move_column_left();
                     }
                 } else if self.active_pane == Pane::Results {
@@ -495,12 +871,12 @@ impl App {
                     self.query_editor.move_right(count);
                 } else if self.active_pane == Pane::Results && self.results_viewer.edit_mode {
                     for _ in 0..count {
-                        self.results_viewer.save_cell_edit();
+                        self.results_viewer.save_cell_edit()?;
                         self.results_viewer.move_column_right();
                     }
                 } else if self.active_pane == Pane::Results && self.results_viewer.insert_mode {
                     for _ in 0..count {
-                        self.results_viewer.save_insert_field();
+                        self.results_viewer.save_insert_field()?;
                         self.results_viewer.move_column_right();
                     }
                 } else if self.active_pane == Pane::Results {
@@ -520,60 +896,59 @@ impl App {
                 Pane::Results => self.results_viewer.goto_bottom(),
                 _ => {}
             },
-            VimCommand::GotoLineStart => {
-                if self.active_pane == Pane::QueryEditor {
-                    self.query_editor.goto_line_start();
-                }
+            VimCommand::NextPage if self.active_pane == Pane::Results => {
+                self.load_next_page()?;
             }
-            VimCommand::GotoLineEnd => {
-                if self.active_pane == Pane::QueryEditor {
-                    self.query_editor.goto_line_end();
-                }
-            }
-            VimCommand::InsertChar(c) => {
-                if self.vim_state.mode == VimMode::Insert {
-                    if self.active_pane == Pane::QueryEditor {
-                        self.query_editor.insert_char(c);
-                    } else if self.active_pane == Pane::Results
-                        && (self.results_viewer.edit_mode || self.results_viewer.insert_mode)
-                    {
-                        self.results_viewer.edit_insert_char(c);
-                    }
-                }
+            VimCommand::PrevPage if self.active_pane == Pane::Results => {
+                self.load_prev_page()?;
             }
-            VimCommand::InsertNewline => {
-                if self.vim_state.mode == VimMode::Insert && self.active_pane == Pane::QueryEditor {
-                    self.query_editor.insert_newline();
-                }
+            VimCommand::GotoLineStart if self.active_pane == Pane::QueryEditor => {
+                self.query_editor.goto_line_start();
             }
-            VimCommand::Backspace => {
-                if self.vim_state.mode == VimMode::Insert {
-                    if self.active_pane == Pane::QueryEditor {
-                        self.query_editor.backspace();
-                    } else if self.active_pane == Pane::Results
-                        && (self.results_viewer.edit_mode || self.results_viewer.insert_mode)
-                    {
-                        self.results_viewer.edit_backspace();
-                    }
-                }
+            VimCommand::GotoLineEnd if self.active_pane == Pane::QueryEditor => {
+                self.query_editor.goto_line_end();
             }
-            VimCommand::DeleteChar => {
+            VimCommand::InsertChar(c) if self.vim_state.mode == VimMode::Insert => {
                 if self.active_pane == Pane::QueryEditor {
-                    self.query_editor.delete_char();
+                    self.query_editor.insert_char(c);
+                } else if self.active_pane == Pane::Results
+                    && (self.results_viewer.edit_mode || self.results_viewer.insert_mode)
+                {
+                    self.results_viewer.edit_insert_char(c);
                 }
             }
-            VimCommand::YankSelection => {
+            VimCommand::InsertNewline
+                if self.vim_state.mode == VimMode::Insert
+                    && self.active_pane == Pane::QueryEditor =>
+            {
+                self.query_editor.insert_newline();
+            }
+            VimCommand::Backspace if self.vim_state.mode == VimMode::Insert => {
                 if self.active_pane == Pane::QueryEditor {
-                    if let Some(text) = self.query_editor.get_selection() {
-                        self.clipboard = Some(text);
-                    }
-                    self.query_editor.exit_visual_mode();
+                    self.query_editor.backspace();
+                } else if self.active_pane == Pane::Results
+                    && (self.results_viewer.edit_mode || self.results_viewer.insert_mode)
+                {
+                    self.results_viewer.edit_backspace();
                 }
             }
-            VimCommand::DeleteConnection => {
-                if self.active_pane == Pane::DatabaseBrowser {
-                    self.delete_connection()?;
+            VimCommand::DeleteChar if self.active_pane == Pane::QueryEditor => {
+                self.query_editor.delete_char();
+            }
+            VimCommand::YankSelection if self.active_pane == Pane::QueryEditor => {
+                if let Some(text) = self.query_editor.get_selection() {
+                    self.store_register(
+                        register,
+                        YankedText {
+                            text,
+                            linewise: false,
+                        },
+                    );
                 }
+                self.query_editor.exit_visual_mode();
+            }
+            VimCommand::DeleteConnection if self.active_pane == Pane::DatabaseBrowser => {
+                self.delete_connection()?;
             }
             VimCommand::OpenConnectionManager => {
                 self.connection_manager.show();
@@ -584,70 +959,84 @@ impl App {
             VimCommand::ConnectionManagerAction(action) => {
                 self.handle_connection_manager_action(action)?;
             }
-            VimCommand::StartSearch => {
-                if self.active_pane == Pane::DatabaseBrowser {
-                    self.database_browser.enter_search_mode();
-                }
+            VimCommand::StartSearch if self.active_pane == Pane::DatabaseBrowser => {
+                self.database_browser.enter_search_mode();
             }
-            VimCommand::DiscardChanges => {
-                if self.active_pane == Pane::Results {
-                    if self.results_viewer.active_tab == crate::ui::results_viewer::TabMode::Schema
-                    {
-                        self.results_viewer.discard_schema_changes();
-                    } else {
-                        self.results_viewer.discard_all_changes();
-                    }
-                    self.vim_state.enter_normal_mode();
+            VimCommand::DiscardChanges if self.active_pane == Pane::Results => {
+                if self.results_viewer.active_tab == crate::ui::results_viewer::TabMode::Schema {
+                    self.results_viewer.discard_schema_changes();
+                } else {
+                    self.results_viewer.discard_all_changes();
                 }
+                self.vim_state.enter_normal_mode();
             }
             VimCommand::RefreshData => {
+                // Completion reads the schema again on next use
+                if let Some(conn_id) = self.database_browser.selected_connection {
+                    self.schema_cache.remove(&conn_id);
+                }
                 if self.active_pane == Pane::Results {
                     self.refresh_current_table()?;
                 } else if self.active_pane == Pane::DatabaseBrowser {
+                    self.ensure_connection_idle()?;
                     // Refresh database/table list
                     if let Some(conn_id) = self.database_browser.selected_connection {
                         if let Some(conn) = self.connections.get_mut(&conn_id) {
-                            let tables = conn.list_tables()?;
+                            let tables = conn.list_tables().context("Failed to list tables")?;
                             self.database_browser.set_tables(tables);
                         }
                     }
                 }
             }
-            VimCommand::CopyCellValue => {
-                if self.active_pane == Pane::Results {
-                    if let Some(value) = self.results_viewer.get_current_cell_value() {
-                        // Copy to internal clipboard
-                        self.clipboard = Some(value.clone());
-
-                        // Copy to system clipboard using the persistent instance
-                        if let Some(clipboard) = &mut self.system_clipboard {
-                            #[cfg(target_os = "linux")]
-                            let result = clipboard.set().wait().text(&value);
-
-                            #[cfg(not(target_os = "linux"))]
-                            let result = clipboard.set_text(&value);
-
-                            match result {
-                                Ok(_) => {
-                                    self.results_viewer.set_status_message(format!(
-                                        "Copied to clipboard: {}",
-                                        if value.len() > 50 {
-                                            format!("{}...", &value[..50])
-                                        } else {
-                                            value
-                                        }
-                                    ));
-                                }
-                                Err(e) => {
-                                    self.results_viewer
-                                        .set_status_message(format!("Copy failed: {}", e));
+            VimCommand::CopyCellValue if self.active_pane == Pane::Results => {
+                if let Some(value) = self.results_viewer.get_current_cell_value() {
+                    let yanked = YankedText {
+                        text: value.clone(),
+                        linewise: false,
+                    };
+                    // Without a register named, yy copies to the system clipboard
+                    let register = register.unwrap_or('+');
+                    if is_clipboard_register(register) {
+                        let message = match self.set_system_clipboard(&value) {
+                            Ok(()) => format!(
+                                "Copied to clipboard: {}",
+                                if value.chars().count() > 50 {
+                                    format!("{}...", value.chars().take(50).collect::<String>())
+                                } else {
+                                    value
                                 }
-                            }
-                        } else {
-                            self.results_viewer
-                                .set_status_message("Clipboard not available".to_string());
-                        }
+                            ),
+                            Err(e) => format!("{:#}", e),
+                        };
+                        self.results_viewer.set_status_message(message);
+                        self.registers.store(None, yanked);
+                    } else {
+                        self.registers.store(Some(register), yanked);
+                        self.results_viewer
+                            .set_status_message(format!("Yanked to register \"{}", register));
+                    }
+                }
+            }
+            VimCommand::Paste { before, count } if self.active_pane == Pane::QueryEditor => {
+                match self.read_register(register)? {
+                    Some(yanked) => self.query_editor.paste(&yanked, before, count),
+                    None => self
+                        .messages
+                        .info(format!("Nothing in register {}", register.unwrap_or('"'))),
+                }
+            }
+            // In the grid, p and P both replace the selected cell's value
+            VimCommand::Paste { .. }
+                if self.active_pane == Pane::Results && self.can_mark_rows() =>
+            {
+                match self.read_register(register)? {
+                    Some(yanked) => {
+                        let value = yanked.text.strip_suffix('\n').unwrap_or(&yanked.text);
+                        self.results_viewer.paste_cell_value(value)?;
                     }
+                    None => self
+                        .messages
+                        .info(format!("Nothing in register {}", register.unwrap_or('"'))),
                 }
             }
             _ => {}
@@ -655,6 +1044,65 @@ impl App {
         Ok(())
     }
 
+    /// Put yanked or deleted text in `register`; `+` and `*` write the system clipboard.
+    fn store_register(&mut self, register: Option<char>, yanked: YankedText) {
+        match register {
+            Some(name) if is_clipboard_register(name) => {
+                if let Err(err) = self.set_system_clipboard(&yanked.text) {
+                    self.report_error(err);
+                }
+                self.registers.store(None, yanked);
+            }
+            _ => self.registers.store(register, yanked),
+        }
+    }
+
+    fn read_register(&mut self, register: Option<char>) -> Result<Option<YankedText>> {
+        match register {
+            Some(name) if is_clipboard_register(name) => {
+                let clipboard = self
+                    .system_clipboard
+                    .as_mut()
+                    .context("Clipboard not available")?;
+                let text = clipboard
+                    .get_text()
+                    .context("Failed to read the clipboard")?;
+                // Copied text ending in a line break pastes as whole lines
+                Ok(Some(YankedText {
+                    linewise: text.ends_with('\n'),
+                    text,
+                }))
+            }
+            _ => Ok(self.registers.get(register).cloned()),
+        }
+    }
+
+    fn set_system_clipboard(&mut self, text: &str) -> Result<()> {
+        // Use the persistent instance so the copied text stays available
+        let clipboard = self
+            .system_clipboard
+            .as_mut()
+            .context("Clipboard not available")?;
+
+        #[cfg(target_os = "linux")]
+        let result = clipboard.set().wait().text(text);
+
+        #[cfg(not(target_os = "linux"))]
+        let result = clipboard.set_text(text);
+
+        result.context("Copy failed")
+    }
+
+    fn show_registers(&mut self) {
+        let mut entries = self.registers.entries();
+        if let Ok(Some(yanked)) = self.read_register(Some('+')) {
+            if !yanked.text.is_empty() {
+                entries.push(('+', yanked));
+            }
+        }
+        self.registers_popup.show(entries);
+    }
+
     fn execute_command(&mut self, cmd: &str) -> Result<()> {
         let parts: Vec<&str> = cmd.split_whitespace().collect();
         if parts.is_empty() {
@@ -663,8 +1111,27 @@ impl App {
 
         match parts[0] {
             "q" | "quit" => {
+                self.request_quit();
+            }
+            "q!" | "quit!" => {
                 self.should_quit = true;
             }
+            "e" | "edit" | "e!" | "edit!" => {
+                self.edit_file(&parts[1..], parts[0].ends_with('!'))?;
+            }
+            "w" | "write" => {
+                self.write_file(&parts[1..], false)?;
+            }
+            "saveas" | "sav" => {
+                self.write_file(&parts[1..], true)?;
+            }
+            "wq" | "x" => {
+                self.write_file(&[], false)?;
+                self.request_quit();
+            }
+            "r" | "read" => {
+                self.read_file(&parts[1..])?;
+            }
             "open" => {
                 if parts.len() < 2 {
                     return Ok(());
@@ -686,6 +1153,13 @@ impl App {
                 let connection_string = parts[1..].join(" ");
                 self.open_database(&connection_string, DatabaseType::MariaDB)?;
             }
+            "postgres" | "postgresql" | "pg" => {
+                if parts.len() < 2 {
+                    return Ok(());
+                }
+                let connection_string = parts[1..].join(" ");
+                self.open_database(&connection_string, DatabaseType::PostgreSQL)?;
+            }
             "exec" | "execute" => {
                 self.execute_query()?;
             }
@@ -699,14 +1173,83 @@ impl App {
             "connections" | "conn" => {
                 self.connection_manager.show();
             }
-            _ => {}
-        }
-        Ok(())
-    }
-
-    fn open_database(&mut self, connection_string: &str, db_type: DatabaseType) -> Result<()> {
-        self.open_database_with_save(connection_string, db_type, true)
-    }
+            "export" => {
+                self.export_results(&parts[1..])?;
+            }
+            "dump" => {
+                self.dump_database(&parts[1..])?;
+            }
+            "source" | "so" => {
+                self.open_source(&parts[1..])?;
+            }
+            "import" => {
+                self.open_import(&parts[1..])?;
+            }
+            "save-query" => {
+                self.save_query(&parts[1..])?;
+            }
+            "queries" => {
+                let connection_string = self
+                    .database_browser
+                    .get_selected_connection()
+                    .map(|c| c.connection_string.clone());
+                self.saved_query_picker
+                    .show(self.config.saved_queries_for(connection_string.as_deref()));
+            }
+            "history" | "his" => {
+                self.history_popup.show(self.history.entries.clone());
+            }
+            "editor" => {
+                self.open_external_editor()?;
+            }
+            "b" | "buffer" => {
+                let number = parts
+                    .get(1)
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .ok_or_else(|| anyhow::anyhow!("Usage: :b <number>"))?;
+                self.switch_buffer(number.saturating_sub(1))?;
+            }
+            "tabnew" | "tabe" => {
+                let name = (parts.len() > 1).then(|| parts[1..].join(" "));
+                let idx = self.buffers.add(name);
+                self.switch_buffer(idx)?;
+            }
+            "bd" | "bdelete" | "tabclose" | "tabc" | "bd!" | "bdelete!" | "tabclose!" | "tabc!" => {
+                self.close_buffer(parts[0].ends_with('!'))?;
+            }
+            "registers" | "reg" | "display" | "di" => {
+                self.show_registers();
+            }
+            "messages" | "mes" => {
+                if parts.get(1) == Some(&"clear") {
+                    self.messages.clear();
+                } else {
+                    self.messages.show();
+                }
+            }
+            _ => {
+                return Err(anyhow::anyhow!("Not an editor command: {}", cmd));
+            }
+        }
+        Ok(())
+    }
+
+    fn connect_database(
+        connection_string: &str,
+        db_type: &DatabaseType,
+    ) -> Result<Box<dyn DatabaseConnection>> {
+        match db_type {
+            DatabaseType::SQLite => Ok(SQLiteConnection::connect(connection_string)?),
+            DatabaseType::MySQL | DatabaseType::MariaDB => {
+                MySQLConnection::connect(connection_string)
+            }
+            DatabaseType::PostgreSQL => PostgresConnection::connect(connection_string),
+        }
+    }
+
+    fn open_database(&mut self, connection_string: &str, db_type: DatabaseType) -> Result<()> {
+        self.open_database_with_save(connection_string, db_type, true)
+    }
 
     fn open_database_no_save(
         &mut self,
@@ -737,15 +1280,10 @@ impl App {
 
             // If connection object isn't opened yet, open and insert it
             if !self.connections.contains_key(&id) {
-                let mut conn: Box<dyn DatabaseConnection> = match db_type {
-                    DatabaseType::SQLite => SQLiteConnection::connect(connection_string)?,
-                    DatabaseType::MySQL | DatabaseType::MariaDB => {
-                        MySQLConnection::connect(connection_string)?
-                    }
-                };
+                let mut conn = Self::connect_database(connection_string, &db_type)?;
 
                 // Load tables for the selected connection
-                let tables = conn.list_tables()?;
+                let tables = conn.list_tables().context("Failed to list tables")?;
                 self.database_browser.set_tables(tables);
                 self.connections.insert(id, conn);
             }
@@ -754,12 +1292,7 @@ impl App {
         }
 
         // Create connection based on database type
-        let mut conn: Box<dyn DatabaseConnection> = match db_type {
-            DatabaseType::SQLite => SQLiteConnection::connect(connection_string)?,
-            DatabaseType::MySQL | DatabaseType::MariaDB => {
-                MySQLConnection::connect(connection_string)?
-            }
-        };
+        let mut conn = Self::connect_database(connection_string, &db_type)?;
 
         let id = self.next_connection_id;
         self.next_connection_id += 1;
@@ -783,112 +1316,1268 @@ impl App {
                 if let Some(db_name) = connection_string.split('/').next_back() {
                     format!("MariaDB: {}", db_name.split('?').next().unwrap_or(db_name))
                 } else {
-                    format!("MariaDB Connection {}", id)
+                    format!("MariaDB Connection {}", id)
+                }
+            }
+            DatabaseType::PostgreSQL => {
+                if let Some(db_name) = connection_string.split('/').next_back() {
+                    format!(
+                        "PostgreSQL: {}",
+                        db_name.split('?').next().unwrap_or(db_name)
+                    )
+                } else {
+                    format!("PostgreSQL Connection {}", id)
+                }
+            }
+        };
+
+        let conn_info = ConnectionInfo {
+            id,
+            name: name.clone(),
+            db_type: db_type.clone(),
+            connection_string: connection_string.to_string(),
+        };
+
+        self.database_browser.add_connection(conn_info);
+        self.database_browser.selected_connection = Some(id);
+        self.messages.info(format!("Connected to {}", name));
+
+        // Load tables
+        let tables = conn.list_tables().context("Failed to list tables")?;
+        self.database_browser.set_tables(tables);
+
+        self.connections.insert(id, conn);
+
+        // Save to config only if requested
+        if save_to_config {
+            self.config.add_connection(
+                name,
+                connection_string.to_string(),
+                db_type.config_name().to_string(),
+            );
+            self.config.save()?;
+        }
+
+        Ok(())
+    }
+
+    fn go_back_to_database_list(&mut self) -> Result<()> {
+        self.ensure_connection_idle()?;
+
+        // Reset the database browser to show databases instead of tables
+        self.database_browser.go_back_to_databases();
+
+        // For MySQL/MariaDB connections, clear database context and reload database list
+        if let Some(conn_id) = self.database_browser.selected_connection {
+            if let Some(conn) = self.connections.get_mut(&conn_id) {
+                let conn_info = self
+                    .database_browser
+                    .connections
+                    .iter()
+                    .find(|c| c.id == conn_id);
+
+                if let Some(info) = conn_info {
+                    match info.db_type {
+                        DatabaseType::MySQL | DatabaseType::MariaDB => {
+                            // For MySQL, clear the database context first, then get database list
+                            if let Some(mysql_conn) =
+                                conn.as_any_mut().downcast_mut::<MySQLConnection>()
+                            {
+                                mysql_conn.clear_database_context()?;
+                            }
+                            let tables = conn.list_tables().context("Failed to list tables")?;
+                            self.database_browser.set_tables(tables);
+                        }
+                        DatabaseType::PostgreSQL => {
+                            // For PostgreSQL, leave the schema and list schemas again
+                            if let Some(pg_conn) =
+                                conn.as_any_mut().downcast_mut::<PostgresConnection>()
+                            {
+                                pg_conn.clear_schema_context()?;
+                            }
+                            let tables = conn.list_tables().context("Failed to list tables")?;
+                            self.database_browser.set_tables(tables);
+                        }
+                        DatabaseType::SQLite => {}
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn execute_query(&mut self) -> Result<()> {
+        let query = self.query_editor.get_query();
+        if query.trim().is_empty() {
+            return Ok(());
+        }
+
+        self.start_query(query)
+    }
+
+    /// Run a query from the editor, asking for `${name}` placeholder values first.
+    fn start_query(&mut self, query: String) -> Result<()> {
+        if self.running_query.is_some() {
+            return Err(anyhow::anyhow!(
+                "A query is already running (Ctrl+C to cancel)"
+            ));
+        }
+
+        let dialect = self.selected_dialect();
+        let names = param_prompt::placeholders(&query, &dialect);
+        if !names.is_empty() {
+            self.param_prompt.show(query, names, dialect);
+            return Ok(());
+        }
+        self.spawn_query(query)
+    }
+
+    /// Run a query on a worker thread; `poll_running_query` picks up the result.
+    fn spawn_query(&mut self, query: String) -> Result<()> {
+        if self.running_query.is_some() {
+            return Err(anyhow::anyhow!(
+                "A query is already running (Ctrl+C to cancel)"
+            ));
+        }
+
+        if let Some(conn_id) = self.database_browser.selected_connection {
+            if let Some(conn) = self.connections.remove(&conn_id) {
+                let dialect = self.selected_dialect();
+                let job = RunningQuery::spawn(conn_id, conn, query, dialect);
+                self.results_viewer.set_running(job.started);
+                self.running_query = Some(job);
+                self.active_pane = Pane::Results;
+                self.update_focus();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Hand the connection back and show the result once the background query finishes.
+    fn poll_running_query(&mut self) {
+        let finished = match self.running_query.as_ref().and_then(|job| job.poll()) {
+            Some(finished) => finished,
+            None => return,
+        };
+        let job = match self.running_query.take() {
+            Some(job) => job,
+            None => return,
+        };
+        self.results_viewer.clear_running();
+
+        self.record_history(&job, &finished);
+
+        match finished {
+            Ok((conn, statements)) => {
+                // The connection may have been removed from the browser while it was away
+                if job.connection_id < self.database_browser.connections.len() {
+                    self.connections.insert(job.connection_id, conn);
+                }
+                let multiple = statements.len() > 1;
+                let failure = statements
+                    .iter()
+                    .enumerate()
+                    .find_map(|(idx, s)| s.result.as_ref().err().map(|e| (idx, e.clone())));
+
+                // Several statements get a result tab each
+                if multiple {
+                    self.results_viewer.set_statement_results(statements);
+                } else if let Some(Ok(result)) = statements.into_iter().next().map(|s| s.result) {
+                    self.results_viewer.set_query_result(result);
+                }
+
+                match failure {
+                    Some(_) if job.cancelled => self.messages.info(format!(
+                        "Query cancelled after {:.1}s",
+                        job.elapsed().as_secs_f64()
+                    )),
+                    Some((idx, error)) if multiple => self.report_error(
+                        anyhow::anyhow!(error).context(format!("Statement {} failed", idx + 1)),
+                    ),
+                    Some((_, error)) => {
+                        self.report_error(anyhow::anyhow!(error).context("Query failed"))
+                    }
+                    None => {}
+                }
+            }
+            Err(err) => self.report_error(err),
+        }
+    }
+
+    /// `:export <format> <path>`: write the current result to a file. Table browses are read
+    /// again in full, so every row is exported rather than the page on screen.
+    fn export_results(&mut self, args: &[&str]) -> Result<()> {
+        let (format, path) = match args {
+            [format, path @ ..] if !path.is_empty() => {
+                (ExportFormat::from_name(format)?, path.join(" "))
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Usage: :export <csv|tsv|json|ndjson|markdown|sql> <path>"
+                ))
+            }
+        };
+        let result = match &self.results_viewer.result {
+            Some(result) if !result.columns.is_empty() => result,
+            _ => return Err(anyhow::anyhow!("No result to export")),
+        };
+        let dialect = self.selected_dialect();
+        let path = export::expand_path(&path);
+        let table_name = self.results_viewer.table_name.clone();
+
+        let mut writer = ExportWriter::create(
+            &path,
+            format,
+            table_name.as_deref().unwrap_or("query_result"),
+            dialect,
+        )?;
+        match table_name {
+            Some(table_name) => {
+                self.ensure_connection_idle()?;
+                let conn = self
+                    .database_browser
+                    .selected_connection
+                    .and_then(|id| self.connections.get_mut(&id))
+                    .ok_or_else(|| anyhow::anyhow!("No connection selected"))?;
+                conn.stream_table(&table_name, &mut writer)
+                    .with_context(|| format!("Failed to export {}", table_name))?;
+            }
+            None => {
+                writer.columns(&result.columns, &result.column_types)?;
+                for row in &result.rows {
+                    writer.row(row)?;
+                }
+            }
+        }
+        let rows = writer.finish()?;
+
+        self.messages
+            .info(format!("Exported {} rows to {}", rows, path.display()));
+        Ok(())
+    }
+
+    /// `:dump [--schema-only|--data-only] [table] <path>`: write a SQL script with the CREATE
+    /// TABLE, INSERT and CREATE INDEX statements of one table, or of every table in the open
+    /// database or schema.
+    fn dump_database(&mut self, args: &[&str]) -> Result<()> {
+        let mut options = DumpOptions {
+            schema: true,
+            data: true,
+        };
+        let mut names = Vec::new();
+        for arg in args {
+            match *arg {
+                "--schema-only" | "-s" => options.data = false,
+                "--data-only" | "-d" => options.schema = false,
+                other => names.push(other),
+            }
+        }
+        let (table, path) = match names.as_slice() {
+            [path] if options.schema || options.data => (None, export::expand_path(path)),
+            [table, path] if options.schema || options.data => {
+                (Some(table.to_string()), export::expand_path(path))
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Usage: :dump [--schema-only|--data-only] [table] <path>"
+                ))
+            }
+        };
+
+        self.ensure_connection_idle()?;
+        let (dialect, connection_name) = self
+            .database_browser
+            .get_selected_connection()
+            .map(|c| (c.db_type.clone(), c.name.clone()))
+            .ok_or_else(|| anyhow::anyhow!("No connection selected"))?;
+        let viewing_tables = self.database_browser.is_viewing_tables();
+        let conn = self
+            .database_browser
+            .selected_connection
+            .and_then(|id| self.connections.get_mut(&id))
+            .ok_or_else(|| anyhow::anyhow!("No connection selected"))?;
+        let tables: Vec<String> = match table {
+            Some(table) => vec![table],
+            None if viewing_tables => conn
+                .list_tables()
+                .context("Failed to list tables")?
+                .into_iter()
+                .map(|t| t.name)
+                .collect(),
+            None => {
+                return Err(anyhow::anyhow!(
+                    "Open a database or schema to dump, or name a table"
+                ))
+            }
+        };
+
+        let file = std::fs::File::create(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut out = std::io::BufWriter::new(file);
+        writeln!(
+            out,
+            "-- {} dump of {} made by tui-db on {}",
+            dialect.config_name(),
+            connection_name,
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        )?;
+        write!(out, "{}", dump::preamble(&dialect))?;
+
+        let mut rows = 0;
+        // Views have no rows of their own and go after every table they could select from
+        let mut views = Vec::new();
+        for table_name in &tables {
+            if let Some(view) = Self::view_statement(conn, table_name)? {
+                views.push(view);
+                continue;
+            }
+            writeln!(out)?;
+            if options.schema {
+                let ddl = Self::create_table_statement(conn, table_name)?.ok_or_else(|| {
+                    anyhow::anyhow!("Failed to read the schema of {}", table_name)
+                })?;
+                let ddl = ddl.trim_end();
+                writeln!(out, "{}{}", ddl, if ddl.ends_with(';') { "" } else { ";" })?;
+            }
+            if options.data {
+                let mut writer = InsertWriter::new(&mut out, table_name, dialect.clone());
+                conn.stream_table(table_name, &mut writer)
+                    .with_context(|| format!("Failed to dump {}", table_name))?;
+                rows += writer.finish()?;
+            }
+            // Indexes go last so they're built once rather than updated on every INSERT
+            if options.schema {
+                for index in Self::index_statements(conn, table_name)? {
+                    writeln!(out, "{}", index)?;
+                }
+            }
+        }
+        if options.schema {
+            for view in &views {
+                writeln!(out)?;
+                writeln!(out, "{}", view)?;
+            }
+        }
+        writeln!(out)?;
+        write!(out, "{}", dump::postamble(&dialect))?;
+        out.flush()?;
+
+        let mut message = format!("Dumped {} tables", tables.len() - views.len());
+        if !views.is_empty() && options.schema {
+            message.push_str(&format!(" and {} views", views.len()));
+        }
+        self.messages
+            .info(format!("{} ({} rows) to {}", message, rows, path.display()));
+        Ok(())
+    }
+
+    /// `:source <path>`: split a SQL script into statements and open the popup that runs them.
+    fn open_source(&mut self, args: &[&str]) -> Result<()> {
+        if args.is_empty() {
+            return Err(anyhow::anyhow!("Usage: :source <path>"));
+        }
+        let path = export::expand_path(&args.join(" "));
+        self.ensure_connection_idle()?;
+        let (connection_id, dialect) = self
+            .database_browser
+            .get_selected_connection()
+            .map(|c| (c.id, c.db_type.clone()))
+            .ok_or_else(|| anyhow::anyhow!("No connection selected"))?;
+
+        let script = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let statements = split_statements(&script, &dialect);
+        if statements.is_empty() {
+            return Err(anyhow::anyhow!("{} has no statements", path.display()));
+        }
+        self.source_dialog.show(path, connection_id, statements);
+        Ok(())
+    }
+
+    /// Run the script's statements on a worker thread; `poll_running_script` records each
+    /// outcome as it comes in.
+    fn start_script(&mut self) -> Result<()> {
+        self.ensure_connection_idle()?;
+        let connection_id = self.source_dialog.connection_id;
+        let conn = self
+            .connections
+            .remove(&connection_id)
+            .ok_or_else(|| anyhow::anyhow!("The script's connection is no longer open"))?;
+        let statements = self
+            .source_dialog
+            .statements
+            .iter()
+            .map(|statement| statement.text.clone())
+            .collect();
+        self.running_script = Some(RunningScript::spawn(
+            connection_id,
+            conn,
+            statements,
+            self.source_dialog.stop_on_error,
+        ));
+        self.source_dialog.start();
+        Ok(())
+    }
+
+    /// Esc or Ctrl+C while a script runs: abort the current statement and skip the rest.
+    fn cancel_script(&mut self) -> Result<()> {
+        if let Some(job) = self.running_script.as_mut() {
+            job.cancel().context("Failed to cancel script")?;
+            self.messages.info("Cancelling script...".to_string());
+        }
+        Ok(())
+    }
+
+    /// Record the outcome of each statement the script worker finished, and take the
+    /// connection back once it is done.
+    fn poll_running_script(&mut self) {
+        while let Some(event) = self.running_script.as_ref().and_then(|job| job.poll()) {
+            match event {
+                Ok(ScriptEvent::Statement {
+                    summary,
+                    duration_ms,
+                }) => {
+                    if self.source_dialog.state == SourceState::Running {
+                        self.source_dialog.record(StatementOutcome {
+                            summary,
+                            duration_ms,
+                        });
+                    }
+                }
+                Ok(ScriptEvent::Finished(conn)) => {
+                    if let Some(job) = self.running_script.take() {
+                        // The connection may have been removed from the browser while it was away
+                        if job.connection_id < self.database_browser.connections.len() {
+                            self.connections.insert(job.connection_id, conn);
+                        }
+                        self.source_dialog.finish();
+                        self.source_finished(job.cancelled);
+                    }
+                }
+                Err(err) => {
+                    self.running_script = None;
+                    self.source_dialog.finish();
+                    self.report_error(err);
+                }
+            }
+        }
+    }
+
+    /// Report how the script went and pick up any tables it created or dropped.
+    fn source_finished(&mut self, cancelled: bool) {
+        let dialog = &self.source_dialog;
+        let file_name = dialog
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let message = format!(
+            "{} {} of {} statements from {}, {} failed",
+            if cancelled { "Cancelled after" } else { "Ran" },
+            dialog.outcomes.len(),
+            dialog.statements.len(),
+            file_name,
+            dialog.failures()
+        );
+        let connection_id = dialog.connection_id;
+        self.messages.info(message);
+
+        if !self.database_browser.is_viewing_tables() {
+            return;
+        }
+        if let Some(conn) = self.connections.get_mut(&connection_id) {
+            match conn.list_tables() {
+                Ok(tables) => self.database_browser.set_tables(tables),
+                Err(err) => self.report_error(err.context("Failed to list tables")),
+            }
+        }
+    }
+
+    /// `:import <path> [table]`: read a CSV, TSV or JSON file and open the column mapping
+    /// popup. The rows go into `table` when it exists, otherwise into the open table, or into
+    /// a new table named `table` (or after the file).
+    fn open_import(&mut self, args: &[&str]) -> Result<()> {
+        let path = match args.first() {
+            Some(path) => export::expand_path(path),
+            None => return Err(anyhow::anyhow!("Usage: :import <path> [table]")),
+        };
+        self.ensure_connection_idle()?;
+        let dialect = self
+            .database_browser
+            .get_selected_connection()
+            .map(|c| c.db_type.clone())
+            .ok_or_else(|| anyhow::anyhow!("No connection selected"))?;
+        let file = ImportFile::read(&path)?;
+        if file.rows.is_empty() && file.bad_rows.is_empty() {
+            return Err(anyhow::anyhow!("{} has no rows to import", path.display()));
+        }
+
+        let new_table_name = match args.get(1) {
+            Some(name) => name.to_string(),
+            None => path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+        let candidates: Vec<String> = args
+            .get(1)
+            .map(|name| name.to_string())
+            .into_iter()
+            .chain(self.results_viewer.table_name.clone())
+            .chain(
+                self.database_browser
+                    .get_selected_table()
+                    .filter(|_| self.database_browser.is_viewing_tables())
+                    .map(|t| t.name.clone()),
+            )
+            .collect();
+
+        let conn = self
+            .database_browser
+            .selected_connection
+            .and_then(|id| self.connections.get_mut(&id))
+            .ok_or_else(|| anyhow::anyhow!("No connection selected"))?;
+        let mut table = None;
+        for name in candidates {
+            let columns = conn.get_table_columns(&name)?;
+            if columns.is_empty() {
+                // A table given on the command line that doesn't exist yet is created
+                if args.get(1) == Some(&name.as_str()) {
+                    break;
+                }
+                continue;
+            }
+            // PostgreSQL parameters are cast to the driver's type names
+            let types = conn.get_table_data(&name, 0, 0)?;
+            let columns = columns
+                .into_iter()
+                .map(|column| {
+                    let cast_type = types
+                        .columns
+                        .iter()
+                        .position(|c| *c == column.name)
+                        .and_then(|idx| types.column_types.get(idx).cloned())
+                        .unwrap_or_default();
+                    TargetColumn {
+                        name: column.name,
+                        data_type: column.data_type,
+                        cast_type,
+                    }
+                })
+                .collect();
+            table = Some((name, columns));
+            break;
+        }
+
+        self.import_dialog
+            .show(file, table, new_table_name, dialect);
+        Ok(())
+    }
+
+    /// Open a transaction, create the table if asked to, and hand the INSERT batches to a
+    /// worker thread. MySQL commits DDL implicitly, so there the table is created before the
+    /// transaction and dropped again if the import doesn't complete.
+    fn start_import(&mut self) -> Result<()> {
+        let table_name = match self.import_dialog.target_table() {
+            Some(name) if !name.is_empty() => name,
+            _ => return Err(anyhow::anyhow!("No table to import into")),
+        };
+        let targets = self.import_dialog.targets();
+        if targets.is_empty() {
+            return Err(anyhow::anyhow!("No columns selected for import"));
+        }
+        let file = match &self.import_dialog.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+        self.ensure_connection_idle()?;
+        let connection_id = self
+            .database_browser
+            .selected_connection
+            .ok_or_else(|| anyhow::anyhow!("No connection selected"))?;
+        let conn = self
+            .connections
+            .get_mut(&connection_id)
+            .ok_or_else(|| anyhow::anyhow!("No connection selected"))?;
+
+        let dialect = &self.import_dialog.dialect;
+        let (batches, bad_values) = import::build_batches(file, &table_name, &targets, dialect);
+        let mut bad_rows = file.bad_rows.clone();
+        bad_rows.extend(bad_values);
+
+        let create_sql = self.import_dialog.create_table.then(|| {
+            let columns: Vec<(String, ValueKind)> =
+                targets.iter().map(|t| (t.name.clone(), t.kind)).collect();
+            import::create_table_sql(&table_name, &columns, dialect)
+        });
+        let transactional_ddl = !matches!(dialect, DatabaseType::MySQL | DatabaseType::MariaDB);
+
+        let mut drop_table = None;
+        if let Some(sql) = create_sql.as_ref().filter(|_| !transactional_ddl) {
+            conn.execute_query(sql)
+                .with_context(|| format!("Failed to create table {}", table_name))?;
+            drop_table = Some(format!(
+                "DROP TABLE {}",
+                dialect.quote_identifier(&table_name)
+            ));
+        }
+        if let Err(err) = conn.execute_query("BEGIN") {
+            if let Some(sql) = &drop_table {
+                let _ = conn.execute_query(sql);
+            }
+            return Err(err);
+        }
+        if let Some(sql) = create_sql.as_ref().filter(|_| transactional_ddl) {
+            if let Err(err) = conn.execute_query(sql) {
+                let _ = conn.execute_query("ROLLBACK");
+                return Err(err.context(format!("Failed to create table {}", table_name)));
+            }
+        }
+
+        let job = ImportJob {
+            table_name,
+            batches,
+            bad_rows,
+            drop_table,
+        };
+        self.import_dialog.progress = Some((0, job.total_rows()));
+        // The connection goes to the worker and comes back with the outcome
+        let conn = self
+            .connections
+            .remove(&connection_id)
+            .ok_or_else(|| anyhow::anyhow!("No connection selected"))?;
+        self.import_job = Some(RunningImport::spawn(connection_id, conn, job));
+        Ok(())
+    }
+
+    /// Esc or Ctrl+C while an import runs: stop it and roll back everything inserted so far.
+    fn cancel_import(&mut self) -> Result<()> {
+        if let Some(job) = self.import_job.as_mut() {
+            job.cancel().context("Failed to cancel import")?;
+            self.messages.info("Cancelling import...".to_string());
+        }
+        Ok(())
+    }
+
+    /// Move the progress bar as batches finish, and take the connection back once the
+    /// import is committed or rolled back.
+    fn poll_running_import(&mut self) {
+        while let Some(event) = self.import_job.as_ref().and_then(|job| job.poll()) {
+            match event {
+                Ok(ImportEvent::Progress(imported)) => {
+                    if let Some(job) = &self.import_job {
+                        self.import_dialog.progress = Some((imported, job.total_rows));
+                    }
+                }
+                Ok(ImportEvent::Finished(conn, outcome)) => {
+                    if let Some(job) = self.import_job.take() {
+                        // The connection may have been removed from the browser while it was away
+                        if job.connection_id < self.database_browser.connections.len() {
+                            self.connections.insert(job.connection_id, conn);
+                        }
+                        self.import_dialog.hide();
+                        self.import_finished(job, outcome);
+                    }
+                }
+                Err(err) => {
+                    self.import_job = None;
+                    self.import_dialog.hide();
+                    self.report_error(err);
+                }
+            }
+        }
+    }
+
+    /// Report how the import went; after a commit, pick up the new rows and table.
+    fn import_finished(&mut self, job: RunningImport, outcome: ImportOutcome) {
+        match outcome {
+            ImportOutcome::Committed => {}
+            ImportOutcome::Cancelled => {
+                self.messages.info(format!(
+                    "Import into {} cancelled, nothing was imported",
+                    job.table_name
+                ));
+                return;
+            }
+            ImportOutcome::Failed(problem) => {
+                self.messages.error(problem);
+                return;
+            }
+        }
+
+        if let Some(conn) = self.connections.get_mut(&job.connection_id) {
+            match conn.list_tables() {
+                Ok(tables) => self.database_browser.set_tables(tables),
+                Err(err) => self.report_error(err.context("Failed to list tables")),
+            }
+        }
+
+        let mut message = format!("Imported {} rows into {}", job.total_rows, job.table_name);
+        if !job.bad_rows.is_empty() {
+            message.push_str(&format!(", skipped {}:", job.bad_rows.len()));
+            for problem in job.bad_rows.iter().take(50) {
+                message.push_str(&format!("\n{}", problem));
+            }
+            if job.bad_rows.len() > 50 {
+                message.push_str(&format!("\n... and {} more", job.bad_rows.len() - 50));
+            }
+        }
+        self.messages.info(message);
+        if !job.bad_rows.is_empty() {
+            self.messages.show();
+        }
+
+        if self.results_viewer.table_name.as_deref() == Some(job.table_name.as_str()) {
+            if let Err(err) = self.refresh_current_table() {
+                self.report_error(err);
+            }
+        }
+    }
+
+    /// `:save-query [-g] <name>`: store the editor contents. Queries are scoped to the selected
+    /// connection when it is saved in the config, `-g` (or no saved connection) makes them global.
+    fn save_query(&mut self, args: &[&str]) -> Result<()> {
+        let (global, name) = match args {
+            ["-g", rest @ ..] => (true, rest.join(" ")),
+            rest => (false, rest.join(" ")),
+        };
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Usage: :save-query [-g] <name>"));
+        }
+        let query = self.query_editor.get_query();
+        if query.trim().is_empty() {
+            return Err(anyhow::anyhow!("The editor is empty, nothing to save"));
+        }
+
+        let connection = self
+            .database_browser
+            .get_selected_connection()
+            .map(|c| (c.name.clone(), c.connection_string.clone()));
+        let saved = SavedQuery {
+            name: name.clone(),
+            query,
+        };
+        let scope = match connection {
+            Some((conn_name, connection_string))
+                if !global
+                    && self
+                        .config
+                        .save_query(Some(&connection_string), saved.clone()) =>
+            {
+                conn_name
+            }
+            _ => {
+                self.config.save_query(None, saved);
+                "all connections".to_string()
+            }
+        };
+        self.config.save()?;
+        self.messages
+            .info(format!("Saved query {} for {}", name, scope));
+        Ok(())
+    }
+
+    /// Save each statement of a finished query to the persistent history, with its own
+    /// time, row count and outcome; failing to write it is only logged.
+    fn record_history(&mut self, job: &RunningQuery, finished: &Result<FinishedQuery>) {
+        let connection = self
+            .database_browser
+            .connections
+            .iter()
+            .find(|c| c.id == job.connection_id)
+            .map(|c| c.name.clone())
+            .unwrap_or_default();
+        let timestamp = chrono::Local::now();
+
+        let outcomes: Vec<(String, u64, Result<usize, String>)> = match finished {
+            Ok((_, statements)) => statements
+                .iter()
+                .map(|statement| {
+                    let result = match &statement.result {
+                        Ok(result) => Ok(result.rows_affected.unwrap_or(result.rows.len())),
+                        Err(_) if job.cancelled => Err("cancelled".to_string()),
+                        Err(err) => Err(err.clone()),
+                    };
+                    (statement.sql.clone(), statement.duration_ms, result)
+                })
+                .collect(),
+            // The worker was lost, so there is nothing per statement
+            Err(err) => vec![(
+                job.query.clone(),
+                job.elapsed().as_millis() as u64,
+                Err(format!("{:#}", err)),
+            )],
+        };
+
+        for (query, duration_ms, result) in outcomes {
+            let entry = HistoryEntry {
+                query,
+                connection: connection.clone(),
+                timestamp,
+                duration_ms,
+                rows: result.as_ref().ok().copied(),
+                success: result.is_ok(),
+                error: result.err(),
+            };
+            if let Err(err) = self.history.record(entry) {
+                self.report_error(err);
+                return;
+            }
+        }
+    }
+
+    /// Open the completion popup for the word before the cursor. `explicit` is set for Ctrl+N
+    /// and Tab, which report when nothing matches; narrowing the list while typing just closes it.
+    fn open_completion(&mut self, explicit: bool) -> Result<()> {
+        let (prefix, qualifier) = self.query_editor.word_before_cursor();
+        if !explicit && prefix.is_empty() {
+            return Ok(());
+        }
+        let dialect = self.selected_dialect();
+        let statement = self
+            .query_editor
+            .statement_at_cursor(&dialect)
+            .map(|s| s.text)
+            .unwrap_or_default();
+        let mut tables = completion::mentioned_tables(&statement, &dialect);
+        if let Some(qualifier) = &qualifier {
+            if !tables.iter().any(|t| {
+                t.name.eq_ignore_ascii_case(qualifier)
+                    || t.alias
+                        .as_deref()
+                        .is_some_and(|a| a.eq_ignore_ascii_case(qualifier))
+            }) {
+                tables.push(completion::TableRef {
+                    name: qualifier.clone(),
+                    alias: None,
+                });
+            }
+        }
+
+        let Some(conn_id) = self.database_browser.selected_connection else {
+            if explicit {
+                self.messages.info("No connection selected".to_string());
+            }
+            return Ok(());
+        };
+        // Complete from whatever could be read; the rest is tried again next time
+        if let Err(err) = self.load_schema_cache(conn_id, &tables) {
+            self.messages.error(format!("{:#}", err));
+        }
+
+        let Some(cache) = self.schema_cache.get(&conn_id) else {
+            return Ok(());
+        };
+        let items = completion::candidates(cache, &tables, qualifier.as_deref(), &prefix, &dialect);
+        if items.is_empty() {
+            if explicit {
+                self.messages
+                    .info(format!("No completions for '{}'", prefix));
+            }
+            return Ok(());
+        }
+        self.completion.show(items, &prefix);
+        Ok(())
+    }
+
+    /// Read the table and database names and the columns of `tables` into the connection's
+    /// cache, unless already there. Lookups that fail are left out of the cache and reported.
+    /// A connection busy with a query keeps what is cached.
+    fn load_schema_cache(&mut self, conn_id: usize, tables: &[completion::TableRef]) -> Result<()> {
+        let database = self
+            .database_browser
+            .get_current_database()
+            .map(String::from);
+        if self
+            .schema_cache
+            .get(&conn_id)
+            .is_some_and(|cache| cache.database != database)
+        {
+            self.schema_cache.remove(&conn_id);
+        }
+        let dialect = self.selected_dialect();
+        let cache = self.schema_cache.entry(conn_id).or_default();
+        let Some(conn) = self.connections.get_mut(&conn_id) else {
+            return Ok(());
+        };
+
+        if cache.tables.is_empty() && cache.databases.is_empty() {
+            cache.database = database.clone();
+            let names = conn.table_names().context("Failed to list tables")?;
+            // Server connections list databases or schemas until one is opened
+            match (&dialect, &database) {
+                (DatabaseType::SQLite, _) | (_, Some(_)) => cache.tables = names,
+                (_, None) => cache.databases = names,
+            }
+            if database.is_some() {
+                if let Some(mysql_conn) = conn.as_any_mut().downcast_mut::<MySQLConnection>() {
+                    cache.databases = mysql_conn
+                        .list_databases()
+                        .context("Failed to list databases")?;
+                }
+            }
+        }
+
+        let mut failed = Vec::new();
+        for table in tables {
+            let key = table.name.to_lowercase();
+            if cache.columns.contains_key(&key) {
+                continue;
+            }
+            // Tables that don't exist (yet) simply have no columns
+            match conn.get_table_columns(&table.name) {
+                Ok(columns) => {
+                    cache.columns.insert(key, columns);
+                }
+                Err(err) => failed.push(format!("{}: {:#}", table.name, err)),
+            }
+        }
+        if !failed.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Failed to read columns of {}",
+                failed.join("; ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// Bring buffer `idx` into the editor and select the connection and database it was
+    /// last used with. The buffer being left keeps the browser's current selection.
+    fn switch_buffer(&mut self, idx: usize) -> Result<()> {
+        let Some(target) = self.buffers.buffers.get(idx) else {
+            return Err(anyhow::anyhow!("No buffer {}", idx + 1));
+        };
+        if idx == self.buffers.current {
+            return Ok(());
+        }
+
+        let (connection, database) = (target.connection, target.database.clone());
+        let reselect = connection.is_some()
+            && (connection != self.database_browser.selected_connection
+                || database != self.database_browser.current_database);
+        if reselect {
+            if let Some(job) = &self.running_query {
+                if Some(job.connection_id) == connection {
+                    return Err(anyhow::anyhow!(
+                        "A query is still running on that buffer's connection (Ctrl+C to cancel)"
+                    ));
+                }
+            }
+        }
+
+        let buffer = self.buffers.current_mut();
+        buffer.connection = self.database_browser.selected_connection;
+        buffer.database = self.database_browser.current_database.clone();
+        self.buffers.switch(idx, &mut self.query_editor);
+        self.completion.hide();
+        self.update_focus();
+
+        match connection {
+            Some(conn_id) if reselect => self.select_connection(conn_id, database),
+            _ => Ok(()),
+        }
+    }
+
+    fn close_buffer(&mut self, force: bool) -> Result<()> {
+        let count = self.buffers.buffers.len();
+        if count == 1 {
+            return Err(anyhow::anyhow!("Can't close the last buffer"));
+        }
+        if self.query_editor.is_modified() && !force {
+            return Err(anyhow::anyhow!(
+                "No write since last change (add ! to override)"
+            ));
+        }
+        let closed = self.buffers.current;
+        let next = if closed + 1 < count {
+            closed + 1
+        } else {
+            closed - 1
+        };
+        self.switch_buffer(next)?;
+        self.buffers.remove(closed);
+        Ok(())
+    }
+
+    /// Names of the buffers whose file has changes that weren't written.
+    fn modified_buffers(&self) -> Vec<String> {
+        self.buffers
+            .buffers
+            .iter()
+            .enumerate()
+            .filter(|(idx, buffer)| {
+                if *idx == self.buffers.current {
+                    self.query_editor.is_modified()
+                } else {
+                    buffer.editor.is_modified()
                 }
+            })
+            .map(|(_, buffer)| buffer.name.clone())
+            .collect()
+    }
+
+    /// Quit, or ask first when a file has unsaved changes.
+    fn request_quit(&mut self) {
+        let modified = self.modified_buffers();
+        if modified.is_empty() {
+            self.should_quit = true;
+        } else {
+            self.quit_prompt = Some(format!(
+                "Unsaved changes in {}. Quit anyway? (y/n)",
+                modified.join(", ")
+            ));
+        }
+    }
+
+    /// `:e path` loads a file into the current buffer, `:e` alone reads its file again. A
+    /// path that doesn't exist yet starts an empty file.
+    fn edit_file(&mut self, args: &[&str], force: bool) -> Result<()> {
+        if self.query_editor.is_modified() && !force {
+            return Err(anyhow::anyhow!(
+                "No write since last change (add ! to override)"
+            ));
+        }
+        let path = if args.is_empty() {
+            self.query_editor
+                .file
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("No file name"))?
+        } else {
+            export::expand_path(&args.join(" "))
+        };
+
+        let (text, message) = match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                let message = format!("\"{}\" {} lines", path.display(), text.lines().count());
+                (text, message)
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                (String::new(), format!("\"{}\" [New]", path.display()))
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read {}", path.display()))
             }
         };
+        // The previous text stays reachable with undo
+        self.query_editor.set_content(&text);
+        self.query_editor.goto_top();
+        self.set_buffer_file(path);
+        self.messages.info(message);
+        Ok(())
+    }
 
-        let conn_info = ConnectionInfo {
-            id,
-            name: name.clone(),
-            db_type: db_type.clone(),
-            connection_string: connection_string.to_string(),
+    /// `:w` writes the editor to its file and `:w path` to another one; `:saveas path` also
+    /// makes the path the buffer's file. A buffer without a file takes the first one it is
+    /// written to.
+    fn write_file(&mut self, args: &[&str], save_as: bool) -> Result<()> {
+        let path = match (args.is_empty(), save_as) {
+            (false, _) => export::expand_path(&args.join(" ")),
+            (true, false) => self
+                .query_editor
+                .file
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("No file name (use :w <path>)"))?,
+            (true, true) => return Err(anyhow::anyhow!("Usage: :saveas <path>")),
         };
 
-        self.database_browser.add_connection(conn_info);
-        self.database_browser.selected_connection = Some(id);
+        let mut text = self.query_editor.get_query();
+        text.push('\n');
+        std::fs::write(&path, &text)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        self.messages.info(format!(
+            "\"{}\" {} lines written",
+            path.display(),
+            self.query_editor.content.len()
+        ));
+
+        let current_file = self.query_editor.file.as_deref();
+        if save_as || current_file.is_none_or(|file| file == path) {
+            self.set_buffer_file(path);
+        }
+        Ok(())
+    }
 
-        // Load tables
-        let tables = conn.list_tables()?;
-        self.database_browser.set_tables(tables);
+    /// `:r path` puts a file's lines below the cursor line.
+    fn read_file(&mut self, args: &[&str]) -> Result<()> {
+        if args.is_empty() {
+            return Err(anyhow::anyhow!("Usage: :r <path>"));
+        }
+        let path = export::expand_path(&args.join(" "));
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if text.is_empty() {
+            return Ok(());
+        }
 
-        self.connections.insert(id, conn);
+        self.query_editor.begin_change();
+        self.query_editor.paste(
+            &YankedText {
+                text,
+                linewise: true,
+            },
+            false,
+            1,
+        );
+        self.query_editor.commit_change();
+        Ok(())
+    }
 
-        // Save to config only if requested
-        if save_to_config {
-            let db_type_str = match db_type {
-                DatabaseType::SQLite => "sqlite".to_string(),
-                DatabaseType::MySQL => "mysql".to_string(),
-                DatabaseType::MariaDB => "mariadb".to_string(),
-            };
-            self.config
-                .add_connection(name, connection_string.to_string(), db_type_str);
-            self.config.save()?;
-        }
+    fn set_buffer_file(&mut self, path: PathBuf) {
+        // The tab shows the file name rather than the whole path
+        self.buffers.current_mut().name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        self.query_editor.mark_saved(path);
+    }
 
+    /// `:editor` edits the query, or the selected grid cell from the results pane, in
+    /// `$VISUAL`/`$EDITOR`.
+    fn open_external_editor(&mut self) -> Result<()> {
+        let (target, text) = if self.active_pane == Pane::Results {
+            if !self.can_mark_rows() {
+                return Err(anyhow::anyhow!(
+                    "Select a cell on the Data tab of a table to edit it"
+                ));
+            }
+            let row = self
+                .results_viewer
+                .table_state
+                .selected()
+                .ok_or_else(|| anyhow::anyhow!("No cell selected"))?;
+            let value = self.results_viewer.current_edit_text().unwrap_or_default();
+            let column = self.results_viewer.selected_column;
+            (EditTarget::Cell { row, column }, value)
+        } else {
+            let mut text = self.query_editor.get_query();
+            text.push('\n');
+            (EditTarget::Query, text)
+        };
+        self.external_edit = Some(ExternalEdit { target, text });
         Ok(())
     }
 
-    fn go_back_to_database_list(&mut self) -> Result<()> {
-        // Reset the database browser to show databases instead of tables
-        self.database_browser.go_back_to_databases();
+    /// Load what the external editor left in the file back into the query or cell.
+    pub fn finish_external_edit(&mut self, target: EditTarget, result: Result<String>) {
+        let text = match result {
+            Ok(text) => text,
+            Err(err) => return self.report_error(err),
+        };
+        match target {
+            EditTarget::Query => {
+                if text
+                    .lines()
+                    .ne(self.query_editor.content.iter().map(String::as_str))
+                {
+                    self.query_editor.set_content(&text);
+                }
+            }
+            EditTarget::Cell { row, column } => {
+                // Editors end the file with a line break that isn't part of the value
+                let value = text.strip_suffix('\n').unwrap_or(&text);
+                let value = value.strip_suffix('\r').unwrap_or(value);
+                if let Err(err) = self.results_viewer.set_cell_text(row, column, value) {
+                    self.report_error(err);
+                }
+            }
+        }
+    }
 
-        // For MySQL/MariaDB connections, clear database context and reload database list
-        if let Some(conn_id) = self.database_browser.selected_connection {
-            if let Some(conn) = self.connections.get_mut(&conn_id) {
-                let conn_info = self
-                    .database_browser
-                    .connections
-                    .iter()
-                    .find(|c| c.id == conn_id);
+    /// Select a connection in the browser and put it on a MySQL database or PostgreSQL
+    /// schema, or on none, as choosing them in the browser would.
+    fn select_connection(&mut self, conn_id: usize, database: Option<String>) -> Result<()> {
+        // The connection may have been removed since
+        let Some(info) = self.database_browser.connections.get(conn_id) else {
+            return Ok(());
+        };
+        let db_type = info.db_type.clone();
 
-                if let Some(info) = conn_info {
-                    if matches!(
-                        info.db_type,
-                        crate::db::DatabaseType::MySQL | crate::db::DatabaseType::MariaDB
-                    ) {
-                        // For MySQL, clear the database context first, then get database list
-                        if let Some(mysql_conn) = conn
-                            .as_any_mut()
-                            .downcast_mut::<crate::db::mysql::MySQLConnection>()
-                        {
-                            mysql_conn.clear_database_context()?;
-                        }
-                        let tables = conn.list_tables()?;
-                        self.database_browser.set_tables(tables);
+        self.database_browser.selected_connection = Some(conn_id);
+        self.database_browser.selected_table = None;
+        self.database_browser.list_state.select(Some(conn_id));
+        self.database_browser.set_current_database(database.clone());
+
+        let Some(conn) = self.connections.get_mut(&conn_id) else {
+            self.database_browser.set_tables(Vec::new());
+            return Ok(());
+        };
+        match db_type {
+            DatabaseType::MySQL | DatabaseType::MariaDB => {
+                if let Some(mysql_conn) = conn.as_any_mut().downcast_mut::<MySQLConnection>() {
+                    match &database {
+                        Some(name) => mysql_conn
+                            .use_database(name)
+                            .with_context(|| format!("Failed to switch to database {}", name))?,
+                        None => mysql_conn.clear_database_context()?,
                     }
                 }
             }
+            DatabaseType::PostgreSQL => {
+                if let Some(pg_conn) = conn.as_any_mut().downcast_mut::<PostgresConnection>() {
+                    match &database {
+                        Some(name) => pg_conn
+                            .use_schema(name)
+                            .with_context(|| format!("Failed to switch to schema {}", name))?,
+                        None => pg_conn.clear_schema_context()?,
+                    }
+                }
+            }
+            DatabaseType::SQLite => {}
         }
+        let tables = conn.list_tables().context("Failed to list tables")?;
+        self.database_browser.set_tables(tables);
         Ok(())
     }
 
-    fn execute_query(&mut self) -> Result<()> {
-        let query = self.query_editor.get_query();
-        if query.trim().is_empty() {
-            return Ok(());
-        }
+    /// SQL dialect of the selected connection, SQLite when there is none.
+    pub fn selected_dialect(&self) -> DatabaseType {
+        self.database_browser
+            .get_selected_connection()
+            .map(|c| c.db_type.clone())
+            .unwrap_or(DatabaseType::SQLite)
+    }
 
-        // Get active connection
-        if let Some(conn_id) = self.database_browser.selected_connection {
-            if let Some(conn) = self.connections.get_mut(&conn_id) {
-                let result = conn.execute_query(&query)?;
-                self.results_viewer.set_result(result);
-                self.active_pane = Pane::Results;
-                self.update_focus();
+    /// Whether a background query, an import or a `:source` script is using the connection.
+    fn connection_in_use(&self, connection_id: usize) -> bool {
+        self.running_query
+            .as_ref()
+            .is_some_and(|job| job.connection_id == connection_id)
+            || self
+                .import_job
+                .as_ref()
+                .is_some_and(|job| job.connection_id == connection_id)
+            || (self.source_dialog.visible && self.source_dialog.connection_id == connection_id)
+    }
+
+    /// Actions that need the selected connection must wait for a background query to finish.
+    fn ensure_connection_idle(&self) -> Result<()> {
+        if let Some(job) = &self.running_query {
+            if Some(job.connection_id) == self.database_browser.selected_connection {
+                return Err(anyhow::anyhow!(
+                    "A query is still running on this connection (Ctrl+C to cancel)"
+                ));
+            }
+        }
+        if let Some(job) = &self.import_job {
+            if Some(job.connection_id) == self.database_browser.selected_connection {
+                return Err(anyhow::anyhow!(
+                    "An import is still running on this connection (Ctrl+C to cancel)"
+                ));
             }
         }
-
         Ok(())
     }
 
     fn execute_query_at_cursor(&mut self) -> Result<()> {
-        let query = self.query_editor.get_query_at_cursor();
+        let query = self
+            .query_editor
+            .get_query_at_cursor(&self.selected_dialect());
         if query.trim().is_empty() {
             return Ok(());
         }
 
-        // Get active connection
-        if let Some(conn_id) = self.database_browser.selected_connection {
-            if let Some(conn) = self.connections.get_mut(&conn_id) {
-                let result = conn.execute_query(&query)?;
-                self.results_viewer.set_result(result);
-                self.active_pane = Pane::Results;
-                self.update_focus();
-            }
-        }
-
-        Ok(())
+        self.start_query(query)
     }
 
     fn load_selected_table_data(&mut self) -> Result<()> {
@@ -898,6 +2587,8 @@ impl App {
             None => return Ok(()), // No item selected
         };
 
+        self.ensure_connection_idle()?;
+
         // Get active connection
         if let Some(conn_id) = self.database_browser.selected_connection {
             if let Some(conn) = self.connections.get_mut(&conn_id) {
@@ -927,12 +2618,14 @@ impl App {
                                 conn.as_any_mut()
                                     .downcast_mut::<crate::db::mysql::MySQLConnection>()
                             {
-                                mysql_conn.use_database(&selected_name)?;
+                                mysql_conn.use_database(&selected_name).with_context(|| {
+                                    format!("Failed to switch to database {}", selected_name)
+                                })?;
                                 // Store the current database in the browser
                                 self.database_browser
                                     .set_current_database(Some(selected_name.clone()));
                                 // Reload tables for the selected database
-                                let tables = conn.list_tables()?;
+                                let tables = conn.list_tables().context("Failed to list tables")?;
                                 self.database_browser.set_tables(tables);
                                 return Ok(());
                             }
@@ -945,18 +2638,51 @@ impl App {
                                 // Ensure we're using the correct database
                                 if let Some(db_name) = self.database_browser.get_current_database()
                                 {
-                                    mysql_conn.use_database(db_name)?;
+                                    mysql_conn.use_database(db_name).with_context(|| {
+                                        format!("Failed to switch to database {}", db_name)
+                                    })?;
                                 }
                             }
                         }
+                    } else if info.db_type == DatabaseType::PostgreSQL {
+                        // PostgreSQL lists schemas first, the same way MySQL lists databases
+                        let is_showing_schemas = self
+                            .database_browser
+                            .tables
+                            .iter()
+                            .all(|t| t.row_count.is_none());
+
+                        if let Some(pg_conn) =
+                            conn.as_any_mut().downcast_mut::<PostgresConnection>()
+                        {
+                            if is_showing_schemas {
+                                pg_conn.use_schema(&selected_name).with_context(|| {
+                                    format!("Failed to switch to schema {}", selected_name)
+                                })?;
+                                self.database_browser
+                                    .set_current_database(Some(selected_name.clone()));
+                                let tables = conn.list_tables().context("Failed to list tables")?;
+                                self.database_browser.set_tables(tables);
+                                return Ok(());
+                            } else if let Some(schema) =
+                                self.database_browser.get_current_database()
+                            {
+                                pg_conn.use_schema(schema).with_context(|| {
+                                    format!("Failed to switch to schema {}", schema)
+                                })?;
+                            }
+                        }
                     }
                 }
 
                 // This is a table selection, load table data
-                let result = conn.get_table_data(&selected_name, 1000, 0)?;
+                let result =
+                    conn.get_table_data(&selected_name, self.results_viewer.page_size, 0)?;
 
                 self.results_viewer.set_result(result);
                 self.results_viewer.set_table_name(selected_name.clone());
+                self.results_viewer
+                    .set_page(0, self.database_browser.table_row_count(&selected_name));
 
                 // Load schema information (DDL)
                 let schema = Self::get_table_schema(conn, &selected_name)?;
@@ -978,69 +2704,129 @@ impl App {
         Ok(())
     }
 
-    fn save_table_edits(&mut self) -> Result<()> {
-        if !self.results_viewer.has_modifications() {
-            return Ok(());
-        }
-
-        // Generate UPDATE queries
-        let queries = self.results_viewer.generate_update_queries();
+    /// Rows can be marked for deletion on the Data tab of a table, outside edit/insert mode.
+    fn can_mark_rows(&self) -> bool {
+        self.results_viewer.active_tab == crate::ui::results_viewer::TabMode::Data
+            && self.results_viewer.table_name.is_some()
+            && !self.results_viewer.edit_mode
+            && !self.results_viewer.insert_mode
+    }
 
-        if queries.is_empty() {
+    /// Collect pending grid edits as SQL and open the review popup; nothing runs until the
+    /// user confirms.
+    fn review_table_edits(&mut self) -> Result<()> {
+        if self.results_viewer.insert_mode {
+            // Keep the field being typed, as Esc would
+            self.results_viewer.save_insert_field()?;
+        }
+        if !self.results_viewer.has_modifications()
+            && !self.results_viewer.has_insert_data()
+            && !self.results_viewer.has_deletions()
+        {
             return Ok(());
         }
 
-        // Execute each UPDATE query
-        if let Some(conn_id) = self.database_browser.selected_connection {
-            if let Some(conn) = self.connections.get_mut(&conn_id) {
-                for query in &queries {
-                    conn.execute_query(query)?;
-                }
+        self.ensure_connection_idle()?;
 
-                // Clear modifications after successful save
-                self.results_viewer.clear_modifications();
+        let conn_id = match self.database_browser.selected_connection {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+        let db_type = match self
+            .database_browser
+            .connections
+            .iter()
+            .find(|c| c.id == conn_id)
+        {
+            Some(info) => info.db_type.clone(),
+            None => return Ok(()),
+        };
+        let table_name = match self.results_viewer.table_name.clone() {
+            Some(name) => name,
+            None => return Ok(()),
+        };
 
-                // Reload the table data to show updated values
-                if let Some(table_name) = &self.results_viewer.table_name.clone() {
-                    let result = conn.get_table_data(table_name, 1000, 0)?;
-                    self.results_viewer.set_result(result);
-                    self.results_viewer.set_table_name(table_name.clone());
-                }
+        let mut statements = Vec::new();
+        if let Some(conn) = self.connections.get_mut(&conn_id) {
+            if self.results_viewer.has_modifications() || self.results_viewer.has_deletions() {
+                // Rows are matched on the primary key, never on every column value
+                let key_columns: Vec<String> = conn
+                    .get_table_columns(&table_name)
+                    .with_context(|| format!("Failed to read the primary key of {}", table_name))?
+                    .into_iter()
+                    .filter(|c| c.primary_key)
+                    .map(|c| c.name)
+                    .collect();
+                statements.extend(
+                    self.results_viewer
+                        .generate_update_statements(&db_type, &key_columns)?,
+                );
+                statements.extend(
+                    self.results_viewer
+                        .generate_delete_statements(&db_type, &key_columns)?,
+                );
             }
+            statements.extend(self.results_viewer.generate_insert_statement(&db_type));
         }
 
+        if statements.is_empty() {
+            self.messages
+                .info("No changes to save, edited values match the originals".to_string());
+            self.results_viewer.clear_modifications();
+            return Ok(());
+        }
+
+        self.change_review.show(table_name, statements);
         Ok(())
     }
 
-    fn save_insert_row(&mut self) -> Result<()> {
-        if !self.results_viewer.has_insert_data() {
+    /// Run the reviewed statements in a single transaction and reload the table.
+    fn apply_reviewed_edits(&mut self) -> Result<()> {
+        let statements = self.change_review.confirm();
+        if statements.is_empty() {
             return Ok(());
         }
 
-        // Save the current field before generating query
-        self.results_viewer.save_insert_field();
-
-        // Generate INSERT query
-        let query = match self.results_viewer.generate_insert_query() {
-            Some(q) => q,
-            None => return Ok(()),
-        };
+        self.ensure_connection_idle()?;
 
-        // Execute INSERT query
         if let Some(conn_id) = self.database_browser.selected_connection {
             if let Some(conn) = self.connections.get_mut(&conn_id) {
-                conn.execute_query(&query)?;
+                // On failure everything is rolled back and the edits stay pending
+                let counts = conn.execute_transaction(&statements)?;
 
-                // Clear insert data after successful insert
+                let report: Vec<String> = statements
+                    .iter()
+                    .zip(&counts)
+                    .map(|(statement, affected)| {
+                        format!("{} -- {} row(s)", statement.sql, affected)
+                    })
+                    .collect();
+                self.messages.info(format!(
+                    "Committed {} statement(s), {} row(s) changed\n{}",
+                    statements.len(),
+                    counts.iter().sum::<usize>(),
+                    report.join("\n")
+                ));
+
+                // Clear pending changes after the commit
+                self.results_viewer.clear_modifications();
                 self.results_viewer.clear_insert_data();
-                self.results_viewer.exit_insert_mode();
+                self.results_viewer.clear_deletions();
+                if self.results_viewer.insert_mode {
+                    self.results_viewer.exit_insert_mode();
+                }
+                if self.results_viewer.edit_mode {
+                    self.results_viewer.exit_edit_mode();
+                }
                 self.vim_state.enter_normal_mode();
 
-                // Reload the table data to show the new row
-                if let Some(table_name) = &self.results_viewer.table_name.clone() {
-                    let result = conn.get_table_data(table_name, 1000, 0)?;
+                // Reload the table data to show the committed values
+                if let Some(table_name) = self.results_viewer.table_name.clone() {
+                    let offset = self.results_viewer.page_offset;
+                    let result =
+                        conn.get_table_data(&table_name, self.results_viewer.page_size, offset)?;
                     self.results_viewer.set_result(result);
-                    self.results_viewer.set_table_name(table_name.clone());
+                    self.results_viewer.set_table_name(table_name);
                 }
             }
         }
@@ -1053,18 +2839,15 @@ impl App {
 
         for conn_config in connections {
             // Determine database type
-            let db_type = match conn_config.db_type.as_str() {
-                "mysql" => DatabaseType::MySQL,
-                "mariadb" => DatabaseType::MariaDB,
-                _ => DatabaseType::SQLite, // Default to SQLite
-            };
+            let db_type = DatabaseType::from_config(&conn_config.db_type);
 
-            // Try to open each saved connection
-            if let Ok(()) = self.open_database(&conn_config.connection_string, db_type) {
-                // Connection opened successfully
-            } else {
-                // If connection fails, we can just skip it
-                // The user can manually remove it later with X
+            // Try to open each saved connection; failures are logged and skipped,
+            // the user can manually remove the entry later with X
+            if let Err(err) = self.open_database(&conn_config.connection_string, db_type) {
+                self.messages.error(format!(
+                    "Failed to open saved connection {}: {:#}",
+                    conn_config.name, err
+                ));
             }
         }
 
@@ -1086,11 +2869,7 @@ impl App {
                         if let Some(selected) = self.connection_manager.list_state.selected() {
                             let connections = self.config.get_connections();
                             if let Some(conn) = connections.get(selected) {
-                                let db_type = match conn.db_type.as_str() {
-                                    "mysql" => DatabaseType::MySQL,
-                                    "mariadb" => DatabaseType::MariaDB,
-                                    _ => DatabaseType::SQLite,
-                                };
+                                let db_type = DatabaseType::from_config(&conn.db_type);
 
                                 // Use detailed form if we have individual components stored
                                 if conn.username.is_some()
@@ -1148,11 +2927,7 @@ impl App {
                             let test_data = {
                                 let connections = self.config.get_connections();
                                 connections.get(selected).map(|conn| {
-                                    let db_type = match conn.db_type.as_str() {
-                                        "mysql" => DatabaseType::MySQL,
-                                        "mariadb" => DatabaseType::MariaDB,
-                                        _ => DatabaseType::SQLite,
-                                    };
+                                    let db_type = DatabaseType::from_config(&conn.db_type);
                                     (conn.connection_string.clone(), db_type)
                                 })
                             };
@@ -1167,16 +2942,14 @@ impl App {
                             let connect_data = {
                                 let connections = self.config.get_connections();
                                 connections.get(selected).map(|conn| {
-                                    let db_type = match conn.db_type.as_str() {
-                                        "mysql" => DatabaseType::MySQL,
-                                        "mariadb" => DatabaseType::MariaDB,
-                                        _ => DatabaseType::SQLite,
-                                    };
+                                    let db_type = DatabaseType::from_config(&conn.db_type);
 
-                                    // For MySQL/MariaDB, rebuild connection string if we have individual components
+                                    // For server databases, rebuild connection string if we have individual components
                                     let connection_string = if matches!(
                                         db_type,
-                                        DatabaseType::MySQL | DatabaseType::MariaDB
+                                        DatabaseType::MySQL
+                                            | DatabaseType::MariaDB
+                                            | DatabaseType::PostgreSQL
                                     ) && (conn.username.is_some()
                                         || conn.host.is_some())
                                     {
@@ -1236,7 +3009,7 @@ impl App {
                     return Ok(());
                 }
             }
-            DatabaseType::MySQL | DatabaseType::MariaDB => {
+            DatabaseType::MySQL | DatabaseType::MariaDB | DatabaseType::PostgreSQL => {
                 if self.connection_manager.form.username.is_empty() {
                     self.connection_manager.test_result =
                         Some("✗ Error: Username is required".to_string());
@@ -1255,20 +3028,21 @@ impl App {
             }
         }
 
-        let db_type_str = match self.connection_manager.form.db_type {
-            DatabaseType::SQLite => "sqlite".to_string(),
-            DatabaseType::MySQL => "mysql".to_string(),
-            DatabaseType::MariaDB => "mariadb".to_string(),
-        };
+        let db_type_str = self
+            .connection_manager
+            .form
+            .db_type
+            .config_name()
+            .to_string();
 
         // Get the final connection string
         let connection_string = self.connection_manager.get_connection_string();
 
-        // Prepare optional fields for MySQL/MariaDB
+        // Prepare optional fields for MySQL/MariaDB/PostgreSQL
         let (username, password, host, port, database) = match self.connection_manager.form.db_type
         {
             DatabaseType::SQLite => (None, None, None, None, None),
-            DatabaseType::MySQL | DatabaseType::MariaDB => (
+            DatabaseType::MySQL | DatabaseType::MariaDB | DatabaseType::PostgreSQL => (
                 if self.connection_manager.form.username.is_empty() {
                     None
                 } else {
@@ -1315,17 +3089,19 @@ impl App {
                 self.connection_manager.test_result = None;
             }
             ConnectionManagerMode::Edit(index) => {
-                // Remove old and add updated
-                let old_conn_name = {
+                // Remove old and add updated, keeping the connection's saved queries
+                let old_conn = {
                     let connections = self.config.get_connections();
-                    connections.get(*index).map(|c| c.name.clone())
+                    connections
+                        .get(*index)
+                        .map(|c| (c.name.clone(), c.saved_queries.clone()))
                 };
-                if let Some(name) = old_conn_name {
-                    self.config.remove_connection(&name);
+                if let Some((name, _)) = &old_conn {
+                    self.config.remove_connection(name);
                 }
                 self.config.add_connection_detailed(
                     self.connection_manager.form.name.clone(),
-                    connection_string,
+                    connection_string.clone(),
                     db_type_str,
                     username,
                     password,
@@ -1333,6 +3109,11 @@ impl App {
                     port,
                     database,
                 );
+                if let Some((_, saved_queries)) = old_conn {
+                    if let Some(conn) = self.config.connection_by_string_mut(&connection_string) {
+                        conn.saved_queries = saved_queries;
+                    }
+                }
                 self.config.save()?;
                 self.connection_manager.mode = ConnectionManagerMode::List;
                 self.connection_manager.test_result = None;
@@ -1350,10 +3131,11 @@ impl App {
     ) -> String {
         match db_type {
             DatabaseType::SQLite => conn.connection_string.clone(),
-            DatabaseType::MySQL | DatabaseType::MariaDB => {
+            DatabaseType::MySQL | DatabaseType::MariaDB | DatabaseType::PostgreSQL => {
                 let protocol = match db_type {
                     DatabaseType::MySQL => "mysql",
                     DatabaseType::MariaDB => "mysql", // MariaDB uses mysql protocol
+                    DatabaseType::PostgreSQL => "postgres",
                     _ => unreachable!(),
                 };
 
@@ -1374,8 +3156,9 @@ impl App {
                 url.push_str(host);
 
                 // Add port if not default
-                let port = conn.port.as_deref().unwrap_or("3306");
-                if port != "3306" {
+                let default_port = db_type.default_port();
+                let port = conn.port.as_deref().unwrap_or(default_port);
+                if port != default_port {
                     url.push(':');
                     url.push_str(port);
                 }
@@ -1394,15 +3177,7 @@ impl App {
     }
 
     fn test_connection(&mut self, connection_string: &str, db_type: DatabaseType) -> Result<()> {
-        let result: Result<Box<dyn DatabaseConnection>> = match db_type {
-            DatabaseType::SQLite => SQLiteConnection::connect(connection_string)
-                .map(|c| c as Box<dyn DatabaseConnection>),
-            DatabaseType::MySQL | DatabaseType::MariaDB => {
-                MySQLConnection::connect(connection_string)
-            }
-        };
-
-        match result {
+        match Self::connect_database(connection_string, &db_type) {
             Ok(_) => {
                 self.connection_manager.test_result =
                     Some("✓ Success: Connection successful!".to_string());
@@ -1425,15 +3200,31 @@ impl App {
         let conn_id = conn_info.id;
         let conn_name = conn_info.name.clone();
 
+        // A background query holds the connection outside `connections`, so removing it there
+        // wouldn't stop it; imports and scripts would lose it halfway through
+        if self
+            .database_browser
+            .selected_connection
+            .is_some_and(|idx| self.connection_in_use(idx))
+        {
+            return Err(anyhow::anyhow!(
+                "{} is busy, wait for it to finish or cancel it first",
+                conn_name
+            ));
+        }
+
         // Remove from connections HashMap
         self.connections.remove(&conn_id);
 
         // Remove from database browser
         self.database_browser.remove_connection(conn_id);
+        self.shift_connection_ids(conn_id);
 
         // Remove from config and save
         self.config.remove_connection(&conn_name);
         self.config.save()?;
+        self.messages
+            .info(format!("Removed connection {}", conn_name));
 
         // Clear results viewer if it was using this connection
         self.results_viewer.clear();
@@ -1442,6 +3233,47 @@ impl App {
         Ok(())
     }
 
+    /// Connections are keyed by their position in the browser list. After the one at `removed`
+    /// is deleted, everything that refers to a later connection moves up one, and buffers
+    /// bound to the deleted one lose their connection.
+    fn shift_connection_ids(&mut self, removed: usize) {
+        fn shift_keys<T>(map: &mut HashMap<usize, T>, removed: usize) {
+            *map = map
+                .drain()
+                .filter(|(id, _)| *id != removed)
+                .map(|(id, value)| (if id > removed { id - 1 } else { id }, value))
+                .collect();
+        }
+        let shift = |id: &mut usize| {
+            if *id > removed {
+                *id -= 1;
+            }
+        };
+
+        shift_keys(&mut self.connections, removed);
+        shift_keys(&mut self.schema_cache, removed);
+        self.next_connection_id = self.database_browser.connections.len();
+        for buffer in &mut self.buffers.buffers {
+            if buffer.connection == Some(removed) {
+                buffer.connection = None;
+                buffer.database = None;
+            }
+            if let Some(id) = buffer.connection.as_mut() {
+                shift(id);
+            }
+        }
+        if let Some(job) = self.running_query.as_mut() {
+            shift(&mut job.connection_id);
+        }
+        if let Some(job) = self.import_job.as_mut() {
+            shift(&mut job.connection_id);
+        }
+        if let Some(job) = self.running_script.as_mut() {
+            shift(&mut job.connection_id);
+        }
+        shift(&mut self.source_dialog.connection_id);
+    }
+
     fn next_pane(&mut self) {
         self.active_pane = match self.active_pane {
             Pane::DatabaseBrowser => Pane::QueryEditor,
@@ -1464,6 +3296,8 @@ impl App {
         self.database_browser.focused = self.active_pane == Pane::DatabaseBrowser;
         self.query_editor.focused = self.active_pane == Pane::QueryEditor;
         self.results_viewer.focused = self.active_pane == Pane::Results;
+        self.vim_state
+            .set_editor_focused(self.active_pane == Pane::QueryEditor);
     }
 
     fn get_column_info(
@@ -1473,6 +3307,22 @@ impl App {
         use crate::ui::results_viewer::ColumnInfo;
         let mut columns = Vec::new();
 
+        // PostgreSQL has neither PRAGMA nor DESCRIBE, read the catalog instead
+        if let Some(pg_conn) = conn.as_any_mut().downcast_mut::<PostgresConnection>() {
+            for row in pg_conn.describe_table(table_name)? {
+                if row.len() >= 5 {
+                    columns.push(ColumnInfo {
+                        name: row[0].clone(),
+                        data_type: row[1].clone(),
+                        nullable: row[2].clone(),
+                        default_value: row[3].clone(),
+                        extra: row[4].clone(),
+                    });
+                }
+            }
+            return Ok(columns);
+        }
+
         // Try SQLite PRAGMA first
         let pragma_query = format!("PRAGMA table_info({})", table_name);
         match conn.execute_query(&pragma_query) {
@@ -1480,11 +3330,11 @@ impl App {
                 // SQLite PRAGMA table_info returns: cid, name, type, notnull, dflt_value, pk
                 for row in &result.rows {
                     if row.len() >= 6 {
-                        let name = row[1].clone();
-                        let data_type = row[2].clone();
-                        let not_null = &row[3];
-                        let default_value = row[4].clone();
-                        let is_pk = &row[5];
+                        let name = row[1].to_string();
+                        let data_type = row[2].to_string();
+                        let not_null = row[3].to_string();
+                        let default_value = row[4].to_string();
+                        let is_pk = row[5].to_string();
 
                         let nullable = if not_null == "0" { "YES" } else { "NO" };
                         let extra = if is_pk != "0" { "PRIMARY KEY" } else { "" };
@@ -1507,12 +3357,12 @@ impl App {
                     // DESCRIBE returns: Field, Type, Null, Key, Default, Extra
                     for row in &result.rows {
                         if row.len() >= 6 {
-                            let name = row[0].clone();
-                            let data_type = row[1].clone();
-                            let nullable = row[2].clone();
-                            let key = &row[3];
-                            let default_value = row[4].clone();
-                            let extra_val = row[5].clone();
+                            let name = row[0].to_string();
+                            let data_type = row[1].to_string();
+                            let nullable = row[2].to_string();
+                            let key = row[3].to_string();
+                            let default_value = row[4].to_string();
+                            let extra_val = row[5].to_string();
 
                             let mut extra = extra_val.clone();
                             if key == "PRI" {
@@ -1544,6 +3394,22 @@ impl App {
         conn: &mut Box<dyn DatabaseConnection>,
         table_name: &str,
     ) -> Result<String> {
+        Ok(
+            Self::create_table_statement(conn, table_name)?.unwrap_or_else(|| {
+                format!("Table: {}\n\nSchema information not available", table_name)
+            }),
+        )
+    }
+
+    /// CREATE TABLE for an existing table, or None when the backend can't describe it.
+    fn create_table_statement(
+        conn: &mut Box<dyn DatabaseConnection>,
+        table_name: &str,
+    ) -> Result<Option<String>> {
+        if let Some(pg_conn) = conn.as_any_mut().downcast_mut::<PostgresConnection>() {
+            return Ok(pg_conn.show_create_table(table_name).ok());
+        }
+
         // Try to get CREATE TABLE statement
         let query = format!(
             "SELECT sql FROM sqlite_master WHERE type='table' AND name='{}'",
@@ -1553,17 +3419,17 @@ impl App {
         match conn.execute_query(&query) {
             Ok(result) => {
                 if !result.rows.is_empty() && !result.rows[0].is_empty() {
-                    Ok(result.rows[0][0].clone())
+                    Ok(Some(result.rows[0][0].to_string()))
                 } else {
                     // Fallback: describe table structure manually
                     let pragma_query = format!("PRAGMA table_info({})", table_name);
                     match conn.execute_query(&pragma_query) {
-                        Ok(info_result) => {
+                        Ok(info_result) if !info_result.rows.is_empty() => {
                             let mut schema = format!("CREATE TABLE {} (\n", table_name);
                             for (i, row) in info_result.rows.iter().enumerate() {
                                 if row.len() >= 3 {
-                                    let col_name = &row[1];
-                                    let col_type = &row[2];
+                                    let col_name = row[1].to_string();
+                                    let col_type = row[2].to_string();
                                     schema.push_str(&format!("  {} {}", col_name, col_type));
                                     if i < info_result.rows.len() - 1 {
                                         schema.push_str(",\n");
@@ -1573,58 +3439,124 @@ impl App {
                                 }
                             }
                             schema.push_str(");");
-                            Ok(schema)
+                            Ok(Some(schema))
                         }
-                        Err(_) => Ok(format!(
-                            "Table: {}\n\nSchema information not available",
-                            table_name
-                        )),
+                        _ => Ok(None),
                     }
                 }
             }
             Err(_) => {
                 // For MySQL/MariaDB
-                let show_create = format!("SHOW CREATE TABLE {}", table_name);
+                let show_create = format!(
+                    "SHOW CREATE TABLE {}",
+                    DatabaseType::MySQL.quote_identifier(table_name)
+                );
                 match conn.execute_query(&show_create) {
-                    Ok(result) => {
-                        if !result.rows.is_empty() && result.rows[0].len() >= 2 {
-                            Ok(result.rows[0][1].clone())
-                        } else {
-                            Ok(format!(
-                                "Table: {}\n\nSchema information not available",
-                                table_name
-                            ))
-                        }
+                    Ok(result) if !result.rows.is_empty() && result.rows[0].len() >= 2 => {
+                        Ok(Some(result.rows[0][1].to_string()))
                     }
-                    Err(_) => Ok(format!(
-                        "Table: {}\n\nSchema information not available",
-                        table_name
-                    )),
+                    _ => Ok(None),
                 }
             }
         }
     }
 
+    /// CREATE VIEW statement when `table_name` is a view rather than a table.
+    fn view_statement(
+        conn: &mut Box<dyn DatabaseConnection>,
+        table_name: &str,
+    ) -> Result<Option<String>> {
+        if let Some(pg_conn) = conn.as_any_mut().downcast_mut::<PostgresConnection>() {
+            return pg_conn.view_definition(table_name);
+        }
+        if conn.as_any_mut().is::<MySQLConnection>() {
+            let query = format!(
+                "SELECT TABLE_TYPE FROM information_schema.TABLES \
+                 WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = {}",
+                DatabaseType::MySQL.literal(&CellValue::Text(table_name.to_string()))
+            );
+            let result = conn.execute_query(&query)?;
+            let is_view = result
+                .rows
+                .first()
+                .and_then(|row| row.first())
+                .is_some_and(|kind| kind.to_string() == "VIEW");
+            if !is_view {
+                return Ok(None);
+            }
+            let show_create = format!(
+                "SHOW CREATE VIEW {}",
+                DatabaseType::MySQL.quote_identifier(table_name)
+            );
+            let result = conn.execute_query(&show_create)?;
+            return Ok(result
+                .rows
+                .first()
+                .and_then(|row| row.get(1))
+                .map(|ddl| format!("{};", ddl)));
+        }
+        // SQLite's table list has no views
+        Ok(None)
+    }
+
+    /// CREATE INDEX statements for indexes that aren't part of the CREATE TABLE statement.
+    fn index_statements(
+        conn: &mut Box<dyn DatabaseConnection>,
+        table_name: &str,
+    ) -> Result<Vec<String>> {
+        if let Some(pg_conn) = conn.as_any_mut().downcast_mut::<PostgresConnection>() {
+            return pg_conn.index_definitions(table_name);
+        }
+        if conn.as_any_mut().is::<SQLiteConnection>() {
+            // Indexes created for PRIMARY KEY and UNIQUE constraints have no SQL
+            let query = format!(
+                "SELECT sql FROM sqlite_master WHERE type='index' AND tbl_name='{}' \
+                 AND sql IS NOT NULL ORDER BY name",
+                table_name.replace('\'', "''")
+            );
+            let result = conn.execute_query(&query)?;
+            return Ok(result
+                .rows
+                .iter()
+                .filter_map(|row| row.first())
+                .map(|sql| format!("{};", sql))
+                .collect());
+        }
+        // SHOW CREATE TABLE already includes MySQL's indexes
+        Ok(Vec::new())
+    }
+
     fn get_table_indexes(
         conn: &mut Box<dyn DatabaseConnection>,
         table_name: &str,
     ) -> Result<Vec<String>> {
         let mut indexes = Vec::new();
 
+        if let Some(pg_conn) = conn.as_any_mut().downcast_mut::<PostgresConnection>() {
+            for (index_name, columns) in pg_conn.table_indexes(table_name)? {
+                indexes.push(format!(
+                    "Index: {}\nColumns: {}",
+                    index_name,
+                    columns.join(", ")
+                ));
+            }
+            return Ok(indexes);
+        }
+
         // Try SQLite first
         let query = format!("PRAGMA index_list({})", table_name);
         match conn.execute_query(&query) {
             Ok(result) => {
                 for row in &result.rows {
                     if !row.is_empty() {
-                        let index_name = &row[1];
+                        let index_name = row[1].to_string();
                         // Get index details
                         let detail_query = format!("PRAGMA index_info({})", index_name);
                         if let Ok(detail_result) = conn.execute_query(&detail_query) {
                             let mut columns = Vec::new();
                             for detail_row in &detail_result.rows {
                                 if detail_row.len() >= 3 {
-                                    columns.push(detail_row[2].clone());
+                                    columns.push(detail_row[2].to_string());
                                 }
                             }
                             indexes.push(format!(
@@ -1645,8 +3577,8 @@ impl App {
                         std::collections::HashMap::new();
                     for row in &result.rows {
                         if row.len() >= 5 {
-                            let index_name = &row[2];
-                            let column_name = &row[4];
+                            let index_name = row[2].to_string();
+                            let column_name = row[4].to_string();
                             index_map
                                 .entry(index_name.clone())
                                 .or_default()
@@ -1679,6 +3611,8 @@ impl App {
             return Ok(());
         }
 
+        self.ensure_connection_idle()?;
+
         // Get active connection
         if let Some(conn_id) = self.database_browser.selected_connection {
             if let Some(conn) = self.connections.get_mut(&conn_id) {
@@ -1707,12 +3641,32 @@ impl App {
                         }
 
                         // Generate ALTER TABLE statement
-                        let alter_query = format!(
-                            "ALTER TABLE {} CHANGE COLUMN {} {} {}",
-                            table_name, column_info.name, new_name, new_type
-                        );
-
-                        conn.execute_query(&alter_query)?;
+                        let alter_query = if conn.as_any_mut().is::<PostgresConnection>() {
+                            // PostgreSQL has no CHANGE COLUMN, change the type then rename
+                            let mut statements = Vec::new();
+                            if new_type != column_info.data_type {
+                                statements.push(format!(
+                                    "ALTER TABLE {} ALTER COLUMN {} TYPE {}",
+                                    table_name, column_info.name, new_type
+                                ));
+                            }
+                            if new_name != column_info.name {
+                                statements.push(format!(
+                                    "ALTER TABLE {} RENAME COLUMN {} TO {}",
+                                    table_name, column_info.name, new_name
+                                ));
+                            }
+                            statements.join("; ")
+                        } else {
+                            format!(
+                                "ALTER TABLE {} CHANGE COLUMN {} {} {}",
+                                table_name, column_info.name, new_name, new_type
+                            )
+                        };
+
+                        if !alter_query.is_empty() {
+                            conn.execute_query(&alter_query)?;
+                        }
                     }
                 }
 
@@ -1758,6 +3712,8 @@ impl App {
             return Ok(()); // Need at least name and type
         }
 
+        self.ensure_connection_idle()?;
+
         // Get active connection
         if let Some(conn_id) = self.database_browser.selected_connection {
             if let Some(conn) = self.connections.get_mut(&conn_id) {
@@ -1797,6 +3753,85 @@ impl App {
         Ok(())
     }
 
+    /// Whether moving past the first/last row of the Data tab should turn the page.
+    fn at_page_edge(&self, forward: bool) -> bool {
+        let viewer = &self.results_viewer;
+        if viewer.edit_mode || viewer.insert_mode {
+            return false;
+        }
+        let rows = viewer.result.as_ref().map_or(0, |r| r.rows.len());
+        let selected = viewer.table_state.selected().unwrap_or(0);
+        if forward {
+            viewer.has_next_page() && selected + 1 >= rows
+        } else {
+            viewer.has_prev_page() && selected == 0
+        }
+    }
+
+    fn load_next_page(&mut self) -> Result<()> {
+        if !self.results_viewer.has_next_page() {
+            return Ok(());
+        }
+        let offset = self.results_viewer.page_offset + self.results_viewer.page_size;
+        if self.load_table_page(offset)? {
+            self.results_viewer.goto_top();
+        }
+        Ok(())
+    }
+
+    fn load_prev_page(&mut self) -> Result<()> {
+        if !self.results_viewer.has_prev_page() {
+            return Ok(());
+        }
+        let offset = self
+            .results_viewer
+            .page_offset
+            .saturating_sub(self.results_viewer.page_size);
+        if self.load_table_page(offset)? {
+            self.results_viewer.goto_bottom();
+        }
+        Ok(())
+    }
+
+    /// Replace the Data tab with the page starting at `offset`. Returns false when nothing
+    /// was loaded because there are pending edits or no rows past the end.
+    fn load_table_page(&mut self, offset: usize) -> Result<bool> {
+        let table_name = match self.results_viewer.table_name.clone() {
+            Some(name) => name,
+            None => return Ok(false),
+        };
+        if self.results_viewer.has_any_changes() {
+            // Pending edits refer to rows of the current page
+            self.messages.info(
+                "Save (Ctrl+S) or discard (Ctrl+D) pending changes before changing page"
+                    .to_string(),
+            );
+            return Ok(false);
+        }
+
+        self.ensure_connection_idle()?;
+
+        let conn = match self
+            .database_browser
+            .selected_connection
+            .and_then(|id| self.connections.get_mut(&id))
+        {
+            Some(conn) => conn,
+            None => return Ok(false),
+        };
+        let result = conn.get_table_data(&table_name, self.results_viewer.page_size, offset)?;
+        if result.rows.is_empty() && offset > 0 {
+            self.messages
+                .info(format!("No more rows in {}", table_name));
+            return Ok(false);
+        }
+
+        let total_rows = self.results_viewer.total_rows;
+        self.results_viewer.set_result(result);
+        self.results_viewer.set_page(offset, total_rows);
+        Ok(true)
+    }
+
     fn refresh_current_table(&mut self) -> Result<()> {
         // Get the current table name
         let table_name = match &self.results_viewer.table_name {
@@ -1804,11 +3839,15 @@ impl App {
             None => return Ok(()), // No table loaded
         };
 
+        self.ensure_connection_idle()?;
+
         // Get active connection
         if let Some(conn_id) = self.database_browser.selected_connection {
             if let Some(conn) = self.connections.get_mut(&conn_id) {
-                // Reload table data
-                let result = conn.get_table_data(&table_name, 1000, 0)?;
+                // Reload the current page of table data
+                let offset = self.results_viewer.page_offset;
+                let result =
+                    conn.get_table_data(&table_name, self.results_viewer.page_size, offset)?;
                 self.results_viewer.set_result(result);
 
                 // Reload schema information
@@ -0,0 +1,243 @@
+use crate::db::connection::ColumnInfo;
+use crate::db::DatabaseType;
+use crate::sql::keywords::{is_keyword, KEYWORDS};
+use crate::sql::lexer::{tokenize, Token, TokenKind};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Column,
+    Table,
+    Database,
+    Keyword,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionItem {
+    pub text: String, // What gets inserted
+    pub kind: CompletionKind,
+    pub detail: String, // Column type and table, shown dimmed
+}
+
+/// Schema metadata of one connection, read once and kept until refreshed with `R`.
+#[derive(Debug, Default)]
+pub struct SchemaCache {
+    pub database: Option<String>, // MySQL database or PostgreSQL schema the tables belong to
+    pub tables: Vec<String>,
+    pub databases: Vec<String>,
+    pub columns: HashMap<String, Vec<ColumnInfo>>, // Lowercase table name -> columns
+}
+
+impl SchemaCache {
+    pub fn columns_of(&self, table: &str) -> Option<&Vec<ColumnInfo>> {
+        self.columns.get(&table.to_lowercase())
+    }
+}
+
+/// A table referenced in a statement, with the alias it was given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRef {
+    pub name: String,
+    pub alias: Option<String>,
+}
+
+/// Tables named after FROM, JOIN, UPDATE, INTO and TABLE, with their aliases
+/// (`FROM users u, orders AS o`). Schema qualifiers are dropped.
+pub fn mentioned_tables(statement: &str, dialect: &DatabaseType) -> Vec<TableRef> {
+    let tokens: Vec<Token> = tokenize(statement, dialect)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+    let text = |idx: usize| tokens.get(idx).map_or("", |t| t.text(statement));
+
+    let mut tables = Vec::new();
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let keyword = text(idx).to_uppercase();
+        idx += 1;
+        if !matches!(
+            keyword.as_str(),
+            "FROM" | "JOIN" | "UPDATE" | "INTO" | "TABLE"
+        ) {
+            continue;
+        }
+
+        while let Some(mut name) = identifier(&tokens, idx, statement) {
+            idx += 1;
+            // schema.table
+            while text(idx) == "." {
+                match identifier(&tokens, idx + 1, statement) {
+                    Some(part) => {
+                        name = part;
+                        idx += 2;
+                    }
+                    None => break,
+                }
+            }
+
+            if text(idx).eq_ignore_ascii_case("AS") {
+                idx += 1;
+            }
+            let alias = identifier(&tokens, idx, statement);
+            if alias.is_some() {
+                idx += 1;
+            }
+            tables.push(TableRef { name, alias });
+
+            // FROM a, b lists several tables
+            if keyword == "FROM" && text(idx) == "," {
+                idx += 1;
+            } else {
+                break;
+            }
+        }
+    }
+    tables
+}
+
+/// The identifier at `idx`, unquoted; keywords don't count.
+fn identifier(tokens: &[Token], idx: usize, source: &str) -> Option<String> {
+    let token = tokens.get(idx)?;
+    let text = token.text(source);
+    match token.kind {
+        TokenKind::Word if !is_keyword(text) => Some(text.to_string()),
+        TokenKind::QuotedIdentifier if text.len() >= 2 => {
+            let quote = &text[..1];
+            let inner = &text[1..text.len() - 1];
+            Some(match quote {
+                "[" => inner.to_string(),
+                _ => inner.replace(&quote.repeat(2), quote),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Completions for the word being typed. With a qualifier (`u.` in `u.na`) only the columns
+/// of the table or alias it names are offered; otherwise columns of the statement's tables
+/// come first, then tables, databases and keywords. Matching is a case-insensitive prefix.
+pub fn candidates(
+    cache: &SchemaCache,
+    tables: &[TableRef],
+    qualifier: Option<&str>,
+    prefix: &str,
+    dialect: &DatabaseType,
+) -> Vec<CompletionItem> {
+    let lower_prefix = prefix.to_lowercase();
+    let matches = |name: &str| name.to_lowercase().starts_with(&lower_prefix);
+    let mut items: Vec<CompletionItem> = Vec::new();
+
+    // Column names already offered, before quoting
+    let mut offered: HashSet<&str> = HashSet::new();
+    let mut column_items = |table: &str, items: &mut Vec<CompletionItem>| {
+        for column in cache.columns_of(table).into_iter().flatten() {
+            if matches(&column.name) && offered.insert(&column.name) {
+                items.push(CompletionItem {
+                    text: quote_if_needed(&column.name, dialect),
+                    kind: CompletionKind::Column,
+                    detail: format!("{} {}", table, column.data_type),
+                });
+            }
+        }
+    };
+
+    if let Some(qualifier) = qualifier {
+        let table = tables
+            .iter()
+            .find(|t| {
+                t.alias
+                    .as_deref()
+                    .is_some_and(|a| a.eq_ignore_ascii_case(qualifier))
+            })
+            .or_else(|| {
+                tables
+                    .iter()
+                    .find(|t| t.name.eq_ignore_ascii_case(qualifier))
+            })
+            .map_or(qualifier, |t| t.name.as_str());
+        column_items(table, &mut items);
+        return items;
+    }
+
+    for table in tables {
+        column_items(&table.name, &mut items);
+    }
+    for table in cache.tables.iter().filter(|t| matches(t)) {
+        items.push(CompletionItem {
+            text: quote_if_needed(table, dialect),
+            kind: CompletionKind::Table,
+            detail: String::new(),
+        });
+    }
+    for database in cache.databases.iter().filter(|d| matches(d)) {
+        items.push(CompletionItem {
+            text: quote_if_needed(database, dialect),
+            kind: CompletionKind::Database,
+            detail: String::new(),
+        });
+    }
+    // Keywords follow the case being typed
+    let lowercase = !prefix.is_empty() && prefix.chars().all(|c| !c.is_uppercase());
+    for keyword in KEYWORDS.iter().filter(|k| matches(k)) {
+        items.push(CompletionItem {
+            text: if lowercase {
+                keyword.to_lowercase()
+            } else {
+                keyword.to_string()
+            },
+            kind: CompletionKind::Keyword,
+            detail: String::new(),
+        });
+    }
+    items
+}
+
+/// Names that aren't plain identifiers are inserted quoted.
+fn quote_if_needed(name: &str, dialect: &DatabaseType) -> String {
+    let plain = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && !is_keyword(name);
+    if plain {
+        name.to_string()
+    } else {
+        dialect.quote_identifier(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: "text".to_string(),
+            nullable: true,
+            primary_key: false,
+        }
+    }
+
+    #[test]
+    fn columns_shared_by_tables_are_offered_once() {
+        let mut cache = SchemaCache::default();
+        for table in ["a", "b"] {
+            cache
+                .columns
+                .insert(table.to_string(), vec![column("id"), column("Order Date")]);
+        }
+        let tables: Vec<TableRef> = ["a", "b"]
+            .iter()
+            .map(|name| TableRef {
+                name: name.to_string(),
+                alias: None,
+            })
+            .collect();
+
+        let columns: Vec<String> = candidates(&cache, &tables, None, "", &DatabaseType::PostgreSQL)
+            .into_iter()
+            .filter(|item| item.kind == CompletionKind::Column)
+            .map(|item| item.text)
+            .collect();
+        assert_eq!(columns, vec!["id", "\"Order Date\""]);
+    }
+}
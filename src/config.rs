@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -21,11 +21,42 @@ pub struct ConnectionConfig {
     pub port: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub database: Option<String>,
+
+    // Saved queries only offered while this connection is selected
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub saved_queries: Vec<SavedQuery>,
+}
+
+/// A named query kept in the config; `${name}` placeholders are filled in when it runs.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedQuery {
+    pub name: String,
+    pub query: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub connections: Vec<ConnectionConfig>,
+    /// Saved queries offered for every connection
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub saved_queries: Vec<SavedQuery>,
+    /// Rows fetched per page when browsing table data
+    #[serde(default = "default_page_size")]
+    pub page_size: usize,
+}
+
+fn default_page_size() -> usize {
+    1000
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            connections: Vec::new(),
+            saved_queries: Vec::new(),
+            page_size: default_page_size(),
+        }
+    }
 }
 
 impl Config {
@@ -42,17 +73,24 @@ impl Config {
     pub fn save(&self) -> Result<()> {
         let config_path = Self::config_path()?;
         if let Some(parent) = config_path.parent() {
-            fs::create_dir_all(parent)?;
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
         }
         let contents = serde_json::to_string_pretty(self)?;
-        fs::write(config_path, contents)?;
+        fs::write(&config_path, contents)
+            .with_context(|| format!("Failed to save config to {}", config_path.display()))?;
         Ok(())
     }
 
     fn config_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("config.json"))
+    }
+
+    /// `~/.config/tui-db`, where the config and other app state files live.
+    pub fn config_dir() -> Result<PathBuf> {
         let config_dir =
             dirs::config_dir().ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
-        Ok(config_dir.join("tui-db").join("config.json"))
+        Ok(config_dir.join("tui-db"))
     }
 
     pub fn add_connection(&mut self, name: String, connection_string: String, db_type: String) {
@@ -71,6 +109,7 @@ impl Config {
                 host: None,
                 port: None,
                 database: None,
+                saved_queries: Vec::new(),
             });
         }
     }
@@ -102,6 +141,7 @@ impl Config {
                 host,
                 port,
                 database,
+                saved_queries: Vec::new(),
             });
         }
     }
@@ -115,4 +155,51 @@ impl Config {
     pub fn get_connections(&self) -> &[ConnectionConfig] {
         &self.connections
     }
+
+    pub fn connection_by_string_mut(
+        &mut self,
+        connection_string: &str,
+    ) -> Option<&mut ConnectionConfig> {
+        self.connections
+            .iter_mut()
+            .find(|c| c.connection_string == connection_string)
+    }
+
+    /// Store a query under `name`, replacing any query of the same name in that scope.
+    /// With a connection string the query is scoped to that saved connection.
+    pub fn save_query(&mut self, connection_string: Option<&str>, query: SavedQuery) -> bool {
+        let queries = match connection_string {
+            Some(connection_string) => match self.connection_by_string_mut(connection_string) {
+                Some(conn) => &mut conn.saved_queries,
+                None => return false,
+            },
+            None => &mut self.saved_queries,
+        };
+        match queries.iter_mut().find(|q| q.name == query.name) {
+            Some(existing) => *existing = query,
+            None => queries.push(query),
+        }
+        true
+    }
+
+    /// Queries of the given connection followed by the global ones, tagged with their scope.
+    pub fn saved_queries_for(&self, connection_string: Option<&str>) -> Vec<(String, SavedQuery)> {
+        let scoped = connection_string
+            .and_then(|cs| self.connections.iter().find(|c| c.connection_string == cs))
+            .map(|c| {
+                c.saved_queries
+                    .iter()
+                    .map(|q| (c.name.clone(), q.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        scoped
+            .into_iter()
+            .chain(
+                self.saved_queries
+                    .iter()
+                    .map(|q| ("global".to_string(), q.clone())),
+            )
+            .collect()
+    }
 }
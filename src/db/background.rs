@@ -0,0 +1,315 @@
+use anyhow::{anyhow, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::connection::{DatabaseConnection, DatabaseType, QueryCanceller, QueryResult};
+use crate::import::ImportJob;
+use crate::sql::split_statements;
+
+/// The connection handed back by the worker thread, with the outcome of each statement.
+pub type FinishedQuery = (Box<dyn DatabaseConnection>, Vec<StatementResult>);
+
+/// What one statement of a query returned, or why it failed.
+#[derive(Debug, Clone)]
+pub struct StatementResult {
+    pub sql: String,
+    pub result: Result<QueryResult, String>, // The error with its causes
+    pub duration_ms: u64,
+}
+
+/// A query running on a worker thread so the UI keeps drawing.
+///
+/// The connection moves to the thread for the duration of the query and comes back
+/// through the channel together with the result. A query with several statements runs them
+/// one by one, stopping at the first error, so each statement's outcome can be shown.
+pub struct RunningQuery {
+    pub connection_id: usize,
+    pub query: String,
+    pub started: Instant,
+    pub cancelled: bool,
+    canceller: Box<dyn QueryCanceller>,
+    receiver: Receiver<FinishedQuery>,
+}
+
+impl RunningQuery {
+    pub fn spawn(
+        connection_id: usize,
+        mut conn: Box<dyn DatabaseConnection>,
+        query: String,
+        dialect: DatabaseType,
+    ) -> Self {
+        let canceller = conn.query_canceller();
+        let (sender, receiver) = mpsc::channel();
+
+        let sql = query.clone();
+        thread::spawn(move || {
+            let statements = split_statements(&sql, &dialect);
+            let parts = if statements.len() > 1 {
+                statements.into_iter().map(|s| s.text).collect()
+            } else {
+                vec![sql]
+            };
+
+            let mut results = Vec::new();
+            for part in parts {
+                let started = Instant::now();
+                let result = conn
+                    .execute_query(&part)
+                    .map_err(|err| format!("{:#}", err));
+                let failed = result.is_err();
+                results.push(StatementResult {
+                    sql: part,
+                    result,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                });
+                if failed {
+                    break;
+                }
+            }
+            // The receiver is gone only if the app quit mid-query
+            let _ = sender.send((conn, results));
+        });
+
+        Self {
+            connection_id,
+            query,
+            started: Instant::now(),
+            cancelled: false,
+            canceller,
+            receiver,
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn cancel(&mut self) -> Result<()> {
+        self.cancelled = true;
+        self.canceller.cancel()
+    }
+
+    /// Returns the finished query without blocking, or `None` while it is still running.
+    pub fn poll(&self) -> Option<Result<FinishedQuery>> {
+        match self.receiver.try_recv() {
+            Ok(finished) => Some(Ok(finished)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow!(
+                "Query worker stopped unexpectedly, the connection was lost"
+            ))),
+        }
+    }
+}
+
+/// What a script worker reports: the outcome of each statement as it finishes, then the
+/// connection once the script is done or stopped.
+pub enum ScriptEvent {
+    Statement {
+        summary: Result<String, String>, // "3 rows affected" or the error
+        duration_ms: u64,
+    },
+    Finished(Box<dyn DatabaseConnection>),
+}
+
+/// A `:source` script running on a worker thread, statement by statement, so the UI keeps
+/// drawing and a slow statement can be cancelled.
+pub struct RunningScript {
+    pub connection_id: usize,
+    pub cancelled: bool,
+    stop: Arc<AtomicBool>, // Tells the worker not to start another statement
+    canceller: Box<dyn QueryCanceller>,
+    receiver: Receiver<ScriptEvent>,
+}
+
+impl RunningScript {
+    pub fn spawn(
+        connection_id: usize,
+        mut conn: Box<dyn DatabaseConnection>,
+        statements: Vec<String>,
+        stop_on_error: bool,
+    ) -> Self {
+        let canceller = conn.query_canceller();
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        let worker_stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            for statement in statements {
+                if worker_stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let started = Instant::now();
+                let summary = match conn.execute_query(&statement) {
+                    Ok(result) if !result.columns.is_empty() => {
+                        Ok(format!("{} rows", result.rows.len()))
+                    }
+                    Ok(result) => Ok(format!(
+                        "{} rows affected",
+                        result.rows_affected.unwrap_or(0)
+                    )),
+                    Err(err) => Err(format!("{:#}", err)),
+                };
+                let failed = summary.is_err();
+                let event = ScriptEvent::Statement {
+                    summary,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                };
+                if sender.send(event).is_err() || (failed && stop_on_error) {
+                    break;
+                }
+            }
+            // The receiver is gone only if the app quit mid-script
+            let _ = sender.send(ScriptEvent::Finished(conn));
+        });
+
+        Self {
+            connection_id,
+            cancelled: false,
+            stop,
+            canceller,
+            receiver,
+        }
+    }
+
+    /// Abort the statement running now and skip the rest.
+    pub fn cancel(&mut self) -> Result<()> {
+        self.cancelled = true;
+        self.stop.store(true, Ordering::SeqCst);
+        self.canceller.cancel()
+    }
+
+    /// The next event without blocking, or `None` while the current statement still runs.
+    pub fn poll(&self) -> Option<Result<ScriptEvent>> {
+        match self.receiver.try_recv() {
+            Ok(event) => Some(Ok(event)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow!(
+                "Script worker stopped unexpectedly, the connection was lost"
+            ))),
+        }
+    }
+}
+
+/// How an import worker ended.
+pub enum ImportOutcome {
+    Committed,
+    Cancelled,      // Stopped and rolled back
+    Failed(String), // The error and what was left behind
+}
+
+/// What an import worker reports: the rows inserted so far after each batch, then the
+/// connection once the import is committed or rolled back.
+pub enum ImportEvent {
+    Progress(usize),
+    Finished(Box<dyn DatabaseConnection>, ImportOutcome),
+}
+
+/// An `:import` inserting its batches on a worker thread, inside the transaction opened
+/// before it started. Cancelling aborts the running batch and rolls everything back.
+pub struct RunningImport {
+    pub connection_id: usize,
+    pub table_name: String,
+    pub total_rows: usize,
+    pub bad_rows: Vec<String>,
+    stop: Arc<AtomicBool>, // Tells the worker not to start another batch
+    canceller: Box<dyn QueryCanceller>,
+    receiver: Receiver<ImportEvent>,
+}
+
+impl RunningImport {
+    pub fn spawn(
+        connection_id: usize,
+        mut conn: Box<dyn DatabaseConnection>,
+        mut job: ImportJob,
+    ) -> Self {
+        let canceller = conn.query_canceller();
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let table_name = job.table_name.clone();
+        let total_rows = job.total_rows();
+        let bad_rows = std::mem::take(&mut job.bad_rows);
+
+        let worker_stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            let mut imported = 0;
+            let mut failure = None;
+            for batch in &job.batches {
+                if worker_stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Err(err) = conn.execute_statement(&batch.statement) {
+                    failure = Some(err.context(format!(
+                        "Import into {} failed on the rows from lines {}-{}",
+                        job.table_name, batch.first_line, batch.last_line
+                    )));
+                    break;
+                }
+                imported += batch.rows;
+                if sender.send(ImportEvent::Progress(imported)).is_err() {
+                    worker_stop.store(true, Ordering::SeqCst);
+                }
+            }
+
+            // A batch that failed because it was cancelled counts as a cancel
+            let cancelled = worker_stop.load(Ordering::SeqCst);
+            let failure = failure.or_else(|| {
+                (!cancelled)
+                    .then(|| conn.execute_query("COMMIT").err())
+                    .flatten()
+                    .map(|err| {
+                        err.context(format!("Failed to commit import into {}", job.table_name))
+                    })
+            });
+            let outcome = if cancelled {
+                match job.abort(conn.as_mut()) {
+                    Ok(()) => ImportOutcome::Cancelled,
+                    Err(err) => ImportOutcome::Failed(format!(
+                        "Import into {} cancelled, but {:#}",
+                        job.table_name, err
+                    )),
+                }
+            } else if let Some(err) = failure {
+                // Say what's left behind when the rollback fails too
+                let left = match job.abort(conn.as_mut()) {
+                    Ok(()) => "nothing was imported".to_string(),
+                    Err(abort_err) => format!("{:#}", abort_err),
+                };
+                ImportOutcome::Failed(format!("{:#}, {}", err, left))
+            } else {
+                ImportOutcome::Committed
+            };
+            // The receiver is gone only if the app quit mid-import
+            let _ = sender.send(ImportEvent::Finished(conn, outcome));
+        });
+
+        Self {
+            connection_id,
+            table_name,
+            total_rows,
+            bad_rows,
+            stop,
+            canceller,
+            receiver,
+        }
+    }
+
+    /// Abort the batch running now and roll the import back.
+    pub fn cancel(&mut self) -> Result<()> {
+        self.stop.store(true, Ordering::SeqCst);
+        self.canceller.cancel()
+    }
+
+    /// The next event without blocking, or `None` while the current batch still runs.
+    pub fn poll(&self) -> Option<Result<ImportEvent>> {
+        match self.receiver.try_recv() {
+            Ok(event) => Some(Ok(event)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow!(
+                "Import worker stopped unexpectedly, the connection was lost"
+            ))),
+        }
+    }
+}
@@ -1,10 +1,94 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use std::fmt;
+
+use crate::import::ValueKind;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum DatabaseType {
     SQLite,
     MySQL,
     MariaDB,
+    PostgreSQL,
+}
+
+impl DatabaseType {
+    /// Parse the `db_type` string stored in `ConnectionConfig`
+    pub fn from_config(db_type: &str) -> Self {
+        match db_type {
+            "mysql" => DatabaseType::MySQL,
+            "mariadb" => DatabaseType::MariaDB,
+            "postgres" | "postgresql" => DatabaseType::PostgreSQL,
+            _ => DatabaseType::SQLite, // Default to SQLite
+        }
+    }
+
+    /// The `db_type` string stored in `ConnectionConfig`
+    pub fn config_name(&self) -> &'static str {
+        match self {
+            DatabaseType::SQLite => "sqlite",
+            DatabaseType::MySQL => "mysql",
+            DatabaseType::MariaDB => "mariadb",
+            DatabaseType::PostgreSQL => "postgres",
+        }
+    }
+
+    pub fn default_port(&self) -> &'static str {
+        match self {
+            DatabaseType::PostgreSQL => "5432",
+            _ => "3306",
+        }
+    }
+
+    pub fn quote_identifier(&self, name: &str) -> String {
+        match self {
+            DatabaseType::MySQL | DatabaseType::MariaDB => {
+                format!("`{}`", name.replace('`', "``"))
+            }
+            DatabaseType::SQLite | DatabaseType::PostgreSQL => {
+                format!("\"{}\"", name.replace('"', "\"\""))
+            }
+        }
+    }
+
+    /// `value` as a SQL literal this database reads back unchanged, for writing SQL files.
+    pub fn literal(&self, value: &CellValue) -> String {
+        match (value, self) {
+            // bytea has no X'..' form
+            (CellValue::Blob(bytes), DatabaseType::PostgreSQL) => {
+                let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                format!("'\\x{}'", hex)
+            }
+            // MySQL reads backslashes in string literals as escapes
+            (
+                CellValue::Null
+                | CellValue::Integer(_)
+                | CellValue::Real(_)
+                | CellValue::Decimal(_)
+                | CellValue::Blob(_),
+                _,
+            ) => value.to_sql_literal(),
+            (other, DatabaseType::MySQL | DatabaseType::MariaDB) => {
+                format!(
+                    "'{}'",
+                    other.to_string().replace('\\', "\\\\").replace('\'', "''")
+                )
+            }
+            _ => value.to_sql_literal(),
+        }
+    }
+
+    /// Placeholder for the 1-based parameter `index` bound to a column of `column_type`.
+    /// PostgreSQL parameters are sent as text, so they are cast to the column's type.
+    pub fn placeholder(&self, index: usize, column_type: &str) -> String {
+        match self {
+            DatabaseType::PostgreSQL if !column_type.is_empty() => {
+                format!("CAST(${} AS {})", index, column_type)
+            }
+            DatabaseType::PostgreSQL => format!("${}", index),
+            _ => "?".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -30,24 +114,130 @@ pub struct ColumnInfo {
     pub primary_key: bool,
 }
 
+/// A single value as returned by the database, before any formatting for display.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    DateTime(NaiveDateTime),
+    Decimal(String), // Kept as text so no precision is lost
+}
+
+impl CellValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, CellValue::Null)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            CellValue::Integer(_) | CellValue::Real(_) | CellValue::Decimal(_)
+        )
+    }
+
+    /// The value as a SQL literal, for showing statements. SQL files use
+    /// `DatabaseType::literal`, which knows each database's escaping.
+    pub fn to_sql_literal(&self) -> String {
+        match self {
+            CellValue::Null => "NULL".to_string(),
+            CellValue::Integer(_) | CellValue::Real(_) | CellValue::Decimal(_) => self.to_string(),
+            CellValue::Blob(bytes) => {
+                let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+                format!("X'{}'", hex)
+            }
+            _ => format!("'{}'", self.to_string().replace('\'', "''")),
+        }
+    }
+
+    /// Interpret text typed into a cell that held `original`, in a column declared as
+    /// `column_type`. Numeric cells (or NULL cells of a numeric column) only take numbers;
+    /// anything else is kept as typed, the word NULL included. Setting NULL is a separate
+    /// action.
+    pub fn from_edit(
+        text: &str,
+        original: &CellValue,
+        column_type: &str,
+    ) -> Result<CellValue, String> {
+        let kind = match original {
+            CellValue::Integer(_) => ValueKind::Integer,
+            CellValue::Real(_) | CellValue::Decimal(_) => ValueKind::Real,
+            CellValue::Null => ValueKind::from_type_name(column_type),
+            _ => ValueKind::Text,
+        };
+        let number = text.trim();
+        match kind {
+            ValueKind::Integer => number
+                .parse::<i64>()
+                .map(CellValue::Integer)
+                .map_err(|_| format!("'{}' is not an integer", text)),
+            ValueKind::Real if !number.parse::<f64>().is_ok_and(f64::is_finite) => {
+                Err(format!("'{}' is not a number", text))
+            }
+            // Decimals stay as typed so no precision is lost
+            ValueKind::Real => match original {
+                CellValue::Real(_) => Ok(CellValue::Real(number.parse().unwrap_or_default())),
+                _ => Ok(CellValue::Decimal(number.to_string())),
+            },
+            ValueKind::Text => Ok(CellValue::Text(text.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for CellValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellValue::Null => write!(f, "NULL"),
+            CellValue::Integer(i) => write!(f, "{}", i),
+            CellValue::Real(r) => write!(f, "{}", r),
+            CellValue::Text(s) | CellValue::Decimal(s) => write!(f, "{}", s),
+            CellValue::Blob(bytes) => write!(f, "<BLOB: {} bytes>", bytes.len()),
+            CellValue::Date(d) => write!(f, "{}", d),
+            CellValue::Time(t) => write!(f, "{}", t),
+            CellValue::DateTime(dt) => write!(f, "{}", dt),
+        }
+    }
+}
+
+/// SQL with positional parameters, written for the dialect of the connection it runs on.
+#[derive(Debug, Clone)]
+pub struct BoundStatement {
+    pub sql: String,
+    pub params: Vec<CellValue>,
+    pub expected_rows: Option<usize>, // Rows it must affect inside `execute_transaction`
+}
+
 #[derive(Debug, Clone)]
 pub struct QueryResult {
     pub columns: Vec<String>,
-    pub rows: Vec<Vec<String>>,
+    pub column_types: Vec<String>, // Declared/native type per column, empty if unknown
+    pub rows: Vec<Vec<CellValue>>,
+    pub rowids: Option<Vec<i64>>, // SQLite rowid per row, for tables without a primary key
     pub rows_affected: Option<usize>,
     pub execution_time_ms: u64,
 }
 
 impl QueryResult {
-    pub fn new(columns: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+    pub fn new(columns: Vec<String>, rows: Vec<Vec<CellValue>>) -> Self {
         Self {
+            column_types: vec![String::new(); columns.len()],
             columns,
             rows,
+            rowids: None,
             rows_affected: None,
             execution_time_ms: 0,
         }
     }
 
+    pub fn with_column_types(mut self, column_types: Vec<String>) -> Self {
+        self.column_types = column_types;
+        self
+    }
+
     pub fn with_time(mut self, time_ms: u64) -> Self {
         self.execution_time_ms = time_ms;
         self
@@ -59,12 +249,74 @@ impl QueryResult {
     }
 }
 
+/// Receives a table row by row while it is read in full, e.g. to write an export file.
+pub trait RowSink {
+    fn columns(&mut self, columns: &[String], column_types: &[String]) -> Result<()>;
+    fn row(&mut self, row: &[CellValue]) -> Result<()>;
+}
+
+/// Aborts the statement running on a connection from another thread.
+pub trait QueryCanceller: Send {
+    fn cancel(&self) -> Result<()>;
+}
+
 pub trait DatabaseConnection: Send {
     fn connect(path: &str) -> Result<Box<Self>>
     where
         Self: Sized;
     fn execute_query(&mut self, query: &str) -> Result<QueryResult>;
+    /// Run a single statement with bound parameters and return the number of affected rows.
+    fn execute_statement(&mut self, statement: &BoundStatement) -> Result<usize>;
+    /// Run statements in one transaction, returning the affected rows of each. Nothing is
+    /// kept unless every statement succeeds and affects the rows it expects.
+    fn execute_transaction(&mut self, statements: &[BoundStatement]) -> Result<Vec<usize>> {
+        self.execute_query("BEGIN")?;
+
+        let mut counts = Vec::new();
+        let mut missed = Vec::new();
+        for (idx, statement) in statements.iter().enumerate() {
+            match self.execute_statement(statement) {
+                Ok(affected) => {
+                    if let Some(expected) = statement.expected_rows.filter(|&n| n != affected) {
+                        missed.push(format!(
+                            "{}: {} -- {} row(s) instead of {}",
+                            idx + 1,
+                            statement.sql,
+                            affected,
+                            expected
+                        ));
+                    }
+                    counts.push(affected);
+                }
+                Err(err) => {
+                    // A failed rollback would only hide the error that caused it
+                    let _ = self.execute_query("ROLLBACK");
+                    return Err(err.context(format!(
+                        "Statement {} of {} failed, all changes were rolled back: {}",
+                        idx + 1,
+                        statements.len(),
+                        statement.sql
+                    )));
+                }
+            }
+        }
+        // A row changed or deleted by someone else since it was read doesn't match its key
+        if !missed.is_empty() {
+            let _ = self.execute_query("ROLLBACK");
+            return Err(anyhow!(
+                "{} of {} statements didn't match the rows they were meant to change, all changes were rolled back:\n{}",
+                missed.len(),
+                statements.len(),
+                missed.join("\n")
+            ));
+        }
+
+        self.execute_query("COMMIT")?;
+        Ok(counts)
+    }
     fn list_tables(&mut self) -> Result<Vec<TableInfo>>;
+    /// The names `list_tables` returns, without counting each table's rows.
+    fn table_names(&mut self) -> Result<Vec<String>>;
     #[allow(dead_code)]
     fn get_table_columns(&mut self, table_name: &str) -> Result<Vec<ColumnInfo>>;
     fn get_table_data(
@@ -73,7 +325,104 @@ pub trait DatabaseConnection: Send {
         limit: usize,
         offset: usize,
     ) -> Result<QueryResult>;
+    /// Read every row of a table without LIMIT, passing rows to `sink` as they arrive
+    /// instead of collecting them. Returns the number of rows read.
+    fn stream_table(&mut self, table_name: &str, sink: &mut dyn RowSink) -> Result<usize>;
     #[allow(dead_code)]
     fn close(&mut self) -> Result<()>;
+    /// Taken before a query starts, since the connection itself is busy until it returns.
+    fn query_canceller(&self) -> Box<dyn QueryCanceller>;
+    /// Whether a transaction is open, for drivers that can tell without a round trip.
+    fn in_transaction(&self) -> Option<bool> {
+        None
+    }
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_edit_keeps_the_column_type() {
+        let int = CellValue::Integer(1);
+        assert_eq!(
+            CellValue::from_edit(" 42 ", &int, ""),
+            Ok(CellValue::Integer(42))
+        );
+        assert_eq!(
+            CellValue::from_edit("4x", &int, ""),
+            Err("'4x' is not an integer".to_string())
+        );
+        assert_eq!(
+            CellValue::from_edit("2.5", &CellValue::Real(1.0), ""),
+            Ok(CellValue::Real(2.5))
+        );
+        assert_eq!(
+            CellValue::from_edit("1.10", &CellValue::Decimal("1.00".to_string()), ""),
+            Ok(CellValue::Decimal("1.10".to_string()))
+        );
+        assert_eq!(
+            CellValue::from_edit("inf", &CellValue::Real(1.0), ""),
+            Err("'inf' is not a number".to_string())
+        );
+        // A NULL cell goes by the declared type
+        assert_eq!(
+            CellValue::from_edit("7", &CellValue::Null, "BIGINT"),
+            Ok(CellValue::Integer(7))
+        );
+        assert!(CellValue::from_edit("seven", &CellValue::Null, "integer").is_err());
+        assert_eq!(
+            CellValue::from_edit("0.5", &CellValue::Null, "NUMERIC(10,2)"),
+            Ok(CellValue::Decimal("0.5".to_string()))
+        );
+    }
+
+    #[test]
+    fn from_edit_keeps_text_as_typed() {
+        let text = CellValue::Text("a".to_string());
+        assert_eq!(
+            CellValue::from_edit("NULL", &text, "TEXT"),
+            Ok(CellValue::Text("NULL".to_string()))
+        );
+        assert_eq!(
+            CellValue::from_edit("", &CellValue::Null, "varchar(20)"),
+            Ok(CellValue::Text(String::new()))
+        );
+        assert_eq!(
+            CellValue::from_edit(" 02134 ", &text, ""),
+            Ok(CellValue::Text(" 02134 ".to_string()))
+        );
+    }
+
+    #[test]
+    fn literal_per_dialect() {
+        let quoted = CellValue::Text("it's a\\b".to_string());
+        assert_eq!(DatabaseType::SQLite.literal(&quoted), "'it''s a\\b'");
+        assert_eq!(DatabaseType::PostgreSQL.literal(&quoted), "'it''s a\\b'");
+        assert_eq!(DatabaseType::MySQL.literal(&quoted), "'it''s a\\\\b'");
+        assert_eq!(
+            DatabaseType::SQLite.literal(&CellValue::Text("NULL".to_string())),
+            "'NULL'"
+        );
+
+        for dialect in [
+            DatabaseType::SQLite,
+            DatabaseType::MySQL,
+            DatabaseType::PostgreSQL,
+        ] {
+            assert_eq!(dialect.literal(&CellValue::Null), "NULL");
+            assert_eq!(dialect.literal(&CellValue::Integer(-3)), "-3");
+            assert_eq!(dialect.literal(&CellValue::Real(1.5)), "1.5");
+            assert_eq!(
+                dialect.literal(&CellValue::Decimal("10.00".to_string())),
+                "10.00"
+            );
+        }
+
+        let blob = CellValue::Blob(vec![0x00, 0xab]);
+        assert_eq!(DatabaseType::SQLite.literal(&blob), "X'00AB'");
+        assert_eq!(DatabaseType::MySQL.literal(&blob), "X'00AB'");
+        assert_eq!(DatabaseType::PostgreSQL.literal(&blob), "'\\x00ab'");
+    }
+}
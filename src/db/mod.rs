@@ -1,5 +1,10 @@
+pub mod background;
 pub mod connection;
 pub mod mysql;
+pub mod postgres;
 pub mod sqlite;
 
-pub use connection::{ConnectionInfo, DatabaseConnection, DatabaseType, QueryResult, TableInfo};
+pub use connection::{
+    BoundStatement, CellValue, ConnectionInfo, DatabaseConnection, DatabaseType, QueryResult,
+    RowSink, TableInfo,
+};
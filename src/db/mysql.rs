@@ -1,19 +1,46 @@
 use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use mysql::prelude::*;
 use mysql::*;
 use std::time::Instant;
 
-use super::connection::{DatabaseConnection, QueryResult, TableInfo};
+use super::connection::{
+    BoundStatement, CellValue, ColumnInfo, DatabaseConnection, DatabaseType, QueryCanceller,
+    QueryResult, RowSink, TableInfo,
+};
+use crate::sql::split_statements;
 
 pub struct MySQLConnection {
     conn: PooledConn,
+    pool: Pool,
     current_database: Option<String>,
 }
 
+struct MySQLCanceller {
+    pool: Pool,
+    connection_id: u32,
+}
+
+impl QueryCanceller for MySQLCanceller {
+    fn cancel(&self) -> Result<()> {
+        // The busy connection can't take commands, so kill its query from a second one
+        let mut conn = self.pool.get_conn()?;
+        conn.query_drop(format!("KILL QUERY {}", self.connection_id))?;
+        Ok(())
+    }
+}
+
 impl MySQLConnection {
+    /// Options from the URL. Rows affected count the rows an UPDATE matched, as in the other
+    /// databases, rather than only those whose values changed.
+    fn opts(url: &str) -> Result<Opts> {
+        let opts = OptsBuilder::from_opts(Opts::from_url(url)?)
+            .additional_capabilities(consts::CapabilityFlags::CLIENT_FOUND_ROWS);
+        Ok(opts.into())
+    }
+
     pub fn connect(connection_string: &str) -> Result<Box<dyn DatabaseConnection>> {
-        let opts = Opts::from_url(connection_string)?;
-        let pool = Pool::new(opts)?;
+        let pool = Pool::new(Self::opts(connection_string)?)?;
         let mut conn = pool.get_conn()?;
 
         // Clear any database context to start with database list
@@ -21,6 +48,7 @@ impl MySQLConnection {
 
         Ok(Box::new(MySQLConnection {
             conn,
+            pool,
             current_database: None,
         }))
     }
@@ -55,151 +83,57 @@ impl MySQLConnection {
         Ok(current_db)
     }
 
-    pub fn clear_database_context(&mut self) -> Result<()> {
-        // Switch to no database to force showing database list
-        self.conn.query_drop("USE information_schema")?;
-        self.current_database = None;
-        Ok(())
+    /// Names of the databases on the server, without the system ones.
+    pub fn list_databases(&mut self) -> Result<Vec<String>> {
+        let names: Vec<String> = self.conn.query("SHOW DATABASES")?;
+        Ok(names
+            .into_iter()
+            .filter(|name| {
+                !["information_schema", "mysql", "performance_schema", "sys"]
+                    .contains(&name.as_str())
+            })
+            .collect())
     }
-}
-
-impl DatabaseConnection for MySQLConnection {
-    fn connect(path: &str) -> Result<Box<Self>>
-    where
-        Self: Sized,
-    {
-        let opts = Opts::from_url(path)?;
-        let pool = Pool::new(opts)?;
-        let mut conn = pool.get_conn()?;
-
-        // Clear any database context to start with database list
-        conn.query_drop("USE information_schema")?;
 
-        Ok(Box::new(MySQLConnection {
-            conn,
-            current_database: None,
-        }))
+    /// `db`.`table` in the current database; MySQL has no default to fall back on.
+    fn qualified_table_name(&mut self, table_name: &str) -> Result<String> {
+        // Always get the current database from MySQL to ensure we have the right context
+        match self.get_current_database()? {
+            Some(db_name) if !db_name.is_empty() => Ok(format!(
+                "`{}`.`{}`",
+                db_name.replace('`', "``"),
+                table_name.replace('`', "``")
+            )),
+            _ => Err(anyhow::anyhow!(
+                "No database selected. Please select a database first."
+            )),
+        }
     }
 
-    fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
-        let start = Instant::now();
-
-        // Split query into individual statements
-        let statements: Vec<&str> = query
-            .split(';')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        let mut last_result = QueryResult::new(Vec::new(), Vec::new());
-
-        for stmt_text in statements {
-            let stmt_upper = stmt_text.to_uppercase();
-
-            if stmt_upper.starts_with("SELECT")
-                || stmt_upper.starts_with("SHOW")
-                || stmt_upper.starts_with("DESCRIBE")
-                || stmt_upper.starts_with("EXPLAIN")
-            {
-                // Query that returns rows
-                let result: Vec<Row> = self.conn.query(stmt_text)?;
-
-                if result.is_empty() {
-                    last_result = QueryResult::new(Vec::new(), Vec::new());
-                } else {
-                    // Get column names
-                    let columns: Vec<String> = result[0]
-                        .columns_ref()
-                        .iter()
-                        .map(|col| col.name_str().to_string())
-                        .collect();
-
-                    // Convert rows to Vec<Vec<String>>
-                    let mut rows = Vec::new();
-                    for row in result {
-                        let mut row_data = Vec::new();
-                        for (idx, _col) in row.columns_ref().iter().enumerate() {
-                            // Try to get the value safely, handling NULLs
-                            let value = match row.get_opt::<mysql::Value, _>(idx) {
-                                Some(Ok(mysql::Value::NULL)) => "NULL".to_string(),
-                                Some(Ok(val)) => {
-                                    // Convert MySQL value to string based on its type
-                                    match val {
-                                        mysql::Value::Bytes(bytes) => {
-                                            String::from_utf8_lossy(&bytes).to_string()
-                                        }
-                                        mysql::Value::Int(i) => i.to_string(),
-                                        mysql::Value::UInt(u) => u.to_string(),
-                                        mysql::Value::Float(f) => f.to_string(),
-                                        mysql::Value::Double(d) => d.to_string(),
-                                        mysql::Value::Date(
-                                            year,
-                                            month,
-                                            day,
-                                            hour,
-                                            min,
-                                            sec,
-                                            micro,
-                                        ) => {
-                                            if hour == 0 && min == 0 && sec == 0 && micro == 0 {
-                                                format!("{:04}-{:02}-{:02}", year, month, day)
-                                            } else {
-                                                format!(
-                                                    "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
-                                                    year, month, day, hour, min, sec
-                                                )
-                                            }
-                                        }
-                                        mysql::Value::Time(
-                                            negative,
-                                            days,
-                                            hours,
-                                            minutes,
-                                            seconds,
-                                            _microseconds,
-                                        ) => {
-                                            let sign = if negative { "-" } else { "" };
-                                            if days > 0 {
-                                                format!(
-                                                    "{}{:02}:{:02}:{:02}",
-                                                    sign,
-                                                    days * 24 + hours as u32,
-                                                    minutes,
-                                                    seconds
-                                                )
-                                            } else {
-                                                format!(
-                                                    "{}{:02}:{:02}:{:02}",
-                                                    sign, hours, minutes, seconds
-                                                )
-                                            }
-                                        }
-                                        mysql::Value::NULL => "NULL".to_string(),
-                                    }
-                                }
-                                Some(Err(_)) | None => "NULL".to_string(),
-                            };
-                            row_data.push(value);
-                        }
-                        rows.push(row_data);
-                    }
-
-                    last_result = QueryResult::new(columns, rows);
+    fn row_to_values(row: &Row, columns: &[Column]) -> Vec<CellValue> {
+        columns
+            .iter()
+            .enumerate()
+            .map(|(idx, col)| {
+                // Try to get the value safely, handling NULLs
+                match row.get_opt::<mysql::Value, _>(idx) {
+                    Some(Ok(val)) => to_cell_value(val, col),
+                    Some(Err(_)) | None => CellValue::Null,
                 }
-            } else {
-                // INSERT, UPDATE, DELETE, CREATE, DROP, etc.
-                self.conn.query_drop(stmt_text)?;
-                let affected = self.conn.affected_rows();
-                last_result =
-                    QueryResult::new(Vec::new(), Vec::new()).with_affected(affected as usize);
-            }
-        }
+            })
+            .collect()
+    }
 
-        let elapsed = start.elapsed();
-        Ok(last_result.with_time(elapsed.as_millis() as u64))
+    pub fn clear_database_context(&mut self) -> Result<()> {
+        // Switch to no database to force showing database list
+        self.conn.query_drop("USE information_schema")?;
+        self.current_database = None;
+        Ok(())
     }
 
-    fn list_tables(&mut self) -> Result<Vec<TableInfo>> {
+    /// Tables of the current database, or the databases when none is in use, with each
+    /// table's row count if `count_rows` is set.
+    fn tables(&mut self, count_rows: bool) -> Result<Vec<TableInfo>> {
         // First, try to get the current database
         let current_db_result: Vec<Row> = self.conn.query("SELECT DATABASE()")?;
         let current_db: Option<String> = if let Some(row) = current_db_result.first() {
@@ -223,9 +157,12 @@ impl DatabaseConnection for MySQLConnection {
                     }
 
                     // Get row count for each table, with error handling
-                    let count_query = format!("SELECT COUNT(*) FROM `{}`", table_name);
-                    let count: Option<u64> =
-                        self.conn.query_first(&count_query).unwrap_or_default();
+                    let count: Option<u64> = if count_rows {
+                        let count_query = format!("SELECT COUNT(*) FROM `{}`", table_name);
+                        self.conn.query_first(&count_query).unwrap_or_default()
+                    } else {
+                        None
+                    };
 
                     tables.push(TableInfo {
                         name: table_name,
@@ -276,13 +213,218 @@ impl DatabaseConnection for MySQLConnection {
             Ok(databases)
         }
     }
+}
 
-    fn get_table_columns(
-        &mut self,
-        _table_name: &str,
-    ) -> Result<Vec<super::connection::ColumnInfo>> {
-        // Not implemented yet
-        Ok(Vec::new())
+fn column_type_name(column: &Column) -> String {
+    format!("{:?}", column.column_type())
+        .trim_start_matches("MYSQL_TYPE_")
+        .to_string()
+}
+
+fn to_mysql_value(value: &CellValue) -> Value {
+    match value {
+        CellValue::Null => Value::NULL,
+        CellValue::Integer(i) => Value::Int(*i),
+        CellValue::Real(r) => Value::Double(*r),
+        CellValue::Blob(bytes) => Value::Bytes(bytes.clone()),
+        // Dates and decimals go over as text and are converted by the server
+        other => Value::Bytes(other.to_string().into_bytes()),
+    }
+}
+
+fn to_cell_value(value: Value, column: &Column) -> CellValue {
+    use mysql::consts::ColumnType::*;
+
+    match value {
+        Value::NULL => CellValue::Null,
+        Value::Int(i) => CellValue::Integer(i),
+        Value::UInt(u) => i64::try_from(u)
+            .map(CellValue::Integer)
+            .unwrap_or_else(|_| CellValue::Decimal(u.to_string())),
+        Value::Float(f) => CellValue::Real(f as f64),
+        Value::Double(d) => CellValue::Real(d),
+        Value::Date(year, month, day, hour, min, sec, micro) => {
+            let date = NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32);
+            let time = NaiveTime::from_hms_micro_opt(hour as u32, min as u32, sec as u32, micro);
+            match (date, time) {
+                (Some(date), _)
+                    if matches!(column.column_type(), MYSQL_TYPE_DATE | MYSQL_TYPE_NEWDATE) =>
+                {
+                    CellValue::Date(date)
+                }
+                (Some(date), Some(time)) => CellValue::DateTime(date.and_time(time)),
+                // Zero dates such as 0000-00-00 have no chrono equivalent
+                _ => CellValue::Text(format!(
+                    "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                    year, month, day, hour, min, sec
+                )),
+            }
+        }
+        Value::Time(negative, days, hours, minutes, seconds, micro) => {
+            let time =
+                NaiveTime::from_hms_micro_opt(hours as u32, minutes as u32, seconds as u32, micro);
+            match time {
+                Some(time) if !negative && days == 0 => CellValue::Time(time),
+                // TIME is an interval in MySQL and may be negative or exceed a day
+                _ => {
+                    let sign = if negative { "-" } else { "" };
+                    CellValue::Text(format!(
+                        "{}{:02}:{:02}:{:02}",
+                        sign,
+                        days * 24 + hours as u32,
+                        minutes,
+                        seconds
+                    ))
+                }
+            }
+        }
+        Value::Bytes(bytes) => {
+            // The text protocol sends everything as bytes, the column type says what they hold
+            let text = String::from_utf8_lossy(&bytes).to_string();
+            match column.column_type() {
+                MYSQL_TYPE_TINY | MYSQL_TYPE_SHORT | MYSQL_TYPE_LONG | MYSQL_TYPE_LONGLONG
+                | MYSQL_TYPE_INT24 | MYSQL_TYPE_YEAR => text
+                    .parse::<i64>()
+                    .map(CellValue::Integer)
+                    .unwrap_or(CellValue::Decimal(text)),
+                MYSQL_TYPE_FLOAT | MYSQL_TYPE_DOUBLE => text
+                    .parse::<f64>()
+                    .map(CellValue::Real)
+                    .unwrap_or(CellValue::Text(text)),
+                MYSQL_TYPE_DECIMAL | MYSQL_TYPE_NEWDECIMAL => CellValue::Decimal(text),
+                MYSQL_TYPE_DATE | MYSQL_TYPE_NEWDATE => {
+                    NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+                        .map(CellValue::Date)
+                        .unwrap_or(CellValue::Text(text))
+                }
+                MYSQL_TYPE_DATETIME
+                | MYSQL_TYPE_DATETIME2
+                | MYSQL_TYPE_TIMESTAMP
+                | MYSQL_TYPE_TIMESTAMP2 => {
+                    NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f")
+                        .map(CellValue::DateTime)
+                        .unwrap_or(CellValue::Text(text))
+                }
+                MYSQL_TYPE_TIME | MYSQL_TYPE_TIME2 => {
+                    NaiveTime::parse_from_str(&text, "%H:%M:%S%.f")
+                        .map(CellValue::Time)
+                        .unwrap_or(CellValue::Text(text))
+                }
+                // Character set 63 is `binary`: BLOB, BINARY, VARBINARY and BIT columns
+                _ if column.character_set() == 63 => CellValue::Blob(bytes),
+                _ => CellValue::Text(text),
+            }
+        }
+    }
+}
+
+impl DatabaseConnection for MySQLConnection {
+    fn connect(path: &str) -> Result<Box<Self>>
+    where
+        Self: Sized,
+    {
+        let pool = Pool::new(Self::opts(path)?)?;
+        let mut conn = pool.get_conn()?;
+
+        // Clear any database context to start with database list
+        conn.query_drop("USE information_schema")?;
+
+        Ok(Box::new(MySQLConnection {
+            conn,
+            pool,
+            current_database: None,
+        }))
+    }
+
+    fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+        let start = Instant::now();
+
+        // Split query into individual statements, keeping quoted text and trigger bodies whole
+        let statements = split_statements(query, &DatabaseType::MySQL);
+
+        let mut last_result = QueryResult::new(Vec::new(), Vec::new());
+
+        for statement in &statements {
+            let stmt_text = statement.text.as_str();
+            let stmt_upper = stmt_text.to_uppercase();
+
+            if stmt_upper.starts_with("SELECT")
+                || stmt_upper.starts_with("SHOW")
+                || stmt_upper.starts_with("DESCRIBE")
+                || stmt_upper.starts_with("EXPLAIN")
+            {
+                // Query that returns rows
+                let result: Vec<Row> = self.conn.query(stmt_text)?;
+
+                if result.is_empty() {
+                    last_result = QueryResult::new(Vec::new(), Vec::new());
+                } else {
+                    // Get column names
+                    let columns: Vec<String> = result[0]
+                        .columns_ref()
+                        .iter()
+                        .map(|col| col.name_str().to_string())
+                        .collect();
+
+                    let column_types: Vec<String> = result[0]
+                        .columns_ref()
+                        .iter()
+                        .map(column_type_name)
+                        .collect();
+
+                    let rows = result
+                        .iter()
+                        .map(|row| Self::row_to_values(row, row.columns_ref()))
+                        .collect();
+
+                    last_result = QueryResult::new(columns, rows).with_column_types(column_types);
+                }
+            } else {
+                // INSERT, UPDATE, DELETE, CREATE, DROP, etc.
+                self.conn.query_drop(stmt_text)?;
+                let affected = self.conn.affected_rows();
+                last_result =
+                    QueryResult::new(Vec::new(), Vec::new()).with_affected(affected as usize);
+            }
+        }
+
+        let elapsed = start.elapsed();
+        Ok(last_result.with_time(elapsed.as_millis() as u64))
+    }
+
+    fn execute_statement(&mut self, statement: &BoundStatement) -> Result<usize> {
+        let params: Vec<Value> = statement.params.iter().map(to_mysql_value).collect();
+        self.conn
+            .exec_drop(&statement.sql, Params::Positional(params))?;
+        Ok(self.conn.affected_rows() as usize)
+    }
+
+    fn list_tables(&mut self) -> Result<Vec<TableInfo>> {
+        self.tables(true)
+    }
+
+    fn table_names(&mut self) -> Result<Vec<String>> {
+        Ok(self.tables(false)?.into_iter().map(|t| t.name).collect())
+    }
+
+    fn get_table_columns(&mut self, table_name: &str) -> Result<Vec<ColumnInfo>> {
+        let rows: Vec<(String, String, String, String)> = self.conn.exec(
+            "SELECT COLUMN_NAME, COLUMN_TYPE, IS_NULLABLE, COLUMN_KEY \
+             FROM information_schema.COLUMNS \
+             WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? \
+             ORDER BY ORDINAL_POSITION",
+            (table_name,),
+        )?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, data_type, nullable, key)| ColumnInfo {
+                name,
+                data_type,
+                nullable: nullable == "YES",
+                primary_key: key == "PRI",
+            })
+            .collect())
     }
 
     fn get_table_data(
@@ -291,24 +433,7 @@ impl DatabaseConnection for MySQLConnection {
         limit: usize,
         offset: usize,
     ) -> Result<QueryResult> {
-        // Always get the current database from MySQL to ensure we have the right context
-        let current_db = self.get_current_database()?;
-
-        let qualified_table_name = if let Some(db_name) = current_db {
-            if !db_name.is_empty() {
-                // Use fully qualified table name
-                format!("`{}`.`{}`", db_name, table_name)
-            } else {
-                return Err(anyhow::anyhow!(
-                    "No database selected. Please select a database first."
-                ));
-            }
-        } else {
-            return Err(anyhow::anyhow!(
-                "No database selected. Please select a database first."
-            ));
-        };
-
+        let qualified_table_name = self.qualified_table_name(table_name)?;
         let query = format!(
             "SELECT * FROM {} LIMIT {} OFFSET {}",
             qualified_table_name, limit, offset
@@ -316,10 +441,39 @@ impl DatabaseConnection for MySQLConnection {
         self.execute_query(&query)
     }
 
+    fn stream_table(&mut self, table_name: &str, sink: &mut dyn RowSink) -> Result<usize> {
+        let qualified_table_name = self.qualified_table_name(table_name)?;
+        // query_iter reads rows off the wire one at a time instead of buffering the result
+        let mut result = self
+            .conn
+            .query_iter(format!("SELECT * FROM {}", qualified_table_name))?;
+        let columns: Vec<Column> = result.columns().as_ref().to_vec();
+        let column_names: Vec<String> = columns
+            .iter()
+            .map(|col| col.name_str().to_string())
+            .collect();
+        let column_types: Vec<String> = columns.iter().map(column_type_name).collect();
+        sink.columns(&column_names, &column_types)?;
+
+        let mut count = 0;
+        for row in result.by_ref() {
+            sink.row(&Self::row_to_values(&row?, &columns))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
     fn close(&mut self) -> Result<()> {
         Ok(())
     }
 
+    fn query_canceller(&self) -> Box<dyn QueryCanceller> {
+        Box::new(MySQLCanceller {
+            pool: self.pool.clone(),
+            connection_id: self.conn.connection_id(),
+        })
+    }
+
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
@@ -0,0 +1,893 @@
+use anyhow::{anyhow, Result};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use postgres::{
+    types::{FromSql, Kind, ToSql, Type},
+    CancelToken, Client, NoTls, SimpleQueryMessage,
+};
+use std::error::Error;
+use std::time::Instant;
+
+use super::connection::{
+    BoundStatement, CellValue, ColumnInfo, DatabaseConnection, DatabaseType, QueryCanceller,
+    QueryResult, RowSink, TableInfo,
+};
+use crate::sql::split_statements;
+
+const SYSTEM_SCHEMAS: [&str; 2] = ["pg_catalog", "information_schema"];
+
+pub struct PostgresConnection {
+    client: Client,
+    current_schema: Option<String>,
+}
+
+struct PostgresCanceller(CancelToken);
+
+impl QueryCanceller for PostgresCanceller {
+    fn cancel(&self) -> Result<()> {
+        self.0.cancel_query(NoTls)?;
+        Ok(())
+    }
+}
+
+impl PostgresConnection {
+    pub fn connect(connection_string: &str) -> Result<Box<dyn DatabaseConnection>> {
+        let conn: Box<dyn DatabaseConnection> =
+            <Self as DatabaseConnection>::connect(connection_string)?;
+        Ok(conn)
+    }
+
+    /// Switch to a schema, the PostgreSQL counterpart of MySQL's `USE database`.
+    pub fn use_schema(&mut self, schema_name: &str) -> Result<()> {
+        let query = format!("SET search_path TO {}", quote_ident(schema_name));
+        self.client.batch_execute(&query)?;
+        self.current_schema = Some(schema_name.to_string());
+        Ok(())
+    }
+
+    pub fn clear_schema_context(&mut self) -> Result<()> {
+        // Back to the server default so unqualified names behave as in psql
+        self.client.batch_execute("RESET search_path")?;
+        self.current_schema = None;
+        Ok(())
+    }
+
+    fn qualified_table_name(&self, table_name: &str) -> Result<String> {
+        match &self.current_schema {
+            Some(schema) => Ok(format!(
+                "{}.{}",
+                quote_ident(schema),
+                quote_ident(table_name)
+            )),
+            None => Err(anyhow::anyhow!(
+                "No schema selected. Please select a schema first."
+            )),
+        }
+    }
+
+    /// Column details in the same shape as MySQL's `DESCRIBE`:
+    /// name, type, nullable (YES/NO), default, extra.
+    pub fn describe_table(&mut self, table_name: &str) -> Result<Vec<Vec<String>>> {
+        let regclass = self.regclass(table_name)?;
+        let query = format!(
+            "SELECT a.attname, format_type(a.atttypid, a.atttypmod), \
+                    CASE WHEN a.attnotnull THEN 'NO' ELSE 'YES' END, \
+                    pg_get_expr(d.adbin, d.adrelid), \
+                    CASE WHEN EXISTS ( \
+                        SELECT 1 FROM pg_index i \
+                        WHERE i.indrelid = a.attrelid AND i.indisprimary \
+                          AND a.attnum = ANY(i.indkey)) \
+                    THEN 'PRIMARY KEY' ELSE '' END \
+             FROM pg_attribute a \
+             LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum \
+             WHERE a.attrelid = {} AND a.attnum > 0 AND NOT a.attisdropped \
+             ORDER BY a.attnum",
+            regclass
+        );
+
+        Ok(self
+            .query_strings(&query)?
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|value| value.unwrap_or_else(|| "NULL".to_string()))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Rebuild a CREATE TABLE statement from the catalog, since PostgreSQL has
+    /// no `SHOW CREATE TABLE`. Views get their CREATE VIEW statement.
+    pub fn show_create_table(&mut self, table_name: &str) -> Result<String> {
+        if let Some(view) = self.view_definition(table_name)? {
+            return Ok(view);
+        }
+        let qualified_table_name = self.qualified_table_name(table_name)?;
+        let columns = self.describe_table(table_name)?;
+
+        let mut definitions: Vec<String> = columns
+            .iter()
+            .map(|row| {
+                let mut definition = format!("  {} {}", quote_ident(&row[0]), row[1]);
+                if row[3] != "NULL" {
+                    definition.push_str(&format!(" DEFAULT {}", row[3]));
+                }
+                if row[2] == "NO" {
+                    definition.push_str(" NOT NULL");
+                }
+                definition
+            })
+            .collect();
+
+        let constraint_query = format!(
+            "SELECT conname, pg_get_constraintdef(oid) FROM pg_constraint \
+             WHERE conrelid = {} ORDER BY contype, conname",
+            self.regclass(table_name)?
+        );
+        for row in self.query_strings(&constraint_query)? {
+            if let [Some(name), Some(definition)] = row.as_slice() {
+                definitions.push(format!("  CONSTRAINT {} {}", quote_ident(name), definition));
+            }
+        }
+
+        Ok(format!(
+            "CREATE TABLE {} (\n{}\n);",
+            qualified_table_name,
+            definitions.join(",\n")
+        ))
+    }
+
+    /// CREATE VIEW statement for `table_name`, or `None` when it isn't a view.
+    pub fn view_definition(&mut self, table_name: &str) -> Result<Option<String>> {
+        let query = format!(
+            "SELECT pg_get_viewdef(c.oid, true) FROM pg_class c \
+             WHERE c.oid = {} AND c.relkind = 'v'",
+            self.regclass(table_name)?
+        );
+        let definition = self
+            .query_strings(&query)?
+            .into_iter()
+            .next()
+            .and_then(|row| row.into_iter().next().flatten());
+        match definition {
+            Some(definition) => Ok(Some(format!(
+                "CREATE VIEW {} AS\n{};",
+                self.qualified_table_name(table_name)?,
+                definition.trim_end().trim_end_matches(';')
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Index names with their columns, in key order.
+    pub fn table_indexes(&mut self, table_name: &str) -> Result<Vec<(String, Vec<String>)>> {
+        let query = format!(
+            "SELECT ic.relname, a.attname \
+             FROM pg_index i \
+             JOIN pg_class ic ON ic.oid = i.indexrelid \
+             CROSS JOIN LATERAL unnest(i.indkey) WITH ORDINALITY AS k(attnum, ord) \
+             JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = k.attnum \
+             WHERE i.indrelid = {} \
+             ORDER BY ic.relname, k.ord",
+            self.regclass(table_name)?
+        );
+
+        let mut indexes: Vec<(String, Vec<String>)> = Vec::new();
+        for row in self.query_strings(&query)? {
+            if let [Some(index_name), Some(column_name)] = row.as_slice() {
+                match indexes.last_mut() {
+                    Some((name, columns)) if name == index_name => {
+                        columns.push(column_name.clone())
+                    }
+                    _ => indexes.push((index_name.clone(), vec![column_name.clone()])),
+                }
+            }
+        }
+        Ok(indexes)
+    }
+
+    /// CREATE INDEX statements for the table's indexes, leaving out those that back a
+    /// constraint since `show_create_table` already declares them.
+    pub fn index_definitions(&mut self, table_name: &str) -> Result<Vec<String>> {
+        let query = format!(
+            "SELECT pg_get_indexdef(i.indexrelid) FROM pg_index i \
+             WHERE i.indrelid = {} \
+               AND NOT EXISTS (SELECT 1 FROM pg_constraint c \
+                               WHERE c.conindid = i.indexrelid AND c.conrelid = i.indrelid \
+                                 AND c.contype IN ('p', 'u', 'x')) \
+             ORDER BY i.indexrelid::regclass::text",
+            self.regclass(table_name)?
+        );
+        Ok(self
+            .query_strings(&query)?
+            .into_iter()
+            .filter_map(|row| row.into_iter().next().flatten())
+            .map(|definition| format!("{};", definition))
+            .collect())
+    }
+
+    fn regclass(&self, table_name: &str) -> Result<String> {
+        let qualified_table_name = self.qualified_table_name(table_name)?;
+        Ok(format!(
+            "{}::regclass",
+            quote_literal(&qualified_table_name)
+        ))
+    }
+
+    /// Run one statement through the simple protocol, where every value arrives as text.
+    fn simple_query(&mut self, statement: &str) -> Result<QueryResult> {
+        let mut columns: Option<Vec<String>> = None;
+        let mut rows = Vec::new();
+        let mut affected = 0;
+
+        for message in self.client.simple_query(statement)? {
+            match message {
+                SimpleQueryMessage::RowDescription(description) => {
+                    columns = Some(description.iter().map(|c| c.name().to_string()).collect());
+                }
+                SimpleQueryMessage::Row(row) => {
+                    if columns.is_none() {
+                        columns =
+                            Some(row.columns().iter().map(|c| c.name().to_string()).collect());
+                    }
+                    rows.push(
+                        (0..row.len())
+                            .map(|idx| to_cell_value(row.get(idx), None))
+                            .collect(),
+                    );
+                }
+                SimpleQueryMessage::CommandComplete(count) => {
+                    affected = count as usize;
+                }
+                _ => {}
+            }
+        }
+
+        // Statements that describe a row set return rows, everything else reports affected rows
+        Ok(match columns {
+            Some(columns) => QueryResult::new(columns, rows),
+            None => QueryResult::new(Vec::new(), Vec::new()).with_affected(affected),
+        })
+    }
+
+    fn query_strings(&mut self, query: &str) -> Result<Vec<Vec<Option<String>>>> {
+        let mut rows = Vec::new();
+        for message in self.client.simple_query(query)? {
+            if let SimpleQueryMessage::Row(row) = message {
+                rows.push(
+                    (0..row.len())
+                        .map(|idx| row.get(idx).map(str::to_string))
+                        .collect(),
+                );
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Tables and views of the current schema, or the schemas when none is selected. Row
+    /// counts are only taken with `count_rows`, since each one scans its table.
+    fn tables(&mut self, count_rows: bool) -> Result<Vec<TableInfo>> {
+        if let Some(schema) = self.current_schema.clone() {
+            // We have a current schema, show its tables and views
+            let query = format!(
+                "SELECT table_name FROM information_schema.tables \
+                 WHERE table_schema = {} AND table_type IN ('BASE TABLE', 'VIEW') \
+                 ORDER BY table_name",
+                quote_literal(&schema)
+            );
+            let names = self.query_strings(&query)?;
+
+            let mut tables = Vec::new();
+            for row in names {
+                let table_name = match row.into_iter().next().flatten() {
+                    Some(name) if !name.is_empty() => name,
+                    _ => continue, // Skip empty names
+                };
+
+                // Get row count for each table, with error handling
+                let count_query = format!(
+                    "SELECT COUNT(*) FROM {}.{}",
+                    quote_ident(&schema),
+                    quote_ident(&table_name)
+                );
+                let count = count_rows
+                    .then(|| self.query_strings(&count_query).ok())
+                    .flatten()
+                    .and_then(|rows| rows.into_iter().next())
+                    .and_then(|row| row.into_iter().next().flatten())
+                    .and_then(|c| c.parse::<usize>().ok());
+
+                tables.push(TableInfo {
+                    name: table_name,
+                    row_count: count,
+                });
+            }
+            Ok(tables)
+        } else {
+            // No current schema, show available schemas
+            let query = "SELECT schema_name FROM information_schema.schemata \
+                         WHERE schema_name NOT LIKE 'pg_toast%' AND schema_name NOT LIKE 'pg_temp%' \
+                         ORDER BY schema_name";
+            let mut schemas = Vec::new();
+            for row in self.query_strings(query)? {
+                let schema_name = row.into_iter().next().flatten().unwrap_or_default();
+                // Skip system schemas for cleaner display
+                if schema_name.is_empty() || SYSTEM_SCHEMAS.contains(&schema_name.as_str()) {
+                    continue;
+                }
+                schemas.push(TableInfo {
+                    name: schema_name,
+                    row_count: None,
+                });
+            }
+            Ok(schemas)
+        }
+    }
+}
+
+/// Quote an identifier with double quotes, doubling any embedded quote.
+pub fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Quote a string literal, doubling any embedded single quote.
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Parse the text form of a value according to its column type.
+fn to_cell_value(text: Option<&str>, column_type: Option<&Type>) -> CellValue {
+    let text = match text {
+        Some(text) => text,
+        None => return CellValue::Null,
+    };
+    let as_text = || CellValue::Text(text.to_string());
+
+    match column_type {
+        Some(&Type::INT2) | Some(&Type::INT4) | Some(&Type::INT8) | Some(&Type::OID) => text
+            .parse::<i64>()
+            .map(CellValue::Integer)
+            .unwrap_or_else(|_| as_text()),
+        Some(&Type::FLOAT4) | Some(&Type::FLOAT8) => text
+            .parse::<f64>()
+            .map(CellValue::Real)
+            .unwrap_or_else(|_| as_text()),
+        Some(&Type::NUMERIC) => CellValue::Decimal(text.to_string()),
+        Some(&Type::DATE) => NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .map(CellValue::Date)
+            .unwrap_or_else(|_| as_text()),
+        Some(&Type::TIME) => NaiveTime::parse_from_str(text, "%H:%M:%S%.f")
+            .map(CellValue::Time)
+            .unwrap_or_else(|_| as_text()),
+        Some(&Type::TIMESTAMP) => NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+            .map(CellValue::DateTime)
+            .unwrap_or_else(|_| as_text()),
+        // bytea arrives in hex format: \x0102ff
+        Some(&Type::BYTEA) => text
+            .strip_prefix("\\x")
+            .and_then(decode_hex)
+            .map(CellValue::Blob)
+            .unwrap_or_else(as_text),
+        _ => as_text(),
+    }
+}
+
+/// A column value exactly as the server sent it, in binary format.
+struct RawValue<'a>(&'a [u8]);
+
+impl<'a> FromSql<'a> for RawValue<'a> {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(RawValue(raw))
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+}
+
+/// Whether `from_binary` understands the binary format of `column_type`.
+fn reads_binary(column_type: &Type) -> bool {
+    matches!(column_type.kind(), Kind::Enum(_))
+        || [
+            Type::BOOL,
+            Type::INT2,
+            Type::INT4,
+            Type::INT8,
+            Type::OID,
+            Type::FLOAT4,
+            Type::FLOAT8,
+            Type::NUMERIC,
+            Type::TEXT,
+            Type::VARCHAR,
+            Type::BPCHAR,
+            Type::NAME,
+            Type::UNKNOWN,
+            Type::JSON,
+            Type::JSONB,
+            Type::XML,
+            Type::BYTEA,
+            Type::UUID,
+            Type::DATE,
+            Type::TIME,
+            Type::TIMESTAMP,
+            Type::TIMESTAMPTZ,
+        ]
+        .contains(column_type)
+}
+
+/// Decode a binary column value of a type `reads_binary` accepts. Values are shown the way
+/// the server would print them, except that timestamptz is always in UTC.
+fn from_binary(value: Option<RawValue>, column_type: &Type) -> CellValue {
+    let raw = match value {
+        Some(RawValue(raw)) => raw,
+        None => return CellValue::Null,
+    };
+    let text = |raw: &[u8]| CellValue::Text(String::from_utf8_lossy(raw).into_owned());
+    let int = |raw: &[u8]| {
+        let mut bytes = [0; 8];
+        let start = 8usize.saturating_sub(raw.len());
+        bytes[start..].copy_from_slice(&raw[raw.len().saturating_sub(8)..]);
+        // Sign-extend the 2 and 4 byte integers
+        let shift = 8 * start as u32;
+        (i64::from_be_bytes(bytes) << shift) >> shift
+    };
+
+    match *column_type {
+        Type::BOOL => CellValue::Text(if raw.first() == Some(&1) { "t" } else { "f" }.to_string()),
+        Type::INT2 | Type::INT4 | Type::INT8 => CellValue::Integer(int(raw)),
+        Type::OID => CellValue::Integer(int(raw) & 0xFFFF_FFFF),
+        Type::FLOAT4 => raw
+            .try_into()
+            .map(|bytes| CellValue::Real(f32::from_be_bytes(bytes) as f64))
+            .unwrap_or(CellValue::Null),
+        Type::FLOAT8 => raw
+            .try_into()
+            .map(|bytes| CellValue::Real(f64::from_be_bytes(bytes)))
+            .unwrap_or(CellValue::Null),
+        Type::NUMERIC => numeric_text(raw)
+            .map(CellValue::Decimal)
+            .unwrap_or_else(|| text(raw)),
+        // jsonb starts with a format version byte
+        Type::JSONB => text(raw.get(1..).unwrap_or_default()),
+        Type::BYTEA => CellValue::Blob(raw.to_vec()),
+        Type::UUID => {
+            let hex: String = raw.iter().map(|b| format!("{:02x}", b)).collect();
+            match (hex.len() == 32).then(|| {
+                format!(
+                    "{}-{}-{}-{}-{}",
+                    &hex[..8],
+                    &hex[8..12],
+                    &hex[12..16],
+                    &hex[16..20],
+                    &hex[20..]
+                )
+            }) {
+                Some(uuid) => CellValue::Text(uuid),
+                None => CellValue::Text(hex),
+            }
+        }
+        // Dates count days, times and timestamps count microseconds, from 2000-01-01
+        Type::DATE => match int(raw) {
+            days if days == i32::MAX as i64 => CellValue::Text("infinity".to_string()),
+            days if days == i32::MIN as i64 => CellValue::Text("-infinity".to_string()),
+            days => pg_epoch()
+                .date()
+                .checked_add_signed(Duration::days(days))
+                .map(CellValue::Date)
+                .unwrap_or_else(|| CellValue::Text(days.to_string())),
+        },
+        Type::TIME => {
+            let micros = int(raw);
+            NaiveTime::from_num_seconds_from_midnight_opt(
+                (micros / 1_000_000) as u32,
+                (micros % 1_000_000 * 1000) as u32,
+            )
+            .map(CellValue::Time)
+            .unwrap_or_else(|| CellValue::Text("24:00:00".to_string()))
+        }
+        Type::TIMESTAMP | Type::TIMESTAMPTZ => {
+            let timestamp = match int(raw) {
+                i64::MAX => return CellValue::Text("infinity".to_string()),
+                i64::MIN => return CellValue::Text("-infinity".to_string()),
+                micros => pg_epoch().checked_add_signed(Duration::microseconds(micros)),
+            };
+            match (timestamp, column_type == &Type::TIMESTAMPTZ) {
+                (Some(timestamp), false) => CellValue::DateTime(timestamp),
+                (Some(timestamp), true) => CellValue::Text(format!("{}+00", timestamp)),
+                (None, _) => CellValue::Text(int(raw).to_string()),
+            }
+        }
+        // Text types, json and enum labels are sent as their text
+        _ => text(raw),
+    }
+}
+
+fn pg_epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2000, 1, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .unwrap_or_default()
+}
+
+/// Print a binary numeric: base-10000 digits with the weight of the first one, a sign and
+/// the number of decimal places to show.
+fn numeric_text(raw: &[u8]) -> Option<String> {
+    let word = |i: usize| {
+        raw.get(i * 2..i * 2 + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+    };
+    let ndigits = word(0)? as usize;
+    let weight = word(1)? as i16 as i64;
+    let sign = word(2)?;
+    let scale = word(3)? as usize;
+    let digits = (0..ndigits)
+        .map(|i| word(4 + i))
+        .collect::<Option<Vec<u16>>>()?;
+    match sign {
+        0xC000 => return Some("NaN".to_string()),
+        0xD000 => return Some("Infinity".to_string()),
+        0xF000 => return Some("-Infinity".to_string()),
+        _ => {}
+    }
+    let digit = |pos: i64| {
+        usize::try_from(pos)
+            .ok()
+            .and_then(|pos| digits.get(pos))
+            .copied()
+            .unwrap_or(0)
+    };
+
+    let mut text = String::new();
+    if sign == 0x4000 {
+        text.push('-');
+    }
+    if weight < 0 {
+        text.push('0');
+    } else {
+        text.push_str(&digit(0).to_string());
+        for pos in 1..=weight {
+            text.push_str(&format!("{:04}", digit(pos)));
+        }
+    }
+    if scale > 0 {
+        let mut fraction = String::new();
+        let mut pos = weight + 1;
+        while fraction.len() < scale {
+            fraction.push_str(&format!("{:04}", digit(pos)));
+            pos += 1;
+        }
+        fraction.truncate(scale);
+        text.push('.');
+        text.push_str(&fraction);
+    }
+    Some(text)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+impl DatabaseConnection for PostgresConnection {
+    fn connect(path: &str) -> Result<Box<Self>>
+    where
+        Self: Sized,
+    {
+        let client = Client::connect(path, NoTls)?;
+        Ok(Box::new(PostgresConnection {
+            client,
+            current_schema: None,
+        }))
+    }
+
+    fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+        let start = Instant::now();
+
+        // Split query into individual statements, keeping quoted text and trigger bodies whole
+        let statements = split_statements(query, &DatabaseType::PostgreSQL);
+
+        let mut last_result = QueryResult::new(Vec::new(), Vec::new());
+
+        for statement in &statements {
+            let stmt_text = statement.text.as_str();
+            last_result = match self.client.prepare(stmt_text) {
+                Ok(prepared) if prepared.columns().is_empty() => {
+                    let affected = self.client.execute(&prepared, &[])?;
+                    QueryResult::new(Vec::new(), Vec::new()).with_affected(affected as usize)
+                }
+                // Types that can't be read in binary below still come back as text
+                Ok(prepared) if !prepared.columns().iter().all(|c| reads_binary(c.type_())) => {
+                    let types: Vec<Type> = prepared
+                        .columns()
+                        .iter()
+                        .map(|c| c.type_().clone())
+                        .collect();
+                    let result = self.simple_query(stmt_text)?;
+                    let rows = result
+                        .rows
+                        .into_iter()
+                        .map(|row| {
+                            row.into_iter()
+                                .zip(&types)
+                                .map(|(value, t)| match value {
+                                    CellValue::Text(text) => to_cell_value(Some(&text), Some(t)),
+                                    other => other,
+                                })
+                                .collect()
+                        })
+                        .collect();
+                    QueryResult::new(result.columns, rows)
+                        .with_column_types(types.iter().map(|t| t.name().to_string()).collect())
+                }
+                Ok(prepared) => {
+                    let columns: Vec<String> = prepared
+                        .columns()
+                        .iter()
+                        .map(|c| c.name().to_string())
+                        .collect();
+                    let types: Vec<Type> = prepared
+                        .columns()
+                        .iter()
+                        .map(|c| c.type_().clone())
+                        .collect();
+                    let rows = self
+                        .client
+                        .query(&prepared, &[])?
+                        .iter()
+                        .map(|row| {
+                            (0..row.len())
+                                .map(|idx| Ok(from_binary(row.try_get(idx)?, &types[idx])))
+                                .collect::<Result<Vec<_>>>()
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    QueryResult::new(columns, rows)
+                        .with_column_types(types.iter().map(|t| t.name().to_string()).collect())
+                }
+                // Some statements can't be prepared; the simple protocol may still run them
+                Err(prepare_error) => {
+                    let prepare_error = anyhow!(prepare_error);
+                    self.simple_query(stmt_text).map_err(|error| {
+                        if format!("{:#}", error) == format!("{:#}", prepare_error) {
+                            error
+                        } else {
+                            anyhow!(
+                                "{:#} (preparing it failed with: {:#})",
+                                error,
+                                prepare_error
+                            )
+                        }
+                    })?
+                }
+            };
+        }
+
+        let elapsed = start.elapsed();
+        Ok(last_result.with_time(elapsed.as_millis() as u64))
+    }
+
+    fn execute_statement(&mut self, statement: &BoundStatement) -> Result<usize> {
+        // Every parameter is declared as text; the SQL casts it to the column type
+        let params: Vec<Option<String>> = statement
+            .params
+            .iter()
+            .map(|value| match value {
+                CellValue::Null => None,
+                CellValue::Blob(bytes) => Some(format!(
+                    "\\x{}",
+                    bytes
+                        .iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect::<String>()
+                )),
+                other => Some(other.to_string()),
+            })
+            .collect();
+        let types = vec![Type::TEXT; params.len()];
+        let prepared = self.client.prepare_typed(&statement.sql, &types)?;
+        let param_refs: Vec<&(dyn ToSql + Sync)> =
+            params.iter().map(|p| p as &(dyn ToSql + Sync)).collect();
+        Ok(self.client.execute(&prepared, &param_refs)? as usize)
+    }
+
+    fn list_tables(&mut self) -> Result<Vec<TableInfo>> {
+        self.tables(true)
+    }
+
+    fn table_names(&mut self) -> Result<Vec<String>> {
+        Ok(self.tables(false)?.into_iter().map(|t| t.name).collect())
+    }
+
+    fn get_table_columns(&mut self, table_name: &str) -> Result<Vec<ColumnInfo>> {
+        let schema = self
+            .current_schema
+            .clone()
+            .unwrap_or_else(|| "public".to_string());
+        let query = format!(
+            "SELECT c.column_name, c.data_type, c.is_nullable, \
+                    CASE WHEN pk.column_name IS NULL THEN 'f' ELSE 't' END \
+             FROM information_schema.columns c \
+             LEFT JOIN ( \
+                 SELECT kcu.column_name FROM information_schema.table_constraints tc \
+                 JOIN information_schema.key_column_usage kcu \
+                   ON tc.constraint_name = kcu.constraint_name \
+                  AND tc.table_schema = kcu.table_schema \
+                 WHERE tc.constraint_type = 'PRIMARY KEY' \
+                   AND tc.table_schema = {schema} AND tc.table_name = {table} \
+             ) pk ON pk.column_name = c.column_name \
+             WHERE c.table_schema = {schema} AND c.table_name = {table} \
+             ORDER BY c.ordinal_position",
+            schema = quote_literal(&schema),
+            table = quote_literal(table_name)
+        );
+
+        let columns = self
+            .query_strings(&query)?
+            .into_iter()
+            .map(|row| {
+                let field = |idx: usize| row.get(idx).cloned().flatten().unwrap_or_default();
+                ColumnInfo {
+                    name: field(0),
+                    data_type: field(1),
+                    nullable: field(2) == "YES",
+                    primary_key: field(3) == "t",
+                }
+            })
+            .collect();
+
+        Ok(columns)
+    }
+
+    fn get_table_data(
+        &mut self,
+        table_name: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<QueryResult> {
+        let qualified_table_name = self.qualified_table_name(table_name)?;
+        let query = format!(
+            "SELECT * FROM {} LIMIT {} OFFSET {}",
+            qualified_table_name, limit, offset
+        );
+        self.execute_query(&query)
+    }
+
+    fn stream_table(&mut self, table_name: &str, sink: &mut dyn RowSink) -> Result<usize> {
+        let query = format!("SELECT * FROM {}", self.qualified_table_name(table_name)?);
+        let prepared = self.client.prepare(&query)?;
+        let column_names: Vec<String> = prepared
+            .columns()
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect();
+        let types: Vec<Type> = prepared
+            .columns()
+            .iter()
+            .map(|c| c.type_().clone())
+            .collect();
+        sink.columns(
+            &column_names,
+            &types
+                .iter()
+                .map(|t| t.name().to_string())
+                .collect::<Vec<_>>(),
+        )?;
+
+        // Fetch through a cursor so the whole table never sits in memory; dropping the
+        // transaction on error rolls it back and closes the cursor
+        let mut transaction = self.client.transaction()?;
+        transaction.batch_execute(&format!(
+            "DECLARE tui_db_export NO SCROLL CURSOR FOR {}",
+            query
+        ))?;
+        let mut count = 0;
+        loop {
+            let mut fetched = 0;
+            for message in transaction.simple_query("FETCH 1000 FROM tui_db_export")? {
+                if let SimpleQueryMessage::Row(row) = message {
+                    let values: Vec<CellValue> = (0..row.len())
+                        .map(|idx| to_cell_value(row.get(idx), types.get(idx)))
+                        .collect();
+                    sink.row(&values)?;
+                    fetched += 1;
+                }
+            }
+            if fetched == 0 {
+                break;
+            }
+            count += fetched;
+        }
+        transaction.commit()?;
+        Ok(count)
+    }
+
+    fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn query_canceller(&self) -> Box<dyn QueryCanceller> {
+        Box::new(PostgresCanceller(self.client.cancel_token()))
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numeric(weight: i16, sign: u16, scale: u16, digits: &[u16]) -> Vec<u8> {
+        let mut raw = Vec::new();
+        for word in [digits.len() as u16, weight as u16, sign, scale]
+            .iter()
+            .chain(digits)
+        {
+            raw.extend_from_slice(&word.to_be_bytes());
+        }
+        raw
+    }
+
+    #[test]
+    fn prints_binary_numerics() {
+        assert_eq!(numeric_text(&numeric(0, 0, 0, &[])).unwrap(), "0");
+        assert_eq!(numeric_text(&numeric(0, 0, 3, &[])).unwrap(), "0.000");
+        assert_eq!(
+            numeric_text(&numeric(1, 0, 4, &[1234, 5678, 9012])).unwrap(),
+            "12345678.9012"
+        );
+        assert_eq!(
+            numeric_text(&numeric(-1, 0x4000, 5, &[1, 2000])).unwrap(),
+            "-0.00012"
+        );
+        assert_eq!(numeric_text(&numeric(2, 0, 0, &[1])).unwrap(), "100000000");
+        assert_eq!(
+            numeric_text(&numeric(0, 0, 3, &[123, 4500])).unwrap(),
+            "123.450"
+        );
+        assert_eq!(numeric_text(&numeric(0, 0xC000, 0, &[])).unwrap(), "NaN");
+        assert!(numeric_text(&[0, 1]).is_none());
+    }
+
+    #[test]
+    fn decodes_binary_values() {
+        let value = |raw: &[u8], t: &Type| from_binary(Some(RawValue(raw)), t);
+        assert_eq!(
+            value(&(-2i16).to_be_bytes(), &Type::INT2),
+            CellValue::Integer(-2)
+        );
+        assert_eq!(
+            value(&u32::MAX.to_be_bytes(), &Type::OID),
+            CellValue::Integer(u32::MAX as i64)
+        );
+        assert_eq!(
+            value(&1.5f32.to_be_bytes(), &Type::FLOAT4),
+            CellValue::Real(1.5)
+        );
+        assert_eq!(value(&[1], &Type::BOOL), CellValue::Text("t".to_string()));
+        assert_eq!(
+            value(b"\x01{}", &Type::JSONB),
+            CellValue::Text("{}".to_string())
+        );
+        assert_eq!(
+            value(&59i32.to_be_bytes(), &Type::DATE),
+            CellValue::Date(NaiveDate::from_ymd_opt(2000, 2, 29).unwrap())
+        );
+        assert_eq!(
+            value(&i64::MAX.to_be_bytes(), &Type::TIMESTAMP),
+            CellValue::Text("infinity".to_string())
+        );
+        assert_eq!(
+            value(&3_600_500_000i64.to_be_bytes(), &Type::TIMESTAMPTZ),
+            CellValue::Text("2000-01-01 01:00:00.500+00".to_string())
+        );
+        assert_eq!(
+            value(&[0xab; 16], &Type::UUID),
+            CellValue::Text("abababab-abab-abab-abab-abababababab".to_string())
+        );
+        assert_eq!(from_binary(None, &Type::TEXT), CellValue::Null);
+    }
+}
@@ -1,36 +1,58 @@
 use anyhow::{Context, Result};
-use rusqlite::{Connection, Row};
+use rusqlite::{
+    params_from_iter,
+    types::{Value, ValueRef},
+    Connection, InterruptHandle, Row,
+};
 use std::time::Instant;
 
-use super::connection::{ColumnInfo, DatabaseConnection, QueryResult, TableInfo};
+use super::connection::{
+    BoundStatement, CellValue, ColumnInfo, DatabaseConnection, DatabaseType, QueryCanceller,
+    QueryResult, RowSink, TableInfo,
+};
+use crate::sql::split_statements;
 
 pub struct SQLiteConnection {
     conn: Connection,
 }
 
+struct SQLiteCanceller(InterruptHandle);
+
+impl QueryCanceller for SQLiteCanceller {
+    fn cancel(&self) -> Result<()> {
+        // sqlite3_interrupt makes the running statement fail with SQLITE_INTERRUPT
+        self.0.interrupt();
+        Ok(())
+    }
+}
+
 impl SQLiteConnection {
-    fn row_to_strings(row: &Row, column_count: usize) -> Result<Vec<String>> {
+    fn row_to_values(row: &Row, column_count: usize) -> Result<Vec<CellValue>> {
         let mut values = Vec::new();
         for i in 0..column_count {
-            let value: Result<String, rusqlite::Error> = row.get(i);
-            match value {
-                Ok(v) => values.push(v),
-                Err(_) => {
-                    // Try other types
-                    if let Ok(v) = row.get::<_, i64>(i) {
-                        values.push(v.to_string());
-                    } else if let Ok(v) = row.get::<_, f64>(i) {
-                        values.push(v.to_string());
-                    } else if let Ok(v) = row.get::<_, Vec<u8>>(i) {
-                        values.push(format!("<BLOB: {} bytes>", v.len()));
-                    } else {
-                        values.push("NULL".to_string());
-                    }
-                }
-            }
+            // SQLite values carry their own storage class, whatever the declared type
+            let value = match row.get_ref(i)? {
+                ValueRef::Null => CellValue::Null,
+                ValueRef::Integer(v) => CellValue::Integer(v),
+                ValueRef::Real(v) => CellValue::Real(v),
+                ValueRef::Text(v) => CellValue::Text(String::from_utf8_lossy(v).to_string()),
+                ValueRef::Blob(v) => CellValue::Blob(v.to_vec()),
+            };
+            values.push(value);
         }
         Ok(values)
     }
+
+    fn to_sql_value(value: &CellValue) -> Value {
+        match value {
+            CellValue::Null => Value::Null,
+            CellValue::Integer(v) => Value::Integer(*v),
+            CellValue::Real(v) => Value::Real(*v),
+            CellValue::Blob(v) => Value::Blob(v.clone()),
+            // SQLite has no date or decimal storage class, they are stored as text
+            other => Value::Text(other.to_string()),
+        }
+    }
 }
 
 impl DatabaseConnection for SQLiteConnection {
@@ -43,12 +65,8 @@ impl DatabaseConnection for SQLiteConnection {
     fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
         let start = Instant::now();
 
-        // Split query into individual statements
-        let statements: Vec<&str> = query
-            .split(';')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .collect();
+        // Split query into individual statements, keeping quoted text and trigger bodies whole
+        let statements = split_statements(query, &DatabaseType::SQLite);
 
         if statements.is_empty() {
             return Ok(QueryResult::new(vec![], vec![]).with_time(0));
@@ -58,22 +76,28 @@ impl DatabaseConnection for SQLiteConnection {
         let mut total_affected = 0;
 
         // Execute each statement
-        for stmt_text in statements {
+        for statement in &statements {
+            let stmt_text = statement.text.as_str();
             let trimmed = stmt_text.trim().to_uppercase();
 
             if trimmed.starts_with("SELECT") || trimmed.starts_with("PRAGMA") {
                 let mut stmt = self.conn.prepare(stmt_text)?;
                 let column_names: Vec<String> =
                     stmt.column_names().iter().map(|s| s.to_string()).collect();
+                let column_types: Vec<String> = stmt
+                    .columns()
+                    .iter()
+                    .map(|c| c.decl_type().unwrap_or_default().to_string())
+                    .collect();
                 let column_count = column_names.len();
 
                 let rows = stmt
-                    .query_map([], |row| Ok(Self::row_to_strings(row, column_count)))?
+                    .query_map([], |row| Ok(Self::row_to_values(row, column_count)))?
                     .collect::<Result<Vec<_>, _>>()?
                     .into_iter()
                     .collect::<Result<Vec<_>>>()?;
 
-                last_result = QueryResult::new(column_names, rows);
+                last_result = QueryResult::new(column_names, rows).with_column_types(column_types);
             } else {
                 let affected = self.conn.execute(stmt_text, [])?;
                 total_affected += affected;
@@ -91,20 +115,16 @@ impl DatabaseConnection for SQLiteConnection {
         Ok(last_result.with_time(elapsed))
     }
 
-    fn list_tables(&mut self) -> Result<Vec<TableInfo>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' ORDER BY name"
-        )?;
-
-        let tables = stmt
-            .query_map([], |row| {
-                let name: String = row.get(0)?;
-                Ok(name)
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+    fn execute_statement(&mut self, statement: &BoundStatement) -> Result<usize> {
+        let params = statement.params.iter().map(Self::to_sql_value);
+        Ok(self
+            .conn
+            .execute(&statement.sql, params_from_iter(params))?)
+    }
 
+    fn list_tables(&mut self) -> Result<Vec<TableInfo>> {
         let mut table_infos = Vec::new();
-        for table in tables {
+        for table in self.table_names()? {
             let count: Result<usize, _> =
                 self.conn
                     .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
@@ -119,6 +139,20 @@ impl DatabaseConnection for SQLiteConnection {
         Ok(table_infos)
     }
 
+    fn table_names(&mut self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' ORDER BY name"
+        )?;
+
+        let tables = stmt
+            .query_map([], |row| {
+                let name: String = row.get(0)?;
+                Ok(name)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(tables)
+    }
+
     fn get_table_columns(&mut self, table_name: &str) -> Result<Vec<ColumnInfo>> {
         let mut stmt = self
             .conn
@@ -144,6 +178,27 @@ impl DatabaseConnection for SQLiteConnection {
         limit: usize,
         offset: usize,
     ) -> Result<QueryResult> {
+        // Fetch the rowid too, so rows of tables without a primary key can still be edited.
+        // Views and WITHOUT ROWID tables have none and fall back to a plain SELECT.
+        let rowid_query = format!(
+            "SELECT rowid, * FROM {} LIMIT {} OFFSET {}",
+            table_name, limit, offset
+        );
+        if let Ok(mut result) = self.execute_query(&rowid_query) {
+            let rowids = result
+                .rows
+                .iter_mut()
+                .map(|row| match row.remove(0) {
+                    CellValue::Integer(rowid) => Some(rowid),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>();
+            result.columns.remove(0);
+            result.column_types.remove(0);
+            result.rowids = rowids;
+            return Ok(result);
+        }
+
         let query = format!(
             "SELECT * FROM {} LIMIT {} OFFSET {}",
             table_name, limit, offset
@@ -151,10 +206,40 @@ impl DatabaseConnection for SQLiteConnection {
         self.execute_query(&query)
     }
 
+    fn stream_table(&mut self, table_name: &str, sink: &mut dyn RowSink) -> Result<usize> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT * FROM {}", table_name))?;
+        let column_names: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
+        let column_types: Vec<String> = stmt
+            .columns()
+            .iter()
+            .map(|c| c.decl_type().unwrap_or_default().to_string())
+            .collect();
+        let column_count = column_names.len();
+        sink.columns(&column_names, &column_types)?;
+
+        let mut rows = stmt.query([])?;
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            sink.row(&Self::row_to_values(row, column_count)?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
     fn close(&mut self) -> Result<()> {
         Ok(())
     }
 
+    fn query_canceller(&self) -> Box<dyn QueryCanceller> {
+        Box::new(SQLiteCanceller(self.conn.get_interrupt_handle()))
+    }
+
+    fn in_transaction(&self) -> Option<bool> {
+        Some(!self.conn.is_autocommit())
+    }
+
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
@@ -0,0 +1,94 @@
+use crate::db::{CellValue, DatabaseType, RowSink};
+use anyhow::Result;
+use std::io::Write;
+
+/// Rows per INSERT statement, as in mysqldump's extended inserts.
+const INSERT_BATCH_ROWS: usize = 100;
+
+/// What `:dump` writes for each table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DumpOptions {
+    pub schema: bool,
+    pub data: bool,
+}
+
+/// Statements that open a dump script, so it loads in one go regardless of table order.
+pub fn preamble(dialect: &DatabaseType) -> &'static str {
+    match dialect {
+        DatabaseType::SQLite => "PRAGMA foreign_keys=OFF;\nBEGIN TRANSACTION;\n",
+        DatabaseType::MySQL | DatabaseType::MariaDB => "SET FOREIGN_KEY_CHECKS=0;\n",
+        DatabaseType::PostgreSQL => "BEGIN;\n",
+    }
+}
+
+pub fn postamble(dialect: &DatabaseType) -> &'static str {
+    match dialect {
+        DatabaseType::SQLite | DatabaseType::PostgreSQL => "COMMIT;\n",
+        DatabaseType::MySQL | DatabaseType::MariaDB => "SET FOREIGN_KEY_CHECKS=1;\n",
+    }
+}
+
+/// Writes a table's rows as multi-row INSERT statements.
+pub struct InsertWriter<'a, W: Write> {
+    out: &'a mut W,
+    table_name: String,
+    dialect: DatabaseType,
+    columns: String,      // Quoted column list
+    pending: Vec<String>, // Value tuples not written yet
+    rows: usize,
+}
+
+impl<'a, W: Write> InsertWriter<'a, W> {
+    pub fn new(out: &'a mut W, table_name: &str, dialect: DatabaseType) -> Self {
+        Self {
+            out,
+            table_name: table_name.to_string(),
+            dialect,
+            columns: String::new(),
+            pending: Vec::new(),
+            rows: 0,
+        }
+    }
+
+    /// Write the last partial batch, returning the number of rows written.
+    pub fn finish(mut self) -> Result<usize> {
+        self.flush_batch()?;
+        Ok(self.rows)
+    }
+
+    fn flush_batch(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        writeln!(
+            self.out,
+            "INSERT INTO {} ({}) VALUES\n  {};",
+            self.dialect.quote_identifier(&self.table_name),
+            self.columns,
+            self.pending.join(",\n  ")
+        )?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> RowSink for InsertWriter<'_, W> {
+    fn columns(&mut self, columns: &[String], _column_types: &[String]) -> Result<()> {
+        let quoted: Vec<String> = columns
+            .iter()
+            .map(|c| self.dialect.quote_identifier(c))
+            .collect();
+        self.columns = quoted.join(", ");
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[CellValue]) -> Result<()> {
+        let values: Vec<String> = row.iter().map(|v| self.dialect.literal(v)).collect();
+        self.pending.push(format!("({})", values.join(", ")));
+        self.rows += 1;
+        if self.pending.len() >= INSERT_BATCH_ROWS {
+            self.flush_batch()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use crate::db::{CellValue, DatabaseType, RowSink};
+use anyhow::{anyhow, Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    Json,
+    Ndjson,
+    Markdown,
+    Sql,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "tsv" => Ok(ExportFormat::Tsv),
+            "json" => Ok(ExportFormat::Json),
+            "ndjson" | "jsonl" => Ok(ExportFormat::Ndjson),
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            "sql" => Ok(ExportFormat::Sql),
+            _ => Err(anyhow!(
+                "Unknown export format {}, expected csv, tsv, json, ndjson, markdown or sql",
+                name
+            )),
+        }
+    }
+}
+
+/// Expand a leading `~/` to the home directory.
+pub fn expand_path(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Writes rows to a file in one of the export formats as they are received.
+pub struct ExportWriter<W: Write> {
+    out: W,
+    format: ExportFormat,
+    columns: Vec<String>,
+    table_name: String,    // INSERT target for the SQL format
+    dialect: DatabaseType, // Identifier quoting and literals for the SQL format
+    rows: usize,
+}
+
+impl ExportWriter<BufWriter<File>> {
+    pub fn create(
+        path: &PathBuf,
+        format: ExportFormat,
+        table_name: &str,
+        dialect: DatabaseType,
+    ) -> Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        Ok(Self::new(BufWriter::new(file), format, table_name, dialect))
+    }
+}
+
+impl<W: Write> ExportWriter<W> {
+    pub fn new(out: W, format: ExportFormat, table_name: &str, dialect: DatabaseType) -> Self {
+        Self {
+            out,
+            format,
+            columns: Vec::new(),
+            table_name: table_name.to_string(),
+            dialect,
+            rows: 0,
+        }
+    }
+
+    /// Write any closing syntax and flush, returning the number of rows written.
+    pub fn finish(mut self) -> Result<usize> {
+        if self.format == ExportFormat::Json {
+            if self.rows == 0 {
+                write!(self.out, "[")?;
+            }
+            writeln!(self.out, "\n]")?;
+        }
+        self.out.flush()?;
+        Ok(self.rows)
+    }
+
+    fn json_object(&self, row: &[CellValue]) -> String {
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .zip(row)
+            .map(|(column, value)| format!("{}:{}", json_string(column), json_value(value)))
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+impl<W: Write> RowSink for ExportWriter<W> {
+    fn columns(&mut self, columns: &[String], _column_types: &[String]) -> Result<()> {
+        self.columns = columns.to_vec();
+        match self.format {
+            ExportFormat::Csv => {
+                let header: Vec<String> = columns.iter().map(|c| csv_field(c)).collect();
+                write!(self.out, "{}\r\n", header.join(","))?;
+            }
+            ExportFormat::Tsv => {
+                let header: Vec<String> = columns.iter().map(|c| tsv_field(c)).collect();
+                writeln!(self.out, "{}", header.join("\t"))?;
+            }
+            ExportFormat::Markdown => {
+                let header: Vec<String> = columns.iter().map(|c| markdown_field(c)).collect();
+                writeln!(self.out, "| {} |", header.join(" | "))?;
+                writeln!(self.out, "|{}", "---|".repeat(columns.len()))?;
+            }
+            ExportFormat::Json | ExportFormat::Ndjson | ExportFormat::Sql => {}
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[CellValue]) -> Result<()> {
+        match self.format {
+            ExportFormat::Csv => {
+                // NULL is an empty field, an empty string is quoted so the two stay apart
+                let fields: Vec<String> = row
+                    .iter()
+                    .map(|value| match value {
+                        CellValue::Null => String::new(),
+                        CellValue::Text(s) if s.is_empty() => "\"\"".to_string(),
+                        other => csv_field(&export_text(other)),
+                    })
+                    .collect();
+                write!(self.out, "{}\r\n", fields.join(","))?;
+            }
+            ExportFormat::Tsv => {
+                // \N for NULL, as PostgreSQL COPY and MySQL LOAD DATA read it
+                let fields: Vec<String> = row
+                    .iter()
+                    .map(|value| match value {
+                        CellValue::Null => "\\N".to_string(),
+                        other => tsv_field(&export_text(other)),
+                    })
+                    .collect();
+                writeln!(self.out, "{}", fields.join("\t"))?;
+            }
+            ExportFormat::Json => {
+                let separator = if self.rows == 0 { "[\n  " } else { ",\n  " };
+                let object = self.json_object(row);
+                write!(self.out, "{}{}", separator, object)?;
+            }
+            ExportFormat::Ndjson => {
+                let object = self.json_object(row);
+                writeln!(self.out, "{}", object)?;
+            }
+            ExportFormat::Markdown => {
+                let fields: Vec<String> = row
+                    .iter()
+                    .map(|value| match value {
+                        CellValue::Null => "*NULL*".to_string(),
+                        other => markdown_field(&export_text(other)),
+                    })
+                    .collect();
+                writeln!(self.out, "| {} |", fields.join(" | "))?;
+            }
+            ExportFormat::Sql => {
+                let columns: Vec<String> = self
+                    .columns
+                    .iter()
+                    .map(|c| self.dialect.quote_identifier(c))
+                    .collect();
+                let values: Vec<String> = row.iter().map(|v| self.dialect.literal(v)).collect();
+                writeln!(
+                    self.out,
+                    "INSERT INTO {} ({}) VALUES ({});",
+                    self.dialect.quote_identifier(&self.table_name),
+                    columns.join(", "),
+                    values.join(", ")
+                )?;
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+}
+
+/// Text form of a value for the text formats; blobs are written as hex.
+fn export_text(value: &CellValue) -> String {
+    match value {
+        CellValue::Blob(bytes) => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+        other => other.to_string(),
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn tsv_field(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn markdown_field(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+fn json_string(text: &str) -> String {
+    serde_json::Value::String(text.to_string()).to_string()
+}
+
+fn json_value(value: &CellValue) -> String {
+    match value {
+        CellValue::Null => "null".to_string(),
+        CellValue::Integer(i) => i.to_string(),
+        // NaN and infinity have no JSON form
+        CellValue::Real(r) if r.is_finite() => r.to_string(),
+        CellValue::Real(_) => "null".to_string(),
+        // Decimals stay strings so no precision is lost by JSON readers
+        other => json_string(&export_text(other)),
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::{anyhow, Context, Result};
+use std::io::Write;
+use std::process::Command;
+
+/// What an external edit replaces when the editor exits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditTarget {
+    Query,
+    Cell { row: usize, column: usize }, // Grid cell, saved with the other edits on `Ctrl+S`
+}
+
+/// Text to open in the user's editor. The app can't suspend the terminal itself, so it
+/// leaves this for the main loop to pick up.
+#[derive(Debug)]
+pub struct ExternalEdit {
+    pub target: EditTarget,
+    pub text: String,
+}
+
+/// `$VISUAL`, then `$EDITOR`, then `vi`, like git does.
+fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Write `text` to a temp file, open it in the editor and return the file's text once the
+/// editor exits. The terminal must already be handed over to the editor.
+pub fn edit(edit: &ExternalEdit) -> Result<String> {
+    let suffix = match edit.target {
+        EditTarget::Query => ".sql",
+        EditTarget::Cell { .. } => ".txt",
+    };
+    // A new file with a random name that only the user can read; removed when dropped
+    let mut file = tempfile::Builder::new()
+        .prefix("tui-db-")
+        .suffix(suffix)
+        .tempfile()
+        .context("Failed to create a temp file")?;
+    file.write_all(edit.text.as_bytes())
+        .and_then(|_| file.flush())
+        .with_context(|| format!("Failed to write {}", file.path().display()))?;
+
+    run_editor(file.path())?;
+    // Read by path: editors often save by replacing the file rather than writing into it
+    std::fs::read_to_string(file.path())
+        .with_context(|| format!("Failed to read {}", file.path().display()))
+}
+
+fn run_editor(path: &std::path::Path) -> Result<()> {
+    // Allow arguments such as `code --wait`
+    let command = editor_command();
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to start editor {}", program))?;
+    if !status.success() {
+        return Err(anyhow!(
+            "Editor {} exited with {}, text left unchanged",
+            program,
+            status
+        ));
+    }
+    Ok(())
+}
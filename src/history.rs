@@ -0,0 +1,79 @@
+use crate::config::Config;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Only the most recent entries are kept in memory; the file itself is append-only.
+const MAX_ENTRIES: usize = 5000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub query: String,
+    pub connection: String,
+    pub timestamp: DateTime<Local>,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows: Option<usize>,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Every query sent from the editor, stored one JSON object per line in
+/// `~/.config/tui-db/history.jsonl`.
+#[derive(Debug, Default)]
+pub struct QueryHistory {
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl QueryHistory {
+    pub fn load() -> Result<Self> {
+        let path = Self::history_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        // A line cut short by a crash shouldn't cost the rest of the history
+        let mut entries: Vec<HistoryEntry> = contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        if entries.len() > MAX_ENTRIES {
+            entries.drain(..entries.len() - MAX_ENTRIES);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Keep the entry and append it to the history file.
+    pub fn record(&mut self, entry: HistoryEntry) -> Result<()> {
+        let path = Self::history_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let line = serde_json::to_string(&entry)?;
+
+        self.entries.push(entry);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        writeln!(file, "{}", line)
+            .with_context(|| format!("Failed to write history to {}", path.display()))?;
+        Ok(())
+    }
+
+    fn history_path() -> Result<PathBuf> {
+        Ok(Config::config_dir()?.join("history.jsonl"))
+    }
+}
@@ -0,0 +1,683 @@
+use crate::db::{BoundStatement, CellValue, DatabaseConnection, DatabaseType};
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Upper bound on bound parameters per INSERT; SQLite allows 32766, PostgreSQL 65535.
+const MAX_PARAMS: usize = 30000;
+const MAX_BATCH_ROWS: usize = 500;
+
+/// A row of the file with the line it starts on, for error reports.
+#[derive(Debug, Clone)]
+pub struct ImportRow {
+    pub line: usize,
+    pub values: Vec<CellValue>,
+}
+
+/// A parsed CSV, TSV or JSON file. Rows that couldn't be read are kept in `bad_rows`.
+#[derive(Debug, Clone)]
+pub struct ImportFile {
+    pub path: PathBuf,
+    pub columns: Vec<String>,
+    pub rows: Vec<ImportRow>,
+    pub bad_rows: Vec<String>,
+}
+
+/// Header columns and the records of a file; unreadable records are described by line.
+type Parsed = (Vec<String>, Vec<Result<ImportRow, String>>);
+
+/// How a column's values are checked and bound; also the type of a newly created column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Integer,
+    Real,
+    Text,
+}
+
+impl ValueKind {
+    /// Classify a declared column type such as `BIGINT`, `numeric(10,2)` or `varchar(20)`.
+    pub fn from_type_name(type_name: &str) -> Self {
+        let upper = type_name.to_uppercase();
+        let integer =
+            upper.contains("INT") && !upper.contains("INTERVAL") && !upper.contains("POINT");
+        if integer || upper.ends_with("SERIAL") {
+            ValueKind::Integer
+        } else if ["REAL", "FLOA", "DOUB", "NUMERIC", "DECIMAL"]
+            .iter()
+            .any(|t| upper.contains(t))
+        {
+            ValueKind::Real
+        } else {
+            ValueKind::Text
+        }
+    }
+
+    pub fn sql_type(&self, dialect: &DatabaseType) -> &'static str {
+        match (self, dialect) {
+            (ValueKind::Integer, DatabaseType::SQLite) => "INTEGER",
+            (ValueKind::Integer, _) => "BIGINT",
+            (ValueKind::Real, DatabaseType::SQLite) => "REAL",
+            (ValueKind::Real, DatabaseType::PostgreSQL) => "DOUBLE PRECISION",
+            (ValueKind::Real, _) => "DOUBLE",
+            (ValueKind::Text, _) => "TEXT",
+        }
+    }
+
+    /// Convert a value read from the file, or explain why it doesn't fit.
+    fn convert(&self, value: &CellValue) -> Result<CellValue, String> {
+        match (self, value) {
+            (_, CellValue::Null) => Ok(CellValue::Null),
+            (ValueKind::Integer, CellValue::Integer(_)) | (ValueKind::Real, CellValue::Real(_)) => {
+                Ok(value.clone())
+            }
+            (ValueKind::Real, CellValue::Integer(i)) => Ok(CellValue::Real(*i as f64)),
+            (ValueKind::Integer, other) => other
+                .to_string()
+                .trim()
+                .parse::<i64>()
+                .map(CellValue::Integer)
+                .map_err(|_| format!("'{}' is not an integer", other)),
+            (ValueKind::Real, other) => other
+                .to_string()
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|f| f.is_finite())
+                .map(CellValue::Real)
+                .ok_or_else(|| format!("'{}' is not a number", other)),
+            (ValueKind::Text, other) => Ok(CellValue::Text(other.to_string())),
+        }
+    }
+}
+
+impl ImportFile {
+    /// Read a file, picking the format from its extension: `.tsv`/`.tab`, `.json`,
+    /// `.ndjson`/`.jsonl`, anything else is read as CSV.
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        let (columns, records) = match extension.as_str() {
+            "tsv" | "tab" => parse_tsv(&text),
+            "json" if text.trim_start().starts_with('[') => parse_json_array(&text)?,
+            "json" | "ndjson" | "jsonl" => parse_ndjson(&text),
+            _ => parse_csv(&text)?,
+        };
+
+        let mut file = ImportFile {
+            path: path.to_path_buf(),
+            columns,
+            rows: Vec::new(),
+            bad_rows: Vec::new(),
+        };
+        for record in records {
+            match record {
+                Ok(row) if row.values.len() == file.columns.len() => file.rows.push(row),
+                Ok(row) => file.bad_rows.push(format!(
+                    "line {}: expected {} fields, found {}",
+                    row.line,
+                    file.columns.len(),
+                    row.values.len()
+                )),
+                Err(problem) => file.bad_rows.push(problem),
+            }
+        }
+        if file.columns.is_empty() {
+            return Err(anyhow!("{} has no header or columns", path.display()));
+        }
+        Ok(file)
+    }
+
+    /// The narrowest kind that holds every non-NULL value of a column. Numbers written
+    /// with a leading zero, such as ZIP codes or phone numbers, keep the column as text.
+    pub fn infer_kind(&self, column: usize) -> ValueKind {
+        let mut kind = ValueKind::Integer;
+        for row in &self.rows {
+            let value = &row.values[column];
+            if value.is_null() {
+                continue;
+            }
+            if matches!(value, CellValue::Text(text) if has_leading_zero(text)) {
+                return ValueKind::Text;
+            }
+            if kind == ValueKind::Integer && ValueKind::Integer.convert(value).is_err() {
+                kind = ValueKind::Real;
+            }
+            if kind == ValueKind::Real && ValueKind::Real.convert(value).is_err() {
+                return ValueKind::Text;
+            }
+        }
+        kind
+    }
+}
+
+/// `02134` or `-007`, but not `0` or `0.5`.
+pub fn has_leading_zero(text: &str) -> bool {
+    let digits = text.trim().trim_start_matches(['+', '-']);
+    digits.starts_with('0') && digits[1..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// CSV as in RFC 4180: quoted fields may hold commas, doubled quotes and line breaks.
+/// An empty unquoted field is NULL, `""` is an empty string.
+fn parse_csv(text: &str) -> Result<Parsed> {
+    let mut records: Vec<ImportRow> = Vec::new();
+    let mut values: Vec<CellValue> = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false; // The current field started with a quote
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = text.chars().peekable();
+
+    fn end_field(field: &mut String, quoted: &mut bool, values: &mut Vec<CellValue>) {
+        if field.is_empty() && !*quoted {
+            values.push(CellValue::Null);
+        } else {
+            values.push(CellValue::Text(std::mem::take(field)));
+        }
+        *quoted = false;
+    }
+
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(ch);
+                }
+                _ => field.push(ch),
+            }
+            continue;
+        }
+        match ch {
+            '"' if field.is_empty() && !quoted => {
+                quoted = true;
+                in_quotes = true;
+            }
+            ',' => end_field(&mut field, &mut quoted, &mut values),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                end_field(&mut field, &mut quoted, &mut values);
+                // Blank lines are skipped rather than read as a row of one NULL
+                if !(values.len() == 1 && values[0].is_null()) {
+                    records.push(ImportRow {
+                        line: record_line,
+                        values: std::mem::take(&mut values),
+                    });
+                }
+                values.clear();
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(ch),
+        }
+    }
+    if in_quotes {
+        return Err(anyhow!(
+            "Unterminated quoted field starting on line {}",
+            record_line
+        ));
+    }
+    if !field.is_empty() || quoted || !values.is_empty() {
+        end_field(&mut field, &mut quoted, &mut values);
+        records.push(ImportRow {
+            line: record_line,
+            values,
+        });
+    }
+
+    let mut records = records.into_iter();
+    let columns = records
+        .next()
+        .map(|header| {
+            header
+                .values
+                .iter()
+                .map(|v| {
+                    if v.is_null() {
+                        String::new()
+                    } else {
+                        v.to_string()
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok((columns, records.map(Ok).collect()))
+}
+
+/// Tab-separated values with backslash escapes and `\N` for NULL, as written by
+/// `:export tsv`, PostgreSQL COPY and MySQL SELECT INTO OUTFILE.
+fn parse_tsv(text: &str) -> Parsed {
+    let unescape = |field: &str| -> CellValue {
+        if field == "\\N" {
+            return CellValue::Null;
+        }
+        let mut value = String::with_capacity(field.len());
+        let mut chars = field.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '\\' {
+                value.push(ch);
+                continue;
+            }
+            match chars.next() {
+                Some('t') => value.push('\t'),
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some(other) => value.push(other),
+                None => value.push('\\'),
+            }
+        }
+        CellValue::Text(value)
+    };
+
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty());
+    let columns = lines
+        .next()
+        .map(|(_, header)| header.split('\t').map(|c| c.to_string()).collect())
+        .unwrap_or_default();
+    let records = lines
+        .map(|(idx, line)| {
+            Ok(ImportRow {
+                line: idx + 1,
+                values: line.split('\t').map(unescape).collect(),
+            })
+        })
+        .collect();
+    (columns, records)
+}
+
+/// One JSON object per line.
+fn parse_ndjson(text: &str) -> Parsed {
+    let objects = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str::<Value>(line)
+                .map(|value| (idx + 1, value))
+                .map_err(|err| format!("line {}: {}", idx + 1, err))
+        })
+        .collect();
+    json_records(objects)
+}
+
+/// A JSON array of objects. Elements are read one at a time so each keeps its line number.
+fn parse_json_array(text: &str) -> Result<Parsed> {
+    let mut objects = Vec::new();
+    let mut pos = text.find('[').unwrap_or(0) + 1;
+    let mut line = 1 + text[..pos].matches('\n').count();
+
+    loop {
+        let skipped = text[pos..].len() - text[pos..].trim_start().len();
+        line += text[pos..pos + skipped].matches('\n').count();
+        pos += skipped;
+        if text[pos..].starts_with(']') {
+            break;
+        }
+
+        let mut stream = serde_json::Deserializer::from_str(&text[pos..]).into_iter::<Value>();
+        let value = match stream.next() {
+            Some(value) => value.with_context(|| format!("Invalid JSON on line {}", line))?,
+            None => return Err(anyhow!("Unterminated JSON array")),
+        };
+        let end = pos + stream.byte_offset();
+        objects.push(Ok((line, value)));
+        line += text[pos..end].matches('\n').count();
+        pos = end;
+
+        let skipped = text[pos..].len() - text[pos..].trim_start().len();
+        line += text[pos..pos + skipped].matches('\n').count();
+        pos += skipped;
+        match text[pos..].chars().next() {
+            Some(',') => pos += 1,
+            Some(']') => break,
+            _ => return Err(anyhow!("Expected , or ] on line {}", line)),
+        }
+    }
+    Ok(json_records(objects))
+}
+
+/// Columns are the keys of all objects, in order of first appearance.
+fn json_records(objects: Vec<Result<(usize, Value), String>>) -> Parsed {
+    let mut columns: Vec<String> = Vec::new();
+    for (_, value) in objects.iter().flatten() {
+        if let Value::Object(map) = value {
+            for key in map.keys() {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+    }
+
+    let records = objects
+        .into_iter()
+        .map(|object| {
+            let (line, value) = object?;
+            let map = match value {
+                Value::Object(map) => map,
+                _ => return Err(format!("line {}: expected a JSON object", line)),
+            };
+            let values = columns
+                .iter()
+                .map(|column| match map.get(column) {
+                    None | Some(Value::Null) => CellValue::Null,
+                    Some(Value::Bool(b)) => CellValue::Integer(*b as i64),
+                    Some(Value::Number(n)) => match (n.as_i64(), n.as_f64()) {
+                        (Some(i), _) => CellValue::Integer(i),
+                        (None, Some(f)) if !n.is_u64() => CellValue::Real(f),
+                        _ => CellValue::Decimal(n.to_string()),
+                    },
+                    Some(Value::String(s)) => CellValue::Text(s.clone()),
+                    Some(other) => CellValue::Text(other.to_string()),
+                })
+                .collect();
+            Ok(ImportRow { line, values })
+        })
+        .collect();
+    (columns, records)
+}
+
+/// CREATE TABLE for a new table holding the imported columns.
+pub fn create_table_sql(
+    table_name: &str,
+    columns: &[(String, ValueKind)],
+    dialect: &DatabaseType,
+) -> String {
+    let definitions: Vec<String> = columns
+        .iter()
+        .map(|(name, kind)| {
+            format!(
+                "{} {}",
+                dialect.quote_identifier(name),
+                kind.sql_type(dialect)
+            )
+        })
+        .collect();
+    format!(
+        "CREATE TABLE {} ({})",
+        dialect.quote_identifier(table_name),
+        definitions.join(", ")
+    )
+}
+
+/// A multi-row INSERT and the file lines it covers.
+#[derive(Debug)]
+pub struct ImportBatch {
+    pub statement: BoundStatement,
+    pub rows: usize,
+    pub first_line: usize,
+    pub last_line: usize,
+}
+
+/// One entry per imported column: position in the file, target column, its type and
+/// declared type name (for PostgreSQL casts).
+pub struct ColumnTarget {
+    pub file_column: usize,
+    pub name: String,
+    pub kind: ValueKind,
+    pub type_name: String,
+}
+
+/// Convert every row and group them into batched INSERTs. Rows with values that don't
+/// fit their column are left out and described with their line number.
+pub fn build_batches(
+    file: &ImportFile,
+    table_name: &str,
+    targets: &[ColumnTarget],
+    dialect: &DatabaseType,
+) -> (Vec<ImportBatch>, Vec<String>) {
+    let mut bad_rows = Vec::new();
+    let mut rows: Vec<(usize, Vec<CellValue>)> = Vec::new();
+    for row in &file.rows {
+        let converted: Result<Vec<CellValue>, String> = targets
+            .iter()
+            .map(|target| {
+                target
+                    .kind
+                    .convert(&row.values[target.file_column])
+                    .map_err(|problem| format!("{}: {}", target.name, problem))
+            })
+            .collect();
+        match converted {
+            Ok(values) => rows.push((row.line, values)),
+            Err(problem) => bad_rows.push(format!("line {}: {}", row.line, problem)),
+        }
+    }
+
+    let column_list: Vec<String> = targets
+        .iter()
+        .map(|t| dialect.quote_identifier(&t.name))
+        .collect();
+    let batch_rows = (MAX_PARAMS / targets.len().max(1)).clamp(1, MAX_BATCH_ROWS);
+
+    let mut batches = Vec::new();
+    for chunk in rows.chunks(batch_rows) {
+        let mut params = Vec::with_capacity(chunk.len() * targets.len());
+        let mut tuples = Vec::with_capacity(chunk.len());
+        for (_, values) in chunk {
+            let placeholders: Vec<String> = values
+                .iter()
+                .zip(targets)
+                .map(|(value, target)| {
+                    params.push(value.clone());
+                    dialect.placeholder(params.len(), &target.type_name)
+                })
+                .collect();
+            tuples.push(format!("({})", placeholders.join(", ")));
+        }
+        batches.push(ImportBatch {
+            statement: BoundStatement {
+                sql: format!(
+                    "INSERT INTO {} ({}) VALUES {}",
+                    dialect.quote_identifier(table_name),
+                    column_list.join(", "),
+                    tuples.join(", ")
+                ),
+                params,
+                expected_rows: None,
+            },
+            rows: chunk.len(),
+            first_line: chunk[0].0,
+            last_line: chunk[chunk.len() - 1].0,
+        });
+    }
+    (batches, bad_rows)
+}
+
+/// An import ready to hand to a worker: the INSERT batches run inside the transaction
+/// `start_import` opened, and are committed once the last one is in.
+#[derive(Debug)]
+pub struct ImportJob {
+    pub table_name: String,
+    pub batches: Vec<ImportBatch>,
+    pub bad_rows: Vec<String>,
+    pub drop_table: Option<String>, // DROP for a table created outside the transaction
+}
+
+impl ImportJob {
+    pub fn total_rows(&self) -> usize {
+        self.batches.iter().map(|b| b.rows).sum()
+    }
+
+    /// Undo an import that didn't complete: roll back its transaction and drop the table it
+    /// created outside of it. The error says what was left behind.
+    pub fn abort(&self, conn: &mut dyn DatabaseConnection) -> Result<()> {
+        // SQLite rolls the transaction back itself when a statement is interrupted
+        let rollback = match conn.in_transaction() {
+            Some(false) => Ok(()),
+            _ => conn.execute_query("ROLLBACK").map(|_| ()),
+        };
+        let dropped = match &self.drop_table {
+            Some(sql) => conn.execute_query(sql).map(|_| ()),
+            None => Ok(()),
+        };
+        match (rollback, dropped) {
+            (Ok(()), Ok(())) => Ok(()),
+            // Dropping the table takes the rows in it along
+            (Err(_), Ok(())) if self.drop_table.is_some() => Ok(()),
+            (Err(err), _) => Err(err.context(format!(
+                "the rows imported so far into {} may not have been rolled back",
+                self.table_name
+            ))),
+            (Ok(()), Err(err)) => Err(err.context(format!(
+                "the new table {} was left in place and is empty",
+                self.table_name
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> CellValue {
+        CellValue::Text(value.to_string())
+    }
+
+    fn file_with_column(values: &[CellValue]) -> ImportFile {
+        ImportFile {
+            path: PathBuf::from("test.csv"),
+            columns: vec!["a".to_string()],
+            rows: values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| ImportRow {
+                    line: i + 2,
+                    values: vec![value.clone()],
+                })
+                .collect(),
+            bad_rows: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn csv_quoted_fields_hold_newlines_and_doubled_quotes() {
+        let (columns, records) =
+            parse_csv("id,note\r\n1,\"say \"\"hi\"\"\nthere\"\n2,x\n").unwrap();
+        assert_eq!(columns, ["id", "note"]);
+        let rows: Vec<ImportRow> = records.into_iter().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].values, [text("1"), text("say \"hi\"\nthere")]);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[1].line, 4);
+    }
+
+    #[test]
+    fn csv_empty_field_is_null_and_empty_quotes_are_empty_string() {
+        let (_, records) = parse_csv("a,b,c\n,\"\",x\n\n").unwrap();
+        let rows: Vec<ImportRow> = records.into_iter().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].values, [CellValue::Null, text(""), text("x")]);
+    }
+
+    #[test]
+    fn csv_unterminated_quote_reports_its_line() {
+        let err = parse_csv("a,b\n1,2\n3,\"open\nstill open\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unterminated quoted field starting on line 3"
+        );
+    }
+
+    #[test]
+    fn json_array_keeps_line_numbers() {
+        let json = "[\n  {\"a\": 1},\n\n  {\"a\": 2,\n   \"b\": \"x\"},\n  3\n]";
+        let (columns, records) = parse_json_array(json).unwrap();
+        assert_eq!(columns, ["a", "b"]);
+        let first = records[0].as_ref().unwrap();
+        assert_eq!(first.line, 2);
+        assert_eq!(first.values, [CellValue::Integer(1), CellValue::Null]);
+        assert_eq!(records[1].as_ref().unwrap().line, 4);
+        assert_eq!(
+            records[2].as_ref().unwrap_err(),
+            "line 6: expected a JSON object"
+        );
+
+        let err = parse_json_array("[\n{\"a\": 1}\n{\"a\": 2}]").unwrap_err();
+        assert_eq!(err.to_string(), "Expected , or ] on line 3");
+    }
+
+    #[test]
+    fn infers_the_narrowest_kind() {
+        let kind = |values: &[CellValue]| file_with_column(values).infer_kind(0);
+        assert_eq!(
+            kind(&[text("1"), CellValue::Null, text("-3")]),
+            ValueKind::Integer
+        );
+        assert_eq!(kind(&[text("1"), text("2.5")]), ValueKind::Real);
+        assert_eq!(kind(&[text("0"), text("0.25")]), ValueKind::Real);
+        assert_eq!(kind(&[text("1"), text("abc")]), ValueKind::Text);
+        assert_eq!(kind(&[text("10"), text("02134")]), ValueKind::Text);
+        assert_eq!(kind(&[text("-007")]), ValueKind::Text);
+        assert_eq!(kind(&[text("1.5"), text("inf")]), ValueKind::Text);
+        assert_eq!(kind(&[text("NaN")]), ValueKind::Text);
+        assert_eq!(kind(&[CellValue::Null]), ValueKind::Integer);
+    }
+
+    #[test]
+    fn batches_stay_under_the_parameter_limit() {
+        let columns = 100;
+        let file = ImportFile {
+            path: PathBuf::from("wide.csv"),
+            columns: (0..columns).map(|i| format!("c{}", i)).collect(),
+            rows: (0..701)
+                .map(|i| ImportRow {
+                    line: i + 2,
+                    values: vec![text("1"); columns],
+                })
+                .collect(),
+            bad_rows: Vec::new(),
+        };
+        let targets: Vec<ColumnTarget> = (0..columns)
+            .map(|i| ColumnTarget {
+                file_column: i,
+                name: format!("c{}", i),
+                kind: ValueKind::Integer,
+                type_name: "INTEGER".to_string(),
+            })
+            .collect();
+        let (batches, bad_rows) = build_batches(&file, "t", &targets, &DatabaseType::SQLite);
+        assert!(bad_rows.is_empty());
+        let sizes: Vec<usize> = batches.iter().map(|b| b.rows).collect();
+        assert_eq!(sizes, [300, 300, 101]);
+        assert!(batches
+            .iter()
+            .all(|b| b.statement.params.len() == b.rows * columns
+                && b.statement.params.len() <= MAX_PARAMS));
+        assert_eq!((batches[1].first_line, batches[1].last_line), (302, 601));
+
+        let narrow = file_with_column(&vec![text("x"); 1200]);
+        let target = [ColumnTarget {
+            file_column: 0,
+            name: "a".to_string(),
+            kind: ValueKind::Integer,
+            type_name: "INTEGER".to_string(),
+        }];
+        let (batches, bad_rows) = build_batches(&narrow, "t", &target, &DatabaseType::SQLite);
+        assert!(batches.is_empty());
+        assert_eq!(bad_rows.len(), 1200);
+        assert_eq!(bad_rows[0], "line 2: a: 'x' is not an integer");
+
+        let narrow = file_with_column(&vec![text("7"); 1200]);
+        let (batches, _) = build_batches(&narrow, "t", &target, &DatabaseType::SQLite);
+        let sizes: Vec<usize> = batches.iter().map(|b| b.rows).collect();
+        assert_eq!(sizes, [MAX_BATCH_ROWS, MAX_BATCH_ROWS, 200]);
+    }
+}
@@ -1,6 +1,13 @@
 mod app;
+mod completion;
 mod config;
 mod db;
+mod dump;
+mod export;
+mod external_editor;
+mod history;
+mod import;
+mod sql;
 mod ui;
 mod vim;
 
@@ -44,9 +51,32 @@ fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut A
 
         app.handle_events()?;
 
+        if let Some(edit) = app.external_edit.take() {
+            let result = run_external_editor(terminal, &edit);
+            app.finish_external_edit(edit.target, result);
+        }
+
         if app.should_quit {
             break;
         }
     }
     Ok(())
 }
+
+/// Hand the terminal to `$VISUAL`/`$EDITOR` for one edit, then take it back.
+fn run_external_editor<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    edit: &external_editor::ExternalEdit,
+) -> Result<String> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    let result = external_editor::edit(edit);
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    // The editor drew over the screen, so the next frame has to redraw everything
+    terminal.clear()?;
+    result
+}
@@ -0,0 +1,213 @@
+use crate::db::DatabaseType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+    String, // '...', MySQL "...", PostgreSQL E'...' and $tag$...$tag$
+    QuotedIdentifier,
+    Word, // Keywords and bare identifiers
+    Number,
+    Parameter, // ?, ?1, $1, :name, @name and ${name} placeholders
+    Semicolon,
+    Symbol, // Operators and punctuation, one character each
+}
+
+/// A token as a byte range of the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+
+    /// Whitespace and comments, which never change what a statement means.
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::Whitespace | TokenKind::LineComment | TokenKind::BlockComment
+        )
+    }
+}
+
+/// The token starting at byte `start`, which must be a character boundary before the end
+/// of `text`. Unterminated strings and comments run to the end of the text.
+pub fn next_token(text: &str, start: usize, dialect: &DatabaseType) -> Token {
+    let rest = &text[start..];
+    let mut chars = rest.chars();
+    let c = chars.next().unwrap_or(' ');
+    let next = chars.next();
+    let mysql = matches!(dialect, DatabaseType::MySQL | DatabaseType::MariaDB);
+    let postgres = *dialect == DatabaseType::PostgreSQL;
+
+    let (kind, len) = match c {
+        c if c.is_whitespace() => (
+            TokenKind::Whitespace,
+            rest.find(|ch: char| !ch.is_whitespace())
+                .unwrap_or(rest.len()),
+        ),
+        // MySQL only takes -- as a comment when a space follows
+        '-' if next == Some('-')
+            && (!mysql || rest[2..].chars().next().is_none_or(char::is_whitespace)) =>
+        {
+            (TokenKind::LineComment, line_len(rest))
+        }
+        '#' if mysql => (TokenKind::LineComment, line_len(rest)),
+        '/' if next == Some('*') => (TokenKind::BlockComment, block_comment_len(rest, postgres)),
+        '\'' => (TokenKind::String, quoted_len(rest, '\'', mysql)),
+        '"' if mysql => (TokenKind::String, quoted_len(rest, '"', true)),
+        '"' => (TokenKind::QuotedIdentifier, quoted_len(rest, '"', false)),
+        '`' if !postgres => (TokenKind::QuotedIdentifier, quoted_len(rest, '`', false)),
+        '[' if *dialect == DatabaseType::SQLite => (
+            TokenKind::QuotedIdentifier,
+            rest.find(']').map_or(rest.len(), |i| i + 1),
+        ),
+        'E' | 'e' if postgres && next == Some('\'') => {
+            (TokenKind::String, 1 + quoted_len(&rest[1..], '\'', true))
+        }
+        '$' if next == Some('{') => (
+            TokenKind::Parameter,
+            rest.find('}').map_or(rest.len(), |i| i + 1),
+        ),
+        '$' if postgres && next.is_some_and(|ch| ch.is_ascii_digit()) => {
+            (TokenKind::Parameter, 1 + digits_len(&rest[1..]))
+        }
+        '$' if postgres && dollar_tag(rest).is_some() => {
+            (TokenKind::String, dollar_quoted_len(rest))
+        }
+        '?' => (TokenKind::Parameter, 1 + digits_len(&rest[1..])),
+        // :name and @name, but not the second colon of a PostgreSQL :: cast
+        ':' | '@' | '$' if next.is_some_and(is_word_start) && !text[..start].ends_with(':') => {
+            (TokenKind::Parameter, 1 + word_len(&rest[1..]))
+        }
+        c if c.is_ascii_digit() => (TokenKind::Number, number_len(rest)),
+        '.' if next.is_some_and(|ch| ch.is_ascii_digit()) => (TokenKind::Number, number_len(rest)),
+        ';' => (TokenKind::Semicolon, 1),
+        c if is_word_start(c) => (TokenKind::Word, word_len(rest)),
+        c => (TokenKind::Symbol, c.len_utf8()),
+    };
+
+    Token {
+        kind,
+        start,
+        end: start + len.max(c.len_utf8()).min(rest.len()),
+    }
+}
+
+/// All tokens of `text`, trivia included, covering it without gaps.
+pub fn tokenize(text: &str, dialect: &DatabaseType) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < text.len() {
+        let token = next_token(text, pos, dialect);
+        pos = token.end;
+        tokens.push(token);
+    }
+    tokens
+}
+
+fn is_word_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn word_len(text: &str) -> usize {
+    text.find(|ch: char| !is_word_char(ch))
+        .unwrap_or(text.len())
+}
+
+fn digits_len(text: &str) -> usize {
+    text.find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(text.len())
+}
+
+fn line_len(text: &str) -> usize {
+    text.find('\n').unwrap_or(text.len())
+}
+
+/// A quoted string or identifier starting at `text[0]`; doubled quotes stay inside, as do
+/// backslash-escaped characters when `backslash` is set.
+pub fn quoted_len(text: &str, quote: char, backslash: bool) -> usize {
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((idx, ch)) = chars.next() {
+        if backslash && ch == '\\' {
+            chars.next();
+        } else if ch == quote {
+            if chars.peek().is_some_and(|&(_, next)| next == quote) {
+                chars.next();
+            } else {
+                return idx + ch.len_utf8();
+            }
+        }
+    }
+    text.len()
+}
+
+/// PostgreSQL block comments nest, the other dialects end at the first `*/`.
+fn block_comment_len(text: &str, nested: bool) -> usize {
+    let mut depth = 0;
+    let mut pos = 0;
+    while pos < text.len() {
+        if text[pos..].starts_with("/*") && (nested || depth == 0) {
+            depth += 1;
+            pos += 2;
+        } else if text[pos..].starts_with("*/") {
+            depth -= 1;
+            pos += 2;
+            if depth == 0 {
+                return pos;
+            }
+        } else {
+            pos += text[pos..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    text.len()
+}
+
+/// The `$tag$` opening a PostgreSQL dollar-quoted string, if `text` starts with one.
+fn dollar_tag(text: &str) -> Option<&str> {
+    let end = text[1..].find('$')?;
+    let tag = &text[1..1 + end];
+    let valid = tag.chars().next().is_none_or(is_word_start)
+        && tag.chars().all(|ch| ch.is_alphanumeric() || ch == '_');
+    valid.then(|| &text[..end + 2])
+}
+
+fn dollar_quoted_len(text: &str) -> usize {
+    match dollar_tag(text) {
+        Some(tag) => text[tag.len()..]
+            .find(tag)
+            .map_or(text.len(), |end| tag.len() + end + tag.len()),
+        None => 1,
+    }
+}
+
+fn number_len(text: &str) -> usize {
+    if text.starts_with("0x") || text.starts_with("0X") {
+        return 2 + text[2..]
+            .find(|ch: char| !ch.is_ascii_hexdigit())
+            .unwrap_or(text.len() - 2);
+    }
+    let mut len = digits_len(text);
+    if text[len..].starts_with('.') {
+        len += 1 + digits_len(&text[len + 1..]);
+    }
+    let exponent = &text[len..];
+    if exponent.starts_with(['e', 'E']) {
+        let sign = usize::from(exponent[1..].starts_with(['+', '-']));
+        let digits = digits_len(&exponent[1 + sign..]);
+        if digits > 0 {
+            len += 1 + sign + digits;
+        }
+    }
+    len
+}
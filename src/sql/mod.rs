@@ -0,0 +1,5 @@
+pub mod keywords;
+pub mod lexer;
+pub mod split;
+
+pub use split::{split_statements, Statement};
@@ -0,0 +1,339 @@
+use super::lexer::{next_token, TokenKind};
+use crate::db::DatabaseType;
+
+/// A statement of a script, without its delimiter or surrounding comments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    pub text: String,
+    pub start: usize, // Byte range in the script
+    pub end: usize,
+    pub line: usize, // 1-based line the statement starts on
+}
+
+/// Split a script into statements. Semicolons inside strings, quoted identifiers, comments
+/// and BEGIN...END bodies (triggers, stored procedures) don't end a statement. For MySQL
+/// the client's `DELIMITER` command is understood as well.
+pub fn split_statements(text: &str, dialect: &DatabaseType) -> Vec<Statement> {
+    let mysql = matches!(dialect, DatabaseType::MySQL | DatabaseType::MariaDB);
+    let mut splitter = Splitter {
+        text,
+        statements: Vec::new(),
+        first: None,
+        last_end: 0,
+        words: 0,
+        depth: 0,
+        parens: 0,
+        create: false,
+        routine: false,
+        after_end: false,
+    };
+    let mut delimiter = ";".to_string();
+    let mut pos = 0;
+
+    while pos < text.len() {
+        if mysql && splitter.first.is_none() {
+            if let Some((new_delimiter, line_end)) = delimiter_command(&text[pos..]) {
+                delimiter = new_delimiter;
+                pos += line_end;
+                continue;
+            }
+        }
+        if delimiter != ";" && text[pos..].starts_with(delimiter.as_str()) {
+            splitter.finish();
+            pos += delimiter.len();
+            continue;
+        }
+
+        let mut token = next_token(text, pos, dialect);
+        if delimiter != ";" && matches!(token.kind, TokenKind::Word | TokenKind::Symbol) {
+            // A custom delimiter can follow a word directly, as in END$$
+            if let Some(idx) = token.text(text).find(delimiter.as_str()).filter(|&i| i > 0) {
+                token.end = token.start + idx;
+            }
+        }
+        pos = token.end;
+
+        if token.kind == TokenKind::Semicolon && delimiter == ";" && splitter.depth == 0 {
+            splitter.finish();
+        } else if !token.is_trivia() {
+            match token.kind {
+                TokenKind::Word => splitter.word(token.text(text), pos, dialect),
+                TokenKind::Symbol => splitter.symbol(token.text(text)),
+                _ => {}
+            }
+            splitter.first.get_or_insert(token.start);
+            splitter.last_end = token.end;
+            splitter.words += 1;
+        }
+    }
+    splitter.finish();
+    splitter.statements
+}
+
+struct Splitter<'a> {
+    text: &'a str,
+    statements: Vec<Statement>,
+    first: Option<usize>, // Start of the current statement's first token
+    last_end: usize,      // End of its last token so far
+    words: usize,         // Tokens in the current statement so far
+    depth: usize,         // Open BEGIN and CASE blocks
+    parens: usize,        // Open parentheses
+    create: bool,         // The statement starts with CREATE
+    routine: bool,        // ... and defines a trigger, procedure, function or event
+    after_end: bool,      // The previous word was an END that closes a block
+}
+
+impl Splitter<'_> {
+    /// Track BEGIN/CASE ... END nesting. BEGIN opens a block only as the body of a routine
+    /// or trigger, inside another block, or as MariaDB's BEGIN NOT ATOMIC; anywhere else it
+    /// starts a transaction or is a name. END CASE closes a CASE, while END IF, END LOOP,
+    /// END WHILE, END REPEAT and END FOR close blocks whose openers aren't counted.
+    fn word(&mut self, word: &str, end: usize, dialect: &DatabaseType) {
+        // The word after END belongs to it
+        if std::mem::take(&mut self.after_end) {
+            return;
+        }
+        let upper = word.to_uppercase();
+        match upper.as_str() {
+            "CREATE" if self.words == 0 => self.create = true,
+            // Before the first parenthesis, so column names in CREATE TABLE don't count
+            "TRIGGER" | "PROCEDURE" | "FUNCTION" | "EVENT" if self.create && self.parens == 0 => {
+                self.routine = true
+            }
+            "BEGIN"
+                if self.parens == 0
+                    && (self.depth > 0
+                        || self.routine
+                        || (self.words == 0 && self.next_word(end, dialect) == "NOT")) =>
+            {
+                self.depth += 1
+            }
+            "CASE" => self.depth += 1,
+            "END" if self.depth > 0 => match self.next_word(end, dialect).as_str() {
+                "IF" | "LOOP" | "WHILE" | "REPEAT" | "FOR" => self.after_end = true,
+                "CASE" => {
+                    self.depth -= 1;
+                    self.after_end = true;
+                }
+                _ => self.depth -= 1,
+            },
+            _ => {}
+        }
+    }
+
+    fn symbol(&mut self, symbol: &str) {
+        match symbol {
+            "(" => self.parens += 1,
+            ")" => self.parens = self.parens.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn next_word(&self, mut pos: usize, dialect: &DatabaseType) -> String {
+        while pos < self.text.len() {
+            let token = next_token(self.text, pos, dialect);
+            if !token.is_trivia() {
+                return match token.kind {
+                    TokenKind::Word => token.text(self.text).to_uppercase(),
+                    _ => String::new(),
+                };
+            }
+            pos = token.end;
+        }
+        String::new()
+    }
+
+    fn finish(&mut self) {
+        if let Some(start) = self.first.take() {
+            self.statements.push(Statement {
+                text: self.text[start..self.last_end].to_string(),
+                start,
+                end: self.last_end,
+                line: 1 + self.text[..start].matches('\n').count(),
+            });
+        }
+        self.words = 0;
+        self.depth = 0;
+        self.parens = 0;
+        self.create = false;
+        self.routine = false;
+        self.after_end = false;
+    }
+}
+
+/// A `DELIMITER <token>` line at the start of `text`: the new delimiter and the length of
+/// the line.
+fn delimiter_command(text: &str) -> Option<(String, usize)> {
+    let keyword = text.get(..9)?;
+    if !keyword.eq_ignore_ascii_case("DELIMITER") {
+        return None;
+    }
+    let line_end = text.find('\n').unwrap_or(text.len());
+    let line = &text[9..line_end];
+    if !line.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let delimiter = line.split_whitespace().next()?;
+    Some((delimiter.to_string(), line_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(text: &str, dialect: DatabaseType) -> Vec<String> {
+        split_statements(text, &dialect)
+            .into_iter()
+            .map(|statement| statement.text)
+            .collect()
+    }
+
+    #[test]
+    fn splits_on_semicolons() {
+        let statements =
+            split_statements("SELECT 1;\n\n  SELECT 2 ;SELECT 3", &DatabaseType::SQLite);
+        let texts: Vec<&str> = statements.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["SELECT 1", "SELECT 2", "SELECT 3"]);
+        assert_eq!(statements[1].line, 3);
+        assert_eq!(statements[1].start, 13);
+        assert_eq!(statements[1].end, 21);
+    }
+
+    #[test]
+    fn skips_empty_statements_and_comments() {
+        assert_eq!(
+            split(
+                "-- setup\n;; /* nothing */ ;\nSELECT 1; -- done",
+                DatabaseType::SQLite
+            ),
+            ["SELECT 1"]
+        );
+    }
+
+    #[test]
+    fn ignores_semicolons_in_quotes_and_comments() {
+        assert_eq!(
+            split(
+                "SELECT 'a;b', \"c;d\" -- e;f\n/* g;h */ FROM t; SELECT 2",
+                DatabaseType::SQLite
+            ),
+            ["SELECT 'a;b', \"c;d\" -- e;f\n/* g;h */ FROM t", "SELECT 2"]
+        );
+        assert_eq!(
+            split(
+                "SELECT `x;y`, 'it''s;', \"a\\\";\"; SELECT 2",
+                DatabaseType::MySQL
+            ),
+            ["SELECT `x;y`, 'it''s;', \"a\\\";\"", "SELECT 2"]
+        );
+    }
+
+    #[test]
+    fn keeps_dollar_quoted_bodies_together() {
+        let sql = "CREATE FUNCTION f() RETURNS int AS $body$ BEGIN RETURN 1; END; $body$ \
+                   LANGUAGE plpgsql; SELECT f();";
+        assert_eq!(
+            split(sql, DatabaseType::PostgreSQL),
+            [
+                "CREATE FUNCTION f() RETURNS int AS $body$ BEGIN RETURN 1; END; $body$ \
+                 LANGUAGE plpgsql",
+                "SELECT f()"
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_trigger_bodies_together() {
+        let trigger = "CREATE TRIGGER log_insert AFTER INSERT ON t FOR EACH ROW \
+                       BEGIN INSERT INTO log VALUES (new.id); UPDATE c SET n = n + 1; END";
+        assert_eq!(
+            split(&format!("{};\nSELECT 1;", trigger), DatabaseType::SQLite),
+            [trigger, "SELECT 1"]
+        );
+    }
+
+    #[test]
+    fn understands_mysql_delimiter() {
+        let sql = "DELIMITER $$\n\
+                   CREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END$$\n\
+                   DELIMITER ;\n\
+                   CALL p();";
+        assert_eq!(
+            split(sql, DatabaseType::MySQL),
+            [
+                "CREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END",
+                "CALL p()"
+            ]
+        );
+    }
+
+    #[test]
+    fn end_case_closes_the_case() {
+        let procedure = "CREATE PROCEDURE p(x INT) BEGIN CASE x WHEN 1 THEN SELECT 1; \
+                         ELSE SELECT 2; END CASE; END";
+        assert_eq!(
+            split(&format!("{}; SELECT 3;", procedure), DatabaseType::MySQL),
+            [procedure, "SELECT 3"]
+        );
+    }
+
+    #[test]
+    fn end_if_and_loops_close_uncounted_blocks() {
+        let procedure = "CREATE PROCEDURE p() BEGIN \
+                         IF a THEN SELECT 1; END IF; \
+                         l: LOOP LEAVE l; END LOOP; \
+                         WHILE b DO SELECT 2; END WHILE; \
+                         BEGIN SELECT 3; END; END";
+        assert_eq!(
+            split(&format!("{}; SELECT 4;", procedure), DatabaseType::MySQL),
+            [procedure, "SELECT 4"]
+        );
+    }
+
+    #[test]
+    fn case_expressions_end_with_end() {
+        assert_eq!(
+            split(
+                "SELECT CASE WHEN a THEN 1 ELSE 2 END FROM t; SELECT 2",
+                DatabaseType::SQLite
+            ),
+            ["SELECT CASE WHEN a THEN 1 ELSE 2 END FROM t", "SELECT 2"]
+        );
+    }
+
+    #[test]
+    fn begin_outside_a_routine_is_not_a_block() {
+        assert_eq!(
+            split(
+                "BEGIN; INSERT INTO t VALUES (1); COMMIT;",
+                DatabaseType::SQLite
+            ),
+            ["BEGIN", "INSERT INTO t VALUES (1)", "COMMIT"]
+        );
+        assert_eq!(
+            split(
+                "SELECT begin FROM events; SELECT 1;",
+                DatabaseType::PostgreSQL
+            ),
+            ["SELECT begin FROM events", "SELECT 1"]
+        );
+        assert_eq!(
+            split(
+                "CREATE TABLE t (function TEXT, begin INT); SELECT 1;",
+                DatabaseType::SQLite
+            ),
+            ["CREATE TABLE t (function TEXT, begin INT)", "SELECT 1"]
+        );
+    }
+
+    #[test]
+    fn begin_not_atomic_is_a_block() {
+        assert_eq!(
+            split(
+                "BEGIN NOT ATOMIC SELECT 1; SELECT 2; END; SELECT 3",
+                DatabaseType::MariaDB
+            ),
+            ["BEGIN NOT ATOMIC SELECT 1; SELECT 2; END", "SELECT 3"]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use super::layout::centered_rect;
+use crate::db::BoundStatement;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Popup listing the statements Ctrl+S is about to run, so they can be checked before
+/// anything reaches the database.
+#[derive(Debug)]
+pub struct ChangeReview {
+    pub visible: bool,
+    pub table_name: String,
+    pub statements: Vec<BoundStatement>,
+    pub scroll: usize,
+}
+
+impl ChangeReview {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            table_name: String::new(),
+            statements: Vec::new(),
+            scroll: 0,
+        }
+    }
+
+    pub fn show(&mut self, table_name: String, statements: Vec<BoundStatement>) {
+        self.visible = true;
+        self.table_name = table_name;
+        self.statements = statements;
+        self.scroll = 0;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.statements.clear();
+    }
+
+    /// Close the popup and hand over the reviewed statements.
+    pub fn confirm(&mut self) -> Vec<BoundStatement> {
+        self.visible = false;
+        std::mem::take(&mut self.statements)
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll = self.scroll.saturating_add(1);
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        if !self.visible {
+            return;
+        }
+
+        let popup_area = centered_rect(80, 60, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title(format!(
+                " Review changes to {} ({} statements) ",
+                self.table_name,
+                self.statements.len()
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+        let inner = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(inner);
+
+        let mut lines: Vec<Line> = Vec::new();
+        for (idx, statement) in self.statements.iter().enumerate() {
+            let keyword = statement.sql.split_whitespace().next().unwrap_or("");
+            let color = match keyword {
+                "INSERT" => Color::Green,
+                "DELETE" => Color::Red,
+                _ => Color::Yellow,
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:>3}. ", idx + 1),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    keyword.to_string(),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(statement.sql[keyword.len()..].to_string()),
+            ]));
+            if !statement.params.is_empty() {
+                let params: Vec<String> = statement
+                    .params
+                    .iter()
+                    .map(|p| p.to_sql_literal())
+                    .collect();
+                lines.push(Line::from(Span::styled(
+                    format!("     -- params: {}", params.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+
+        let max_scroll = lines.len().saturating_sub(chunks[0].height as usize);
+        self.scroll = self.scroll.min(max_scroll);
+
+        let paragraph = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll as u16, 0));
+        frame.render_widget(paragraph, chunks[0]);
+
+        let help = Paragraph::new("Enter/y: Apply in one transaction  Esc/n: Cancel  j/k: Scroll")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(help, chunks[1]);
+    }
+}
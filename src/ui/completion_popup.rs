@@ -0,0 +1,144 @@
+use crate::completion::{CompletionItem, CompletionKind};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+const MAX_VISIBLE_ITEMS: usize = 8;
+
+/// Completion list shown under the cursor in insert mode.
+#[derive(Debug)]
+pub struct CompletionPopup {
+    pub visible: bool,
+    pub items: Vec<CompletionItem>,
+    pub selected: usize,
+    pub prefix_len: usize, // Bytes of the word before the cursor that the completion replaces
+    prefix_width: usize,   // Screen columns of that word
+    scroll: usize,
+}
+
+impl CompletionPopup {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            items: Vec::new(),
+            selected: 0,
+            prefix_len: 0,
+            prefix_width: 0,
+            scroll: 0,
+        }
+    }
+
+    pub fn show(&mut self, items: Vec<CompletionItem>, prefix: &str) {
+        self.visible = true;
+        self.items = items;
+        self.selected = 0;
+        self.scroll = 0;
+        self.prefix_len = prefix.len();
+        self.prefix_width = prefix.width();
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.items.clear();
+    }
+
+    pub fn move_down(&mut self) {
+        if !self.items.is_empty() {
+            self.selected = (self.selected + 1) % self.items.len();
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        if !self.items.is_empty() {
+            self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+        }
+    }
+
+    pub fn selected_item(&self) -> Option<&CompletionItem> {
+        self.items.get(self.selected)
+    }
+
+    /// Draw the list below `cursor`, or above it when there is no room, inside `area`.
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, cursor: (u16, u16)) {
+        if !self.visible || self.items.is_empty() {
+            return;
+        }
+
+        let visible_items = self.items.len().min(MAX_VISIBLE_ITEMS);
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + visible_items {
+            self.scroll = self.selected + 1 - visible_items;
+        }
+
+        let content_width = self
+            .items
+            .iter()
+            .map(|item| item.text.width() + item.detail.width() + 4)
+            .max()
+            .unwrap_or(0)
+            .min(60) as u16;
+        let width = (content_width + 2).min(area.width);
+        let height = (visible_items as u16 + 2).min(area.height);
+
+        // Start at the beginning of the word being completed
+        let x = cursor
+            .0
+            .saturating_sub(self.prefix_width as u16)
+            .min(area.right().saturating_sub(width))
+            .max(area.x);
+        let y = if cursor.1 + 1 + height <= area.bottom() {
+            cursor.1 + 1
+        } else {
+            cursor.1.saturating_sub(height).max(area.y)
+        };
+        let popup_area = Rect::new(x, y, width, height);
+
+        let lines: Vec<Line> = self
+            .items
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(visible_items)
+            .map(|(idx, item)| {
+                let (tag, color) = match item.kind {
+                    CompletionKind::Column => ("c", Color::Cyan),
+                    CompletionKind::Table => ("t", Color::Green),
+                    CompletionKind::Database => ("d", Color::Yellow),
+                    CompletionKind::Keyword => ("k", Color::Blue),
+                };
+                let mut line = Line::from(vec![
+                    Span::styled(format!("{} ", tag), Style::default().fg(color)),
+                    Span::raw(item.text.clone()),
+                    Span::styled(
+                        format!("  {}", item.detail),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]);
+                if idx == self.selected {
+                    line = line.style(
+                        Style::default()
+                            .bg(Color::Rgb(60, 60, 80))
+                            .add_modifier(Modifier::BOLD),
+                    );
+                }
+                line
+            })
+            .collect();
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Cyan)),
+            ),
+            popup_area,
+        );
+    }
+}
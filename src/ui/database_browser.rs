@@ -148,6 +148,14 @@ impl DatabaseBrowser {
         self.selected_table.and_then(|idx| self.tables.get(idx))
     }
 
+    /// Row count reported when the table list was loaded, if the backend provides one.
+    pub fn table_row_count(&self, table_name: &str) -> Option<usize> {
+        self.tables
+            .iter()
+            .find(|t| t.name == table_name)
+            .and_then(|t| t.row_count)
+    }
+
     pub fn get_selected_connection(&self) -> Option<&ConnectionInfo> {
         self.selected_connection
             .and_then(|idx| self.connections.get(idx))
@@ -157,6 +165,10 @@ impl DatabaseBrowser {
         // Find the connection with the given id
         let pos = self.connections.iter().position(|c| c.id == id)?;
         let removed = self.connections.remove(pos);
+        // Ids are positions in the list, so the connections after it move up one
+        for conn in &mut self.connections[pos..] {
+            conn.id -= 1;
+        }
 
         // Clear tables if this was the selected connection
         if self.selected_connection == Some(pos) {
@@ -322,14 +334,14 @@ impl DatabaseBrowser {
                 " Databases (Filtered: {} - {} matches) - Press / to search, ESC to clear ",
                 self.search_query, filtered_count
             )
-        } else if self.viewing_tables && self.current_database.is_some() {
+        } else if let (true, Some(database)) = (self.viewing_tables, &self.current_database) {
             format!(
                 " {} > {} (Press ESC to go back, / to search) ",
                 self.connections
                     .get(self.selected_connection.unwrap_or(0))
                     .map(|c| c.name.as_str())
                     .unwrap_or("Connection"),
-                self.current_database.as_ref().unwrap()
+                database
             )
         } else {
             " Databases (Press / to search) ".to_string()
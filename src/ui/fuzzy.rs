@@ -0,0 +1,40 @@
+/// Score `text` against a fuzzy `pattern`: every pattern character must appear in order,
+/// case-insensitively. Consecutive matches and matches at word starts score higher.
+/// Returns `None` when the text doesn't match; an empty pattern matches everything.
+pub fn score(pattern: &str, text: &str) -> Option<i64> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut next = 0;
+    let mut last_match: Option<usize> = None;
+    let mut prev_char: Option<char> = None;
+
+    for (i, ch) in text.chars().enumerate() {
+        if next == pattern.len() {
+            break;
+        }
+        if ch.to_lowercase().eq(std::iter::once(pattern[next])) {
+            score += 1;
+            match last_match {
+                Some(last) if last + 1 == i => score += 5,
+                Some(last) => score -= ((i - last - 1) as i64).min(5),
+                None => {}
+            }
+            if prev_char.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 3;
+            }
+            last_match = Some(i);
+            next += 1;
+        }
+        prev_char = Some(ch);
+    }
+
+    (next == pattern.len()).then_some(score)
+}
@@ -0,0 +1,50 @@
+use super::picker::{Picker, PickerItem};
+use crate::history::HistoryEntry;
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+
+/// `:history` popup: fuzzy-search past queries and load one back into the editor.
+pub type HistoryPopup = Picker<HistoryEntry>;
+
+impl PickerItem for HistoryEntry {
+    const TITLE: &'static str = "Query history";
+    const EMPTY: &'static str = "No queries yet";
+    const LATEST_FIRST: bool = true;
+
+    fn search_text(&self) -> &str {
+        &self.query
+    }
+
+    fn line(&self) -> Line<'_> {
+        let (status, status_color) = if self.success {
+            ("✓", Color::Green)
+        } else {
+            ("✗", Color::Red)
+        };
+        let rows = self
+            .rows
+            .map(|rows| format!("{} rows", rows))
+            .unwrap_or_default();
+        // Show the statement on one line; the full text goes into the editor
+        let query = self.query.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        Line::from(vec![
+            Span::styled(
+                self.timestamp.format("%Y-%m-%d %H:%M ").to_string(),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(status, Style::default().fg(status_color)),
+            Span::styled(
+                format!(" {:<16.16} ", self.connection),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::styled(
+                format!("{:>6}ms {:>10} ", self.duration_ms, rows),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::raw(query),
+        ])
+    }
+}
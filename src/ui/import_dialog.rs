@@ -0,0 +1,301 @@
+use super::layout::centered_rect;
+use crate::db::DatabaseType;
+use crate::import::{ColumnTarget, ImportFile, ValueKind};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph},
+    Frame,
+};
+
+/// Column of the table the file is imported into, as reported by `get_table_columns`.
+#[derive(Debug, Clone)]
+pub struct TargetColumn {
+    pub name: String,
+    pub data_type: String,
+    pub cast_type: String, // Type name PostgreSQL parameters are cast to
+}
+
+/// `:import` popup: map the file's columns onto a table, or create a new one, then show
+/// progress while the rows are inserted.
+#[derive(Debug)]
+pub struct ImportDialog {
+    pub visible: bool,
+    pub file: Option<ImportFile>,
+    pub dialect: DatabaseType,
+    pub table_name: Option<String>, // Existing table the file can be imported into
+    pub table_columns: Vec<TargetColumn>,
+    pub new_table_name: String,
+    pub create_table: bool,
+    pub mappings: Vec<Option<usize>>, // Per file column: index into `table_columns`
+    pub include: Vec<bool>,           // Per file column, when creating a table
+    pub kinds: Vec<ValueKind>,        // Inferred per file column
+    pub selected: usize,
+    pub progress: Option<(usize, usize)>, // Rows inserted / rows to insert
+}
+
+impl ImportDialog {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            file: None,
+            dialect: DatabaseType::SQLite,
+            table_name: None,
+            table_columns: Vec::new(),
+            new_table_name: String::new(),
+            create_table: false,
+            mappings: Vec::new(),
+            include: Vec::new(),
+            kinds: Vec::new(),
+            selected: 0,
+            progress: None,
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        file: ImportFile,
+        table: Option<(String, Vec<TargetColumn>)>,
+        new_table_name: String,
+        dialect: DatabaseType,
+    ) {
+        // Columns with the same name (ignoring case) are mapped up front
+        let (table_name, table_columns) = table.unzip();
+        self.table_columns = table_columns.unwrap_or_default();
+        self.mappings = file
+            .columns
+            .iter()
+            .map(|column| {
+                self.table_columns
+                    .iter()
+                    .position(|c| c.name.eq_ignore_ascii_case(column))
+            })
+            .collect();
+        self.include = vec![true; file.columns.len()];
+        self.kinds = (0..file.columns.len())
+            .map(|idx| file.infer_kind(idx))
+            .collect();
+        self.create_table = table_name.is_none();
+        self.table_name = table_name;
+        self.new_table_name = new_table_name;
+        self.dialect = dialect;
+        self.file = Some(file);
+        self.selected = 0;
+        self.progress = None;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.file = None;
+        self.progress = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.progress.is_some()
+    }
+
+    fn column_count(&self) -> usize {
+        self.file.as_ref().map_or(0, |f| f.columns.len())
+    }
+
+    /// Switch between importing into the selected table and creating a new one.
+    pub fn toggle_target(&mut self) {
+        if self.table_name.is_some() {
+            self.create_table = !self.create_table;
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.column_count() {
+            self.selected += 1;
+        }
+    }
+
+    /// Map the selected file column to the next (or previous) table column, passing
+    /// through "skip"; when creating a table, include or skip the column.
+    pub fn cycle_target(&mut self, forward: bool) {
+        if self.create_table {
+            if let Some(include) = self.include.get_mut(self.selected) {
+                *include = !*include;
+            }
+            return;
+        }
+        let count = self.table_columns.len();
+        if let Some(mapping) = self.mappings.get_mut(self.selected) {
+            *mapping = match (*mapping, forward) {
+                (None, true) if count > 0 => Some(0),
+                (None, false) if count > 0 => Some(count - 1),
+                (Some(idx), true) if idx + 1 < count => Some(idx + 1),
+                (Some(idx), false) if idx > 0 => Some(idx - 1),
+                _ => None,
+            };
+        }
+    }
+
+    /// The file columns that are imported and where they go.
+    pub fn targets(&self) -> Vec<ColumnTarget> {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Vec::new(),
+        };
+        if self.create_table {
+            return file
+                .columns
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| self.include[*idx])
+                .map(|(idx, name)| ColumnTarget {
+                    file_column: idx,
+                    name: name.clone(),
+                    kind: self.kinds[idx],
+                    type_name: self.kinds[idx].sql_type(&self.dialect).to_string(),
+                })
+                .collect();
+        }
+        self.mappings
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, mapping)| {
+                let column = &self.table_columns[(*mapping)?];
+                Some(ColumnTarget {
+                    file_column: idx,
+                    name: column.name.clone(),
+                    kind: ValueKind::from_type_name(&column.data_type),
+                    type_name: column.cast_type.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Table the rows go into.
+    pub fn target_table(&self) -> Option<String> {
+        if self.create_table {
+            Some(self.new_table_name.clone())
+        } else {
+            self.table_name.clone()
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let file = match (&self.file, self.visible) {
+            (Some(file), true) => file,
+            _ => return,
+        };
+
+        let popup_area = centered_rect(70, 70, area);
+        frame.render_widget(Clear, popup_area);
+
+        let file_name = file
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut title = format!(" Import {} ({} rows", file_name, file.rows.len());
+        if !file.bad_rows.is_empty() {
+            title.push_str(&format!(", {} unreadable", file.bad_rows.len()));
+        }
+        title.push_str(") ");
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let active = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+        let inactive = Style::default().fg(Color::DarkGray);
+        let mut target = vec![Span::raw("Target: ")];
+        if let Some(table_name) = &self.table_name {
+            target.push(Span::styled(
+                format!("existing table {}", table_name),
+                if self.create_table { inactive } else { active },
+            ));
+            target.push(Span::raw("  "));
+        }
+        target.push(Span::styled(
+            format!("new table {}", self.new_table_name),
+            if self.create_table { active } else { inactive },
+        ));
+        frame.render_widget(Paragraph::new(Line::from(target)), chunks[0]);
+
+        let height = chunks[1].height as usize;
+        let scroll = (self.selected + 1).saturating_sub(height);
+        let mut lines: Vec<Line> = Vec::new();
+        for (idx, column) in file.columns.iter().enumerate().skip(scroll).take(height) {
+            let (target, style) = if self.create_table {
+                if self.include[idx] {
+                    (
+                        format!("{} {}", column, self.kinds[idx].sql_type(&self.dialect)),
+                        Style::default().fg(Color::Green),
+                    )
+                } else {
+                    ("(skip)".to_string(), inactive)
+                }
+            } else {
+                match self.mappings[idx].and_then(|m| self.table_columns.get(m)) {
+                    Some(target) => (
+                        format!("{} ({})", target.name, target.data_type),
+                        Style::default().fg(Color::Green),
+                    ),
+                    None => ("(skip)".to_string(), inactive),
+                }
+            };
+            let mut line = Line::from(vec![
+                Span::styled(
+                    format!("{:<24.24}", column),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(" → "),
+                Span::styled(target, style),
+            ]);
+            if idx == self.selected {
+                line = line.style(Style::default().bg(Color::Rgb(60, 60, 80)));
+            }
+            lines.push(line);
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[1]);
+
+        match self.progress {
+            Some((done, total)) => {
+                let ratio = if total == 0 {
+                    1.0
+                } else {
+                    done as f64 / total as f64
+                };
+                let gauge = Gauge::default()
+                    .gauge_style(Style::default().fg(Color::Green))
+                    .ratio(ratio.min(1.0))
+                    .label(format!("{}/{} rows  Esc/Ctrl+C: Cancel", done, total));
+                frame.render_widget(gauge, chunks[2]);
+            }
+            None => {
+                let help = if self.create_table {
+                    "j/k: Select  h/l: Include/skip  Tab: Target  Enter: Import  Esc: Cancel"
+                } else {
+                    "j/k: Select  h/l: Change column  Tab: Target  Enter: Import  Esc: Cancel"
+                };
+                frame.render_widget(
+                    Paragraph::new(help).style(Style::default().fg(Color::DarkGray)),
+                    chunks[2],
+                );
+            }
+        }
+    }
+}
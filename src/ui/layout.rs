@@ -1,3 +1,4 @@
+use super::message_log::MessageLevel;
 use crate::app::App;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -44,8 +45,27 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         ])
         .split(content_chunks[1]);
 
+    // Buffer tabs above the query editor
+    let editor_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(main_chunks[0]);
+    app.buffers.render(
+        frame,
+        editor_chunks[0],
+        &app.database_browser.connections,
+        (
+            app.database_browser.selected_connection,
+            app.database_browser.get_current_database(),
+        ),
+        app.query_editor.is_modified(),
+    );
+
     // Render query editor
-    app.query_editor.render(frame, main_chunks[0]);
+    let dialect = app.selected_dialect();
+    app.query_editor.render(frame, editor_chunks[1], &dialect);
+    let cursor = app.query_editor.cursor_position(editor_chunks[1]);
+    app.completion.render(frame, editor_chunks[1], cursor);
 
     // Render results viewer
     app.results_viewer.render(frame, main_chunks[1]);
@@ -67,6 +87,30 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         })
         .collect();
     app.connection_manager.render(frame, size, &connections);
+
+    // Render pending changes review popup (if visible)
+    app.change_review.render(frame, size);
+
+    // Render query history popup (if visible)
+    app.history_popup.render(frame, size);
+
+    // Render saved queries picker (if visible)
+    app.saved_query_picker.render(frame, size);
+
+    // Render placeholder prompt (if visible)
+    app.param_prompt.render(frame, size);
+
+    // Render import popup (if visible)
+    app.import_dialog.render(frame, size);
+
+    // Render script popup (if visible)
+    app.source_dialog.render(frame, size);
+
+    // Render messages popup (if visible)
+    app.messages.render(frame, size);
+
+    // Render registers popup (if visible)
+    app.registers_popup.render(frame, size);
 }
 
 fn render_top_bar(frame: &mut Frame, area: Rect, _app: &App) {
@@ -118,12 +162,29 @@ fn render_status_bar(frame: &mut Frame, area: Rect, app: &App) {
         ));
     }
 
-    // Show help hint
+    // Show the latest message until the next key press, otherwise the help hint
     spans.push(Span::raw("  "));
-    spans.push(Span::styled(
-        "Press ':' for commands, 'q' to quit",
-        Style::default().fg(Color::DarkGray),
-    ));
+    if let Some(ref prompt) = app.quit_prompt {
+        spans.push(Span::styled(
+            prompt.clone(),
+            Style::default().fg(Color::Yellow),
+        ));
+    } else if let Some(ref message) = app.messages.current {
+        let color = match message.level {
+            MessageLevel::Error => Color::Red,
+            MessageLevel::Info => Color::Green,
+        };
+        let mut text = message.text.lines().next().unwrap_or("").to_string();
+        if message.text.lines().count() > 1 {
+            text.push_str(" ... (:messages for details)");
+        }
+        spans.push(Span::styled(text, Style::default().fg(color)));
+    } else {
+        spans.push(Span::styled(
+            "Press ':' for commands, 'q' to quit",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
 
     let paragraph = Paragraph::new(Line::from(spans))
         .block(Block::default().borders(Borders::ALL))
@@ -131,3 +192,23 @@ fn render_status_bar(frame: &mut Frame, area: Rect, app: &App) {
 
     frame.render_widget(paragraph, area);
 }
+
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
@@ -0,0 +1,150 @@
+use super::layout::centered_rect;
+use chrono::{DateTime, Local};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageLevel {
+    Info,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub timestamp: DateTime<Local>,
+    pub level: MessageLevel,
+    pub text: String,
+}
+
+/// History of errors and notices, shown on the status bar and in the `:messages` popup.
+#[derive(Debug)]
+pub struct MessageLog {
+    pub entries: Vec<LogEntry>,
+    pub visible: bool,
+    pub scroll: usize,
+    pub current: Option<LogEntry>, // Message shown on the status bar until the next key press
+}
+
+impl MessageLog {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            visible: false,
+            scroll: 0,
+            current: None,
+        }
+    }
+
+    pub fn error(&mut self, text: String) {
+        self.push(MessageLevel::Error, text);
+    }
+
+    pub fn info(&mut self, text: String) {
+        self.push(MessageLevel::Info, text);
+    }
+
+    fn push(&mut self, level: MessageLevel, text: String) {
+        let entry = LogEntry {
+            timestamp: Local::now(),
+            level,
+            text,
+        };
+        self.current = Some(entry.clone());
+        self.entries.push(entry);
+    }
+
+    pub fn clear_current(&mut self) {
+        self.current = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.current = None;
+        self.scroll = 0;
+    }
+
+    pub fn show(&mut self) {
+        self.visible = true;
+        // Start at the bottom so the most recent messages are visible; clamped in render
+        self.scroll = usize::MAX;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll = self.scroll.saturating_add(1);
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        if !self.visible {
+            return;
+        }
+
+        let popup_area = centered_rect(80, 60, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title(format!(" Messages ({}) ", self.entries.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(inner);
+
+        let max_scroll = self.entries.len().saturating_sub(chunks[0].height as usize);
+        self.scroll = self.scroll.min(max_scroll);
+
+        let mut lines: Vec<Line> = Vec::new();
+        if self.entries.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "No messages",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        for entry in self.entries.iter().skip(self.scroll) {
+            let (label, color) = match entry.level {
+                MessageLevel::Info => ("INFO ", Color::Green),
+                MessageLevel::Error => ("ERROR", Color::Red),
+            };
+            let mut text_lines = entry.text.lines();
+            lines.push(Line::from(vec![
+                Span::styled(
+                    entry.timestamp.format("%H:%M:%S ").to_string(),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    label,
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" "),
+                Span::raw(text_lines.next().unwrap_or("").to_string()),
+            ]));
+            // Driver messages can span several lines; indent the continuation
+            for continuation in text_lines {
+                lines.push(Line::from(format!("               {}", continuation)));
+            }
+        }
+
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, chunks[0]);
+
+        let help = Paragraph::new("j/k: Scroll  Esc/q: Close  :messages clear to empty")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(help, chunks[1]);
+    }
+}
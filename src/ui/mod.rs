@@ -1,11 +1,34 @@
+pub mod change_review;
+pub mod completion_popup;
 pub mod connection_manager;
 pub mod database_browser;
+pub mod fuzzy;
+pub mod history_popup;
+pub mod import_dialog;
 pub mod layout;
+pub mod message_log;
+pub mod param_prompt;
+pub mod picker;
+pub mod query_buffers;
 pub mod query_editor;
+pub mod registers_popup;
 pub mod results_viewer;
+pub mod saved_queries;
+pub mod source_dialog;
+pub mod undo;
 
+pub use change_review::ChangeReview;
+pub use completion_popup::CompletionPopup;
 pub use connection_manager::ConnectionManager;
 pub use database_browser::DatabaseBrowser;
+pub use history_popup::HistoryPopup;
+pub use import_dialog::ImportDialog;
 pub use layout::render;
+pub use message_log::MessageLog;
+pub use param_prompt::ParamPrompt;
+pub use query_buffers::QueryBuffers;
 pub use query_editor::QueryEditor;
+pub use registers_popup::RegistersPopup;
 pub use results_viewer::ResultsViewer;
+pub use saved_queries::SavedQueryPicker;
+pub use source_dialog::SourceDialog;
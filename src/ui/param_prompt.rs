@@ -0,0 +1,234 @@
+use super::layout::centered_rect;
+use crate::db::{CellValue, DatabaseType};
+use crate::import::has_leading_zero;
+use crate::sql::lexer::{self, TokenKind};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::ops::Range;
+
+/// `${name}` placeholders in `query` as (byte range, name). Strings and comments are
+/// skipped, so a literal `${x}` inside quotes stays as it is.
+fn placeholder_tokens<'a>(
+    query: &'a str,
+    dialect: &DatabaseType,
+) -> impl Iterator<Item = (Range<usize>, &'a str)> {
+    lexer::tokenize(query, dialect)
+        .into_iter()
+        .filter(|token| token.kind == TokenKind::Parameter)
+        .filter_map(move |token| {
+            let name = token.text(query).strip_prefix("${")?.strip_suffix('}')?;
+            is_placeholder_name(name).then_some((token.start..token.end, name))
+        })
+}
+
+/// Names of the `${name}` placeholders in `query`, in order of first appearance.
+pub fn placeholders(query: &str, dialect: &DatabaseType) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (_, name) in placeholder_tokens(query, dialect) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+fn is_placeholder_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A typed value as a literal: a number when it reads as one, a quoted string otherwise.
+fn param_literal(text: &str, dialect: &DatabaseType) -> String {
+    let value = if has_leading_zero(text) {
+        CellValue::Text(text.to_string())
+    } else if let Ok(i) = text.parse::<i64>() {
+        CellValue::Integer(i)
+    } else {
+        match text.parse::<f64>() {
+            Ok(f) if f.is_finite() => CellValue::Decimal(text.to_string()),
+            _ => CellValue::Text(text.to_string()),
+        }
+    };
+    dialect.literal(&value)
+}
+
+/// Asks for a value for each `${name}` placeholder before a query runs. Numbers are
+/// substituted as numbers and anything else as a quoted, escaped string.
+#[derive(Debug)]
+pub struct ParamPrompt {
+    pub visible: bool,
+    pub query: String,
+    pub names: Vec<String>,
+    pub values: Vec<String>,
+    pub input: String,
+    dialect: DatabaseType, // Decides what counts as a string or comment when filling in
+}
+
+impl ParamPrompt {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            query: String::new(),
+            names: Vec::new(),
+            values: Vec::new(),
+            input: String::new(),
+            dialect: DatabaseType::SQLite,
+        }
+    }
+
+    pub fn show(&mut self, query: String, names: Vec<String>, dialect: DatabaseType) {
+        self.visible = true;
+        self.query = query;
+        self.names = names;
+        self.dialect = dialect;
+        self.values.clear();
+        self.input.clear();
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.names.clear();
+        self.values.clear();
+        self.input.clear();
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.input.push(c);
+    }
+
+    pub fn backspace(&mut self) {
+        self.input.pop();
+    }
+
+    /// Accept the value being typed. Once every placeholder has a value the popup closes
+    /// and the filled-in query is returned.
+    pub fn submit(&mut self) -> Option<String> {
+        self.values.push(std::mem::take(&mut self.input));
+        if self.values.len() < self.names.len() {
+            return None;
+        }
+
+        let source = std::mem::take(&mut self.query);
+        let mut query = String::with_capacity(source.len());
+        let mut copied = 0;
+        for (range, name) in placeholder_tokens(&source, &self.dialect) {
+            if let Some(i) = self.names.iter().position(|n| n == name) {
+                query.push_str(&source[copied..range.start]);
+                query.push_str(&param_literal(&self.values[i], &self.dialect));
+                copied = range.end;
+            }
+        }
+        query.push_str(&source[copied..]);
+        self.hide();
+        Some(query)
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        if !self.visible {
+            return;
+        }
+
+        let popup_area = centered_rect(60, 40, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title(" Query parameters ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+        let inner = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(inner);
+
+        let mut lines: Vec<Line> = Vec::new();
+        for (i, name) in self.names.iter().enumerate() {
+            let label = Span::styled(format!("{:>16}: ", name), Style::default().fg(Color::Cyan));
+            let line = match i.cmp(&self.values.len()) {
+                std::cmp::Ordering::Less => Line::from(vec![label, Span::raw(&self.values[i])]),
+                std::cmp::Ordering::Equal => {
+                    let cursor_x = chunks[0].x + 18 + self.input.chars().count() as u16;
+                    let cursor_y = chunks[0].y + i as u16;
+                    if cursor_y < chunks[0].y + chunks[0].height {
+                        frame.set_cursor_position((cursor_x, cursor_y));
+                    }
+                    Line::from(vec![
+                        label,
+                        Span::styled(
+                            self.input.clone(),
+                            Style::default()
+                                .fg(Color::Yellow)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                    ])
+                }
+                std::cmp::Ordering::Greater => Line::from(vec![
+                    label,
+                    Span::styled("…", Style::default().fg(Color::DarkGray)),
+                ]),
+            };
+            lines.push(line);
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+        let help = Paragraph::new("Enter: Next / run query  Esc: Cancel")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(help, chunks[1]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(query: &str, dialect: DatabaseType, values: &[&str]) -> String {
+        let mut prompt = ParamPrompt::new();
+        prompt.show(query.to_string(), placeholders(query, &dialect), dialect);
+        let mut filled = None;
+        for value in values {
+            prompt.input = value.to_string();
+            filled = prompt.submit();
+        }
+        filled.unwrap()
+    }
+
+    #[test]
+    fn values_become_literals() {
+        assert_eq!(
+            fill(
+                "SELECT * FROM t WHERE name = ${name} AND id = ${id} AND ${id} < ${max}",
+                DatabaseType::SQLite,
+                &["O'Brien", "42", "1.5"],
+            ),
+            "SELECT * FROM t WHERE name = 'O''Brien' AND id = 42 AND 42 < 1.5"
+        );
+        assert_eq!(
+            fill(
+                "SELECT ${a}, ${b}, ${c}",
+                DatabaseType::MySQL,
+                &["02134", "x\\'; DROP TABLE t; --", "inf"]
+            ),
+            "SELECT '02134', 'x\\\\''; DROP TABLE t; --', 'inf'"
+        );
+    }
+
+    #[test]
+    fn skips_placeholders_in_strings_and_comments() {
+        let query = "SELECT '${a}', ${a} -- ${b}";
+        assert_eq!(placeholders(query, &DatabaseType::PostgreSQL), vec!["a"]);
+        assert_eq!(
+            fill(query, DatabaseType::PostgreSQL, &["-7"]),
+            "SELECT '${a}', -7 -- ${b}"
+        );
+    }
+}
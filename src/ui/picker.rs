@@ -0,0 +1,195 @@
+use super::fuzzy;
+use super::layout::centered_rect;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Something a `Picker` can list. Each popup only decides what is searched and how a row
+/// looks; filtering, selection and scrolling are shared.
+pub trait PickerItem {
+    const TITLE: &'static str;
+    const EMPTY: &'static str; // Shown when there is nothing to pick at all
+    const LATEST_FIRST: bool = false; // Among equal scores, later items first
+
+    /// Text the search is matched against.
+    fn search_text(&self) -> &str;
+
+    /// The item's row in the list.
+    fn line(&self) -> Line<'_>;
+}
+
+/// Popup that fuzzy-searches a list and hands back the chosen item.
+#[derive(Debug)]
+pub struct Picker<T> {
+    pub visible: bool,
+    pub search: String,
+    pub items: Vec<T>,
+    pub matches: Vec<usize>, // Indexes into `items`, best match first
+    pub selected: usize,
+    scroll: usize,
+}
+
+impl<T: PickerItem> Picker<T> {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            search: String::new(),
+            items: Vec::new(),
+            matches: Vec::new(),
+            selected: 0,
+            scroll: 0,
+        }
+    }
+
+    pub fn show(&mut self, items: Vec<T>) {
+        self.visible = true;
+        self.items = items;
+        self.search.clear();
+        self.update_matches();
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.items.clear();
+        self.matches.clear();
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.search.push(c);
+        self.update_matches();
+    }
+
+    pub fn backspace(&mut self) {
+        self.search.pop();
+        self.update_matches();
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.matches.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Close the popup and hand over the selected item.
+    pub fn confirm(&mut self) -> Option<T> {
+        let item = self
+            .matches
+            .get(self.selected)
+            .copied()
+            .map(|i| self.items.swap_remove(i));
+        self.hide();
+        item
+    }
+
+    fn update_matches(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| fuzzy::score(&self.search, item.search_text()).map(|s| (s, i)))
+            .collect();
+        // Best score first, then in list order (or reversed)
+        scored.sort_by(|a, b| {
+            let order = if T::LATEST_FIRST {
+                b.1.cmp(&a.1)
+            } else {
+                a.1.cmp(&b.1)
+            };
+            b.0.cmp(&a.0).then(order)
+        });
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        if !self.visible {
+            return;
+        }
+
+        let popup_area = centered_rect(80, 70, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title(format!(
+                " {} ({}/{}) ",
+                T::TITLE,
+                self.matches.len(),
+                self.items.len()
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let search = Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Yellow)),
+            Span::raw(self.search.clone()),
+        ]));
+        frame.render_widget(search, chunks[0]);
+        frame.set_cursor_position((
+            chunks[0].x + 2 + self.search.chars().count() as u16,
+            chunks[0].y,
+        ));
+
+        // Keep the selection on screen
+        let height = chunks[1].height as usize;
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if height > 0 && self.selected >= self.scroll + height {
+            self.scroll = self.selected + 1 - height;
+        }
+
+        let mut lines: Vec<Line> = Vec::new();
+        if self.matches.is_empty() {
+            lines.push(Line::from(Span::styled(
+                if self.items.is_empty() {
+                    T::EMPTY
+                } else {
+                    "No matching queries"
+                },
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        for (pos, &i) in self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(height)
+        {
+            let mut line = self.items[i].line();
+            if pos == self.selected {
+                line = line.style(
+                    Style::default()
+                        .bg(Color::Rgb(60, 60, 80))
+                        .add_modifier(Modifier::BOLD),
+                );
+            }
+            lines.push(line);
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[1]);
+
+        let help =
+            Paragraph::new("Type to search  ↑/↓: Select  Enter: Load into editor  Esc: Close")
+                .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(help, chunks[2]);
+    }
+}
@@ -0,0 +1,146 @@
+use super::QueryEditor;
+use crate::db::ConnectionInfo;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Tabs,
+    Frame,
+};
+
+/// A named query buffer with its own text, undo history and target connection.
+#[derive(Debug)]
+pub struct QueryBuffer {
+    pub name: String,
+    pub editor: QueryEditor, // Holds the text while the buffer is in the background
+    pub connection: Option<usize>, // Browser connection the buffer's queries run against
+    pub database: Option<String>, // MySQL database or PostgreSQL schema selected for it
+}
+
+/// The editor's buffers. The active buffer's text lives in `App::query_editor` and its
+/// connection is the one selected in the browser; both are swapped in and out on a switch.
+#[derive(Debug)]
+pub struct QueryBuffers {
+    pub buffers: Vec<QueryBuffer>,
+    pub current: usize,
+    created: usize, // Buffers made so far, for default names
+}
+
+impl QueryBuffers {
+    pub fn new() -> Self {
+        Self {
+            buffers: vec![QueryBuffer {
+                name: "query 1".to_string(),
+                editor: QueryEditor::new(),
+                connection: None,
+                database: None,
+            }],
+            current: 0,
+            created: 1,
+        }
+    }
+
+    pub fn current_mut(&mut self) -> &mut QueryBuffer {
+        &mut self.buffers[self.current]
+    }
+
+    /// Add an empty buffer after the current one and return its index.
+    pub fn add(&mut self, name: Option<String>) -> usize {
+        self.created += 1;
+        let idx = self.current + 1;
+        self.buffers.insert(
+            idx,
+            QueryBuffer {
+                name: name.unwrap_or_else(|| format!("query {}", self.created)),
+                editor: QueryEditor::new(),
+                connection: None,
+                database: None,
+            },
+        );
+        idx
+    }
+
+    /// Make `idx` the active buffer; its text is swapped into `editor`.
+    pub fn switch(&mut self, idx: usize, editor: &mut QueryEditor) {
+        // An open undo step belongs to the buffer it was made in
+        editor.commit_change();
+        std::mem::swap(editor, &mut self.buffers[self.current].editor);
+        self.current = idx;
+        std::mem::swap(editor, &mut self.buffers[idx].editor);
+    }
+
+    /// Drop a buffer other than the active one.
+    pub fn remove(&mut self, idx: usize) {
+        self.buffers.remove(idx);
+        if self.current > idx {
+            self.current -= 1;
+        }
+    }
+
+    /// Index of the buffer `count` places after (or before) the current one, wrapping around.
+    pub fn offset(&self, count: usize, forward: bool) -> usize {
+        let len = self.buffers.len();
+        let count = count % len;
+        if forward {
+            (self.current + count) % len
+        } else {
+            (self.current + len - count) % len
+        }
+    }
+
+    /// One tab per buffer with the connection it targets, and `+` when its file has unsaved
+    /// changes. `active_target` is the browser selection, which the active buffer runs against.
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        connections: &[ConnectionInfo],
+        active_target: (Option<usize>, Option<&str>),
+        active_modified: bool,
+    ) {
+        let titles: Vec<Line> = self
+            .buffers
+            .iter()
+            .enumerate()
+            .map(|(idx, buffer)| {
+                let ((connection, database), modified) = if idx == self.current {
+                    (active_target, active_modified)
+                } else {
+                    (
+                        (buffer.connection, buffer.database.as_deref()),
+                        buffer.editor.is_modified(),
+                    )
+                };
+                let target = match connection.and_then(|c| connections.get(c)) {
+                    Some(info) => match database {
+                        Some(database) => format!("{} / {}", info.name, database),
+                        None => info.name.clone(),
+                    },
+                    None => "no connection".to_string(),
+                };
+                Line::from(vec![
+                    Span::raw(format!(
+                        "{}:{}{} ",
+                        idx + 1,
+                        buffer.name,
+                        if modified { "+" } else { "" }
+                    )),
+                    Span::styled(
+                        format!("[{}]", target),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ])
+            })
+            .collect();
+
+        let tabs = Tabs::new(titles)
+            .select(self.current)
+            .style(Style::default().fg(Color::White))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            );
+        frame.render_widget(tabs, area);
+    }
+}
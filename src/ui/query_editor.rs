@@ -1,19 +1,55 @@
+use crate::db::DatabaseType;
+use crate::sql::keywords::is_keyword;
+use crate::sql::lexer::{tokenize, Token, TokenKind};
+use crate::sql::{split_statements, Statement};
+use crate::ui::undo::{Snapshot, UndoTree};
+use crate::vim::motion::{
+    self, next_grapheme, offset_at_width, prev_grapheme, Motion, Operator, Target, YankedText,
+};
 use ratatui::{
     layout::Rect,
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
+use std::ops::Range;
+use std::path::PathBuf;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Debug)]
 pub struct QueryEditor {
     pub content: Vec<String>,
     pub cursor_line: usize,
-    pub cursor_col: usize,
+    pub cursor_col: usize, // Byte offset in the line, always at a grapheme boundary
     pub focused: bool,
     pub visual_start: Option<(usize, usize)>,
     pub scroll_offset: usize,
+    pub file: Option<PathBuf>, // File the text was read from or last written to
+    undo_tree: UndoTree,
+    change_start: Option<(usize, usize)>, // Cursor when the edit being recorded began
+    saved_content: Vec<String>,           // Text when the file was last read or written
+    revision: u64,                        // Bumped by every change to the text
+    highlight: Option<Highlight>,         // Lexed text, rebuilt when `revision` moves on
+}
+
+/// The text as of one revision, lexed and split into statements, so drawing a frame doesn't
+/// go over the whole buffer again.
+#[derive(Debug)]
+struct Highlight {
+    revision: u64,
+    dialect: DatabaseType,
+    text: String,
+    tokens: Vec<Token>,
+    statements: Vec<Range<usize>>,
+}
+
+/// Which statement, given by the statements' end offsets, the cursor is on. The cursor just
+/// past a statement's `;` still counts as on it.
+fn statement_index(cursor: usize, mut ends: impl ExactSizeIterator<Item = usize>) -> Option<usize> {
+    let count = ends.len();
+    ends.position(|end| cursor <= end + 1)
+        .or(count.checked_sub(1))
 }
 
 impl QueryEditor {
@@ -25,44 +61,353 @@ impl QueryEditor {
             focused: false,
             visual_start: None,
             scroll_offset: 0,
+            file: None,
+            undo_tree: UndoTree::new(vec![String::new()]),
+            change_start: None,
+            saved_content: vec![String::new()],
+            revision: 0,
+            highlight: None,
         }
     }
 
+    /// Whether the text differs from the file it was read from or written to. Text that
+    /// was never saved to a file doesn't count.
+    pub fn is_modified(&self) -> bool {
+        self.file.is_some() && self.content != self.saved_content
+    }
+
+    /// Make `path` the editor's file, with the current text as what it holds.
+    pub fn mark_saved(&mut self, path: PathBuf) {
+        self.file = Some(path);
+        self.saved_content = self.content.clone();
+    }
+
     pub fn get_query(&self) -> String {
         self.content.join("\n")
     }
 
-    pub fn get_query_at_cursor(&self) -> String {
-        let full_text = self.content.join("\n");
+    /// Byte offset of the cursor in `get_query()`.
+    fn cursor_offset(&self) -> usize {
+        let before: usize = self.content[..self.cursor_line]
+            .iter()
+            .map(|line| line.len() + 1) // +1 for newline
+            .sum();
+        let line_len = self.content.get(self.cursor_line).map_or(0, String::len);
+        before + self.cursor_col.min(line_len)
+    }
+
+    /// Put the cursor at a byte offset of `get_query()`.
+    fn set_cursor_offset(&mut self, offset: usize) {
+        let mut remaining = offset;
+        for (idx, line) in self.content.iter().enumerate() {
+            if remaining <= line.len() {
+                self.cursor_line = idx;
+                self.cursor_col = remaining;
+                return;
+            }
+            remaining -= line.len() + 1;
+        }
+        self.cursor_line = self.content.len() - 1;
+        self.cursor_col = self.content[self.cursor_line].len();
+    }
+
+    /// Replace the whole text, keeping the lines `get_query()` would give back.
+    fn set_text(&mut self, text: &str) {
+        self.content = text.split('\n').map(String::from).collect();
+        self.revision += 1;
+    }
+
+    pub fn apply_motion(&mut self, motion: Motion, count: Option<usize>) {
+        let text = self.get_query();
+        if let Some(target) = motion::motion_target(&text, self.cursor_offset(), motion, count) {
+            self.set_cursor_offset(target);
+        }
+    }
+
+    /// Apply a delete, change or yank to the text a motion or text object covers. Returns the
+    /// text taken, or `None` when the motion failed and nothing happened. A change leaves
+    /// the cursor where insert mode should start.
+    pub fn operate(
+        &mut self,
+        operator: Operator,
+        target: Target,
+        count: Option<usize>,
+    ) -> Option<YankedText> {
+        let text = self.get_query();
+        let cursor = self.cursor_offset();
+
+        let (start, end, linewise) = match target {
+            Target::Line => (
+                cursor,
+                motion::motion_target(&text, cursor, Motion::LineEnd, count)?,
+                true,
+            ),
+            Target::TextObject(object) => {
+                let (start, end) = motion::text_object_range(&text, cursor, object)?;
+                (start, end, false)
+            }
+            Target::Motion(mut motion) => {
+                // cw on a word changes to its end, like ce
+                let on_blank = text[cursor..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace);
+                if operator == Operator::Change && !on_blank {
+                    motion = match motion {
+                        Motion::WordStart => Motion::WordEnd,
+                        Motion::BigWordStart => Motion::BigWordEnd,
+                        other => other,
+                    };
+                }
+                let dest = motion::motion_target(&text, cursor, motion, count)?;
+                let (start, mut end) = (cursor.min(dest), cursor.max(dest));
+                if motion.is_inclusive() && !text[end..].starts_with('\n') {
+                    end = next_grapheme(&text, end);
+                }
+                // dw on the last word of a line stops at the line end
+                if matches!(motion, Motion::WordStart | Motion::BigWordStart) {
+                    if let Some(newline) = text[start..end].rfind('\n') {
+                        if text[start + newline..end].trim().is_empty() {
+                            end = start + newline;
+                        }
+                    }
+                }
+                (start, end, motion.is_linewise())
+            }
+        };
+
+        let mut new_text = text.clone();
+        let (yanked, new_cursor) = if linewise {
+            let line_start = motion::line_start(&text, start);
+            let line_end = motion::line_end(&text, end);
+            let yanked = YankedText {
+                text: format!("{}\n", &text[line_start..line_end]),
+                linewise: true,
+            };
+            let new_cursor = match operator {
+                Operator::Yank => start,
+                Operator::Change => {
+                    new_text.replace_range(line_start..line_end, "");
+                    line_start
+                }
+                Operator::Delete => {
+                    let line = if line_end < text.len() {
+                        new_text.replace_range(line_start..line_end + 1, "");
+                        line_start
+                    } else if line_start > 0 {
+                        // The last line goes with the newline before it
+                        new_text.replace_range(line_start - 1..line_end, "");
+                        motion::line_start(&new_text, line_start - 1)
+                    } else {
+                        new_text.replace_range(line_start..line_end, "");
+                        0
+                    };
+                    motion::first_non_blank(&new_text, line)
+                }
+            };
+            (yanked, new_cursor)
+        } else {
+            if start == end {
+                return None;
+            }
+            let yanked = YankedText {
+                text: text[start..end].to_string(),
+                linewise: false,
+            };
+            if operator != Operator::Yank {
+                new_text.replace_range(start..end, "");
+            }
+            (yanked, start)
+        };
+
+        self.set_text(&new_text);
+        self.set_cursor_offset(new_cursor);
+        // Normal mode keeps the cursor on a character
+        if operator == Operator::Delete
+            && self.cursor_col > 0
+            && self.cursor_col == self.content[self.cursor_line].len()
+        {
+            self.move_left(1);
+        }
+        self.visual_start = None;
+        Some(yanked)
+    }
+
+    /// `p` (after the cursor, or below the line for whole lines) and `P` (before, or above),
+    /// `count` times. The cursor ends on the last pasted character, or on the first pasted line.
+    pub fn paste(&mut self, yanked: &YankedText, before: bool, count: usize) {
+        let mut text = self.get_query();
+        let cursor = self.cursor_offset();
+        let pasted = yanked.text.repeat(count.max(1));
+
+        let new_cursor = if yanked.linewise {
+            let lines = pasted.strip_suffix('\n').unwrap_or(&pasted);
+            let line = if before {
+                let start = motion::line_start(&text, cursor);
+                text.insert_str(start, &format!("{}\n", lines));
+                start
+            } else {
+                let end = motion::line_end(&text, cursor);
+                text.insert_str(end, &format!("\n{}", lines));
+                end + 1
+            };
+            motion::first_non_blank(&text, line)
+        } else {
+            let at = if before || text[cursor..].starts_with('\n') {
+                cursor
+            } else {
+                next_grapheme(&text, cursor)
+            };
+            text.insert_str(at, &pasted);
+            at + prev_grapheme(&pasted, pasted.len())
+        };
+
+        self.set_text(&text);
+        self.set_cursor_offset(new_cursor);
+    }
+
+    /// The statement `<C-e>` runs: the one under the cursor, split the same way the
+    /// connection splits `<C-r>`. The cursor just past a statement's `;` still counts as on it.
+    pub fn statement_at_cursor(&self, dialect: &DatabaseType) -> Option<Statement> {
+        let mut statements = split_statements(&self.get_query(), dialect);
+        let idx = statement_index(self.cursor_offset(), statements.iter().map(|s| s.end))?;
+        Some(statements.swap_remove(idx))
+    }
+
+    pub fn get_query_at_cursor(&self, dialect: &DatabaseType) -> String {
+        self.statement_at_cursor(dialect)
+            .map(|statement| statement.text)
+            .unwrap_or_default()
+    }
+
+    /// The identifier being typed before the cursor, and the table or alias it is qualified
+    /// with: `u.na` gives `("na", Some("u"))`.
+    pub fn word_before_cursor(&self) -> (String, Option<String>) {
+        let line = &self.content[self.cursor_line];
+        let before = &line[..self.cursor_col.min(line.len())];
+        let word_start = |text: &str| {
+            text.char_indices()
+                .rev()
+                .take_while(|&(_, c)| c.is_alphanumeric() || c == '_')
+                .last()
+                .map_or(text.len(), |(idx, _)| idx)
+        };
+
+        let start = word_start(before);
+        let qualifier = before[..start]
+            .strip_suffix('.')
+            .map(|rest| rest[word_start(rest)..].to_string())
+            .filter(|q| !q.is_empty());
+        (before[start..].to_string(), qualifier)
+    }
+
+    /// Replace the `prefix_len` bytes before the cursor with a completion.
+    pub fn complete_word(&mut self, prefix_len: usize, text: &str) {
+        let line = &mut self.content[self.cursor_line];
+        let end = self.cursor_col.min(line.len());
+        let start = end.saturating_sub(prefix_len);
+        line.replace_range(start..end, text);
+        self.cursor_col = start + text.len();
+        self.revision += 1;
+    }
+
+    /// Screen columns the text before the cursor takes up.
+    fn cursor_width(&self) -> usize {
+        let line = &self.content[self.cursor_line];
+        line[..self.cursor_col.min(line.len())].width()
+    }
+
+    /// Where the cursor is drawn on screen when the editor is rendered in `area`.
+    pub fn cursor_position(&self, area: Rect) -> (u16, u16) {
+        (
+            area.x + 5 + self.cursor_width() as u16,
+            area.y + 1 + self.cursor_line.saturating_sub(self.scroll_offset) as u16,
+        )
+    }
+
+    /// Start recording an undoable change, unless one is already open (an insert session
+    /// stays one change until it ends).
+    pub fn begin_change(&mut self) {
+        self.change_start
+            .get_or_insert((self.cursor_line, self.cursor_col));
+    }
 
-        // Calculate cursor position in the full text
-        let mut cursor_pos = 0;
-        for i in 0..self.cursor_line {
-            cursor_pos += self.content[i].len() + 1; // +1 for newline
+    /// Close the open change; it becomes an undo step if the text changed.
+    pub fn commit_change(&mut self) {
+        if let Some(cursor_before) = self.change_start.take() {
+            if self.content != self.undo_tree.content() {
+                self.undo_tree.record(
+                    self.content.clone(),
+                    cursor_before,
+                    (self.cursor_line, self.cursor_col),
+                );
+            }
         }
-        cursor_pos += self.cursor_col;
+    }
 
-        // Find the start of the query (previous semicolon or start of text)
-        let start = full_text[..cursor_pos]
-            .rfind(';')
-            .map(|pos| pos + 1)
-            .unwrap_or(0);
+    /// Returns false when there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        self.commit_change();
+        let snapshot = self.undo_tree.undo();
+        self.restore(snapshot)
+    }
+
+    pub fn redo(&mut self) -> bool {
+        self.commit_change();
+        let snapshot = self.undo_tree.redo();
+        self.restore(snapshot)
+    }
+
+    /// `g-`: go to the previous text state in time, whichever branch it is on.
+    pub fn undo_older(&mut self) -> bool {
+        self.commit_change();
+        let snapshot = self.undo_tree.older();
+        self.restore(snapshot)
+    }
+
+    /// `g+`: go to the next text state in time.
+    pub fn undo_newer(&mut self) -> bool {
+        self.commit_change();
+        let snapshot = self.undo_tree.newer();
+        self.restore(snapshot)
+    }
 
-        // Find the end of the query (next semicolon or end of text)
-        let end = full_text[cursor_pos..]
-            .find(';')
-            .map(|pos| cursor_pos + pos + 1)
-            .unwrap_or(full_text.len());
+    fn restore(&mut self, snapshot: Option<Snapshot>) -> bool {
+        let Some(snapshot) = snapshot else {
+            return false;
+        };
+        self.content = snapshot.content;
+        self.revision += 1;
+        self.cursor_line = snapshot.cursor.0.min(self.content.len() - 1);
+        self.cursor_col = snapshot.cursor.1.min(self.content[self.cursor_line].len());
+        self.visual_start = None;
+        true
+    }
 
-        // Extract and trim the query
-        full_text[start..end].trim().to_string()
+    /// Replace the editor contents, e.g. with a query from the history. The old text can be
+    /// brought back with undo.
+    pub fn set_content(&mut self, text: &str) {
+        self.begin_change();
+        self.content = text.lines().map(String::from).collect();
+        if self.content.is_empty() {
+            self.content.push(String::new());
+        }
+        self.revision += 1;
+        self.cursor_line = self.content.len() - 1;
+        self.cursor_col = self.content[self.cursor_line].len();
+        self.scroll_offset = 0;
+        self.visual_start = None;
+        self.commit_change();
     }
 
     pub fn clear(&mut self) {
+        self.begin_change();
         self.content = vec![String::new()];
+        self.revision += 1;
         self.cursor_line = 0;
         self.cursor_col = 0;
         self.scroll_offset = 0;
+        self.commit_change();
     }
 
     pub fn insert_char(&mut self, c: char) {
@@ -70,7 +415,8 @@ impl QueryEditor {
             self.content.push(String::new());
         }
         self.content[self.cursor_line].insert(self.cursor_col, c);
-        self.cursor_col += 1;
+        self.cursor_col += c.len_utf8();
+        self.revision += 1;
     }
 
     pub fn insert_newline(&mut self) {
@@ -84,12 +430,16 @@ impl QueryEditor {
         self.content.insert(self.cursor_line + 1, after);
         self.cursor_line += 1;
         self.cursor_col = 0;
+        self.revision += 1;
     }
 
     pub fn backspace(&mut self) {
+        self.revision += 1;
         if self.cursor_col > 0 {
-            self.content[self.cursor_line].remove(self.cursor_col - 1);
-            self.cursor_col -= 1;
+            let line = &mut self.content[self.cursor_line];
+            let start = prev_grapheme(line, self.cursor_col);
+            line.replace_range(start..self.cursor_col, "");
+            self.cursor_col = start;
         } else if self.cursor_line > 0 {
             let current_line = self.content.remove(self.cursor_line);
             self.cursor_line -= 1;
@@ -99,8 +449,11 @@ impl QueryEditor {
     }
 
     pub fn delete_char(&mut self) {
+        self.revision += 1;
         if self.cursor_col < self.content[self.cursor_line].len() {
-            self.content[self.cursor_line].remove(self.cursor_col);
+            let line = &mut self.content[self.cursor_line];
+            let end = next_grapheme(line, self.cursor_col);
+            line.replace_range(self.cursor_col..end, "");
         } else if self.cursor_line < self.content.len() - 1 {
             let next_line = self.content.remove(self.cursor_line + 1);
             self.content[self.cursor_line].push_str(&next_line);
@@ -110,7 +463,7 @@ impl QueryEditor {
     pub fn move_left(&mut self, count: usize) {
         for _ in 0..count {
             if self.cursor_col > 0 {
-                self.cursor_col -= 1;
+                self.cursor_col = prev_grapheme(&self.content[self.cursor_line], self.cursor_col);
             } else if self.cursor_line > 0 {
                 self.cursor_line -= 1;
                 self.cursor_col = self.content[self.cursor_line].len();
@@ -121,7 +474,7 @@ impl QueryEditor {
     pub fn move_right(&mut self, count: usize) {
         for _ in 0..count {
             if self.cursor_col < self.content[self.cursor_line].len() {
-                self.cursor_col += 1;
+                self.cursor_col = next_grapheme(&self.content[self.cursor_line], self.cursor_col);
             } else if self.cursor_line < self.content.len() - 1 {
                 self.cursor_line += 1;
                 self.cursor_col = 0;
@@ -130,21 +483,20 @@ impl QueryEditor {
     }
 
     pub fn move_up(&mut self, count: usize) {
-        for _ in 0..count {
-            if self.cursor_line > 0 {
-                self.cursor_line -= 1;
-                self.cursor_col = self.cursor_col.min(self.content[self.cursor_line].len());
-            }
-        }
+        let line = self.cursor_line.saturating_sub(count);
+        self.move_to_line(line);
     }
 
     pub fn move_down(&mut self, count: usize) {
-        for _ in 0..count {
-            if self.cursor_line < self.content.len() - 1 {
-                self.cursor_line += 1;
-                self.cursor_col = self.cursor_col.min(self.content[self.cursor_line].len());
-            }
-        }
+        let line = (self.cursor_line + count).min(self.content.len() - 1);
+        self.move_to_line(line);
+    }
+
+    /// Keep the screen column when changing lines, whatever the characters' byte lengths.
+    fn move_to_line(&mut self, line: usize) {
+        let width = self.cursor_width();
+        self.cursor_line = line;
+        self.cursor_col = offset_at_width(&self.content[line], width);
     }
 
     pub fn goto_line_start(&mut self) {
@@ -202,7 +554,78 @@ impl QueryEditor {
         }
     }
 
-    pub fn render(&self, frame: &mut Frame, area: Rect) {
+    /// The editor lines as styled spans. While focused, the statement `<C-e>` would run gets
+    /// a background. The text is only lexed again after it changed.
+    fn highlighted_lines(&mut self, dialect: &DatabaseType) -> Vec<Vec<Span<'static>>> {
+        if !self
+            .highlight
+            .as_ref()
+            .is_some_and(|h| h.revision == self.revision && h.dialect == *dialect)
+        {
+            let text = self.get_query();
+            self.highlight = Some(Highlight {
+                revision: self.revision,
+                dialect: dialect.clone(),
+                tokens: tokenize(&text, dialect),
+                statements: split_statements(&text, dialect)
+                    .into_iter()
+                    .map(|s| s.start..s.end)
+                    .collect(),
+                text,
+            });
+        }
+        let Some(Highlight {
+            text,
+            tokens,
+            statements,
+            ..
+        }) = &self.highlight
+        else {
+            return Vec::new();
+        };
+        let statement = if self.focused {
+            statement_index(self.cursor_offset(), statements.iter().map(|s| s.end))
+                .map(|idx| &statements[idx])
+        } else {
+            None
+        };
+
+        let mut lines: Vec<Vec<Span>> = vec![Vec::new(); self.content.len()];
+        let mut line = 0;
+        let mut line_start = 0;
+        for token in tokens {
+            let style = token_style(token, text);
+            let mut pos = token.start;
+            // Tokens such as block comments span lines; cut them at line and statement ends
+            while pos < token.end {
+                let line_end = line_start + self.content[line].len();
+                if pos == line_end {
+                    // The newline itself
+                    pos += 1;
+                    line += 1;
+                    line_start = pos;
+                    continue;
+                }
+                let mut end = token.end.min(line_end);
+                let mut style = style;
+                if let Some(statement) = &statement {
+                    for boundary in [statement.start, statement.end] {
+                        if boundary > pos && boundary < end {
+                            end = boundary;
+                        }
+                    }
+                    if pos >= statement.start && pos < statement.end {
+                        style = style.bg(Color::Rgb(35, 35, 50));
+                    }
+                }
+                lines[line].push(Span::styled(text[pos..end].to_string(), style));
+                pos = end;
+            }
+        }
+        lines
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, dialect: &DatabaseType) {
         let border_style = if self.focused {
             Style::default().fg(Color::Cyan)
         } else {
@@ -210,30 +633,27 @@ impl QueryEditor {
         };
 
         let text: Vec<Line> = self
-            .content
-            .iter()
+            .highlighted_lines(dialect)
+            .into_iter()
             .enumerate()
-            .map(|(i, line)| {
+            .map(|(i, spans)| {
                 let line_num = format!("{:3} ", i + 1);
-                if i == self.cursor_line && self.focused {
-                    Line::from(vec![
-                        Span::styled(line_num, Style::default().fg(Color::DarkGray)),
-                        Span::styled(line.clone(), Style::default().fg(Color::White)),
-                    ])
-                } else {
-                    Line::from(vec![
-                        Span::styled(line_num, Style::default().fg(Color::DarkGray)),
-                        Span::raw(line.clone()),
-                    ])
-                }
+                let mut line = vec![Span::styled(line_num, Style::default().fg(Color::DarkGray))];
+                line.extend(spans);
+                Line::from(line)
             })
             .collect();
 
+        let title = match &self.file {
+            Some(path) if self.is_modified() => format!(" Query Editor - {} [+] ", path.display()),
+            Some(path) => format!(" Query Editor - {} ", path.display()),
+            None => " Query Editor ".to_string(),
+        };
         let paragraph = Paragraph::new(text)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(" Query Editor ")
+                    .title(title)
                     .border_style(border_style),
             )
             .wrap(Wrap { trim: false });
@@ -242,11 +662,83 @@ impl QueryEditor {
 
         // Render cursor if focused
         if self.focused {
-            let cursor_x = area.x + 5 + self.cursor_col as u16;
-            let cursor_y = area.y + 1 + (self.cursor_line - self.scroll_offset) as u16;
+            let (cursor_x, cursor_y) = self.cursor_position(area);
             if cursor_y < area.y + area.height - 1 {
                 frame.set_cursor_position((cursor_x, cursor_y));
             }
         }
     }
 }
+
+fn token_style(token: &Token, text: &str) -> Style {
+    match token.kind {
+        TokenKind::Word if is_keyword(token.text(text)) => Style::default()
+            .fg(Color::Blue)
+            .add_modifier(Modifier::BOLD),
+        TokenKind::Word => Style::default().fg(Color::White),
+        TokenKind::QuotedIdentifier => Style::default().fg(Color::Cyan),
+        TokenKind::String => Style::default().fg(Color::Green),
+        TokenKind::Number => Style::default().fg(Color::Yellow),
+        TokenKind::LineComment | TokenKind::BlockComment => Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::ITALIC),
+        TokenKind::Parameter => Style::default().fg(Color::Magenta),
+        TokenKind::Whitespace | TokenKind::Semicolon | TokenKind::Symbol => {
+            Style::default().fg(Color::Gray)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shown(editor: &mut QueryEditor) -> Vec<String> {
+        editor
+            .highlighted_lines(&DatabaseType::SQLite)
+            .into_iter()
+            .map(|spans| spans.iter().map(|span| span.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn highlighting_follows_edits_and_undo() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("SELECT 1;\nSELECT 2");
+        assert_eq!(shown(&mut editor), vec!["SELECT 1;", "SELECT 2"]);
+
+        let revision = editor.revision;
+        shown(&mut editor);
+        editor.move_left(1);
+        assert_eq!(editor.highlight.as_ref().unwrap().revision, revision);
+
+        editor.begin_change();
+        editor.insert_char('0');
+        editor.insert_newline();
+        editor.commit_change();
+        assert_eq!(shown(&mut editor), vec!["SELECT 1;", "SELECT 0", "2"]);
+
+        editor.undo();
+        assert_eq!(shown(&mut editor), vec!["SELECT 1;", "SELECT 2"]);
+
+        // Undo put the cursor back before the 2
+        editor.backspace();
+        editor.delete_char();
+        assert_eq!(shown(&mut editor), vec!["SELECT 1;", "SELECT"]);
+    }
+
+    #[test]
+    fn marks_the_statement_under_the_cursor() {
+        let mut editor = QueryEditor::new();
+        editor.focused = true;
+        editor.set_content("SELECT 1;\nSELECT 2");
+        editor.goto_top();
+        let marked: Vec<Vec<bool>> = editor
+            .highlighted_lines(&DatabaseType::SQLite)
+            .iter()
+            .map(|spans| spans.iter().map(|span| span.style.bg.is_some()).collect())
+            .collect();
+        assert!(marked[0][0]);
+        assert!(marked[1].iter().all(|&bg| !bg));
+    }
+}
@@ -0,0 +1,99 @@
+use super::layout::centered_rect;
+use crate::vim::motion::YankedText;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// `:registers` popup: what each register holds, one line per register.
+#[derive(Debug)]
+pub struct RegistersPopup {
+    pub visible: bool,
+    pub entries: Vec<(char, YankedText)>,
+    pub scroll: usize,
+}
+
+impl RegistersPopup {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            entries: Vec::new(),
+            scroll: 0,
+        }
+    }
+
+    pub fn show(&mut self, entries: Vec<(char, YankedText)>) {
+        self.visible = true;
+        self.entries = entries;
+        self.scroll = 0;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll = self.scroll.saturating_add(1);
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        if !self.visible {
+            return;
+        }
+
+        let popup_area = centered_rect(80, 60, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title(" Registers ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(inner);
+
+        let max_scroll = self.entries.len().saturating_sub(chunks[0].height as usize);
+        self.scroll = self.scroll.min(max_scroll);
+
+        let mut lines: Vec<Line> = Vec::new();
+        if self.entries.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "All registers are empty",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        for (name, yanked) in self.entries.iter().skip(self.scroll) {
+            // Line breaks are shown as ^J, like Vim does, to keep one register per line
+            let text = yanked.text.replace('\n', "^J").replace('\t', "^I");
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("\"{} ", name),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    if yanked.linewise { "l  " } else { "c  " },
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(text),
+            ]));
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+        let help = Paragraph::new("j/k: Scroll  Esc/q: Close  l: lines  c: characters")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(help, chunks[1]);
+    }
+}
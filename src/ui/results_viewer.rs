@@ -1,17 +1,24 @@
-use crate::db::QueryResult;
+use crate::db::background::StatementResult;
+use crate::db::{BoundStatement, CellValue, DatabaseType, QueryResult};
+use anyhow::{anyhow, Result};
 use ratatui::{
-    layout::{Constraint, Rect},
+    layout::{Alignment, Constraint, Rect},
     style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Tabs},
     Frame,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::time::Instant;
+
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TabMode {
     Data,
     Schema,
     Indexes,
+    Statement(usize), // Result of one statement of a multi-statement run
 }
 
 #[derive(Debug, Clone)]
@@ -33,10 +40,11 @@ pub struct ResultsViewer {
     pub edit_mode: bool,
     pub insert_mode: bool,
     pub selected_column: usize,
-    pub modified_cells: HashMap<(usize, usize), String>, // (row, col) -> new value
-    pub insert_row: HashMap<usize, String>,              // col_idx -> new value for insert
+    pub modified_cells: HashMap<(usize, usize), CellValue>, // (row, col) -> new value
+    pub insert_row: HashMap<usize, CellValue>,              // col_idx -> new value for insert
     pub table_name: Option<String>,
     pub edit_buffer: String,
+    pub edit_typed: bool, // The edit buffer was typed into since it was loaded
     pub visible_columns: usize, // Number of columns that can fit in the display
     pub active_tab: TabMode,
     pub schema_info: Option<String>,       // DDL/CREATE statement
@@ -50,6 +58,13 @@ pub struct ResultsViewer {
     pub schema_modified_cells: HashMap<(usize, usize), String>, // (row, col) -> new value
     pub schema_insert_row: HashMap<usize, String>,              // For new column
     pub status_message: Option<String>, // Temporary status message (e.g., "Copied!")
+    pub running_since: Option<Instant>, // Set while a query runs in the background
+    pub deleted_rows: BTreeSet<usize>,  // Rows marked for deletion with dd / V
+    pub row_selection_anchor: Option<usize>, // Start of a visual-line row selection
+    pub page_size: usize,               // Rows fetched per page of table data
+    pub page_offset: usize,             // Offset of the first row of the current page
+    pub total_rows: Option<usize>,      // Table row count, when the backend reports one
+    pub statement_results: Vec<StatementResult>, // One per statement of the last multi-statement run
 }
 
 impl ResultsViewer {
@@ -73,6 +88,7 @@ impl ResultsViewer {
             insert_row: HashMap::new(),
             table_name: None,
             edit_buffer: String::new(),
+            edit_typed: false,
             visible_columns: 10, // Default to showing 10 columns
             active_tab: TabMode::Data,
             schema_info: None,
@@ -86,14 +102,41 @@ impl ResultsViewer {
             schema_modified_cells: HashMap::new(),
             schema_insert_row: HashMap::new(),
             status_message: None,
+            running_since: None,
+            deleted_rows: BTreeSet::new(),
+            row_selection_anchor: None,
+            page_size: 1000,
+            page_offset: 0,
+            total_rows: None,
+            statement_results: Vec::new(),
         }
     }
 
     pub fn set_result(&mut self, result: QueryResult) {
+        self.statement_results.clear();
+        if matches!(self.active_tab, TabMode::Statement(_)) {
+            self.active_tab = TabMode::Data;
+        }
+        self.load_result(result);
+    }
+
+    /// Show a result in the grid, keeping the statement tabs.
+    fn load_result(&mut self, result: QueryResult) {
         self.result = Some(result);
         self.scroll_offset = 0;
         self.horizontal_scroll = 0;
         self.table_state.select(Some(0));
+        // Marks refer to row positions in the previous result
+        self.deleted_rows.clear();
+        self.row_selection_anchor = None;
+    }
+
+    pub fn set_running(&mut self, started: Instant) {
+        self.running_since = Some(started);
+    }
+
+    pub fn clear_running(&mut self) {
+        self.running_since = None;
     }
 
     pub fn clear(&mut self) {
@@ -192,10 +235,7 @@ impl ResultsViewer {
     pub fn enter_edit_mode(&mut self) {
         self.edit_mode = true;
         self.selected_column = 0;
-        // Initialize edit buffer with current cell value
-        if let Some(value) = self.get_current_cell_value() {
-            self.edit_buffer = value;
-        }
+        self.load_edit_buffer();
     }
 
     pub fn exit_edit_mode(&mut self) {
@@ -207,7 +247,7 @@ impl ResultsViewer {
         self.insert_mode = true;
         self.selected_column = 0;
         self.insert_row.clear();
-        self.edit_buffer.clear();
+        self.load_edit_buffer();
     }
 
     pub fn exit_insert_mode(&mut self) {
@@ -216,9 +256,74 @@ impl ResultsViewer {
         self.edit_buffer.clear();
     }
 
-    pub fn save_insert_field(&mut self) {
+    /// Put the selected cell's (or new row field's) value in the edit buffer. NULL starts
+    /// out empty, so typing replaces it.
+    fn load_edit_buffer(&mut self) {
+        self.edit_buffer = if self.insert_mode {
+            self.insert_row
+                .get(&self.selected_column)
+                .filter(|value| !value.is_null())
+                .map(|value| value.to_string())
+                .unwrap_or_default()
+        } else {
+            self.current_edit_text().unwrap_or_default()
+        };
+        self.edit_typed = false;
+    }
+
+    /// Keep the field typed into the new row. A field left empty is not inserted, so the
+    /// column gets its default.
+    pub fn save_insert_field(&mut self) -> Result<()> {
+        if !self.edit_typed {
+            return Ok(());
+        }
+        let col = self.selected_column;
+        if self.edit_buffer.is_empty() {
+            self.insert_row.remove(&col);
+        } else {
+            let value = self.parse_edit(None, col, &self.edit_buffer)?;
+            self.insert_row.insert(col, value);
+        }
+        self.edit_typed = false;
+        Ok(())
+    }
+
+    /// `Delete` while editing: set the cell, or the new row's field, to NULL.
+    pub fn set_null(&mut self) {
         let col = self.selected_column;
-        self.insert_row.insert(col, self.edit_buffer.clone());
+        if self.insert_mode {
+            self.insert_row.insert(col, CellValue::Null);
+        } else if let Some(row) = self.table_state.selected() {
+            if self.original_value(row, col).is_null() {
+                self.modified_cells.remove(&(row, col));
+            } else {
+                self.modified_cells.insert((row, col), CellValue::Null);
+            }
+        }
+        self.edit_buffer.clear();
+        self.edit_typed = false;
+    }
+
+    fn original_value(&self, row: usize, col: usize) -> CellValue {
+        self.result
+            .as_ref()
+            .and_then(|result| result.rows.get(row)?.get(col).cloned())
+            .unwrap_or(CellValue::Null)
+    }
+
+    /// Check typed text against the column: the cell's original value for an edit (`row`),
+    /// or the column's declared type for the new row.
+    fn parse_edit(&self, row: Option<usize>, col: usize, text: &str) -> Result<CellValue> {
+        let original = row.map_or(CellValue::Null, |row| self.original_value(row, col));
+        let (name, column_type) = match &self.result {
+            Some(result) => (
+                result.columns.get(col).map(String::as_str).unwrap_or(""),
+                Self::column_type(result, col),
+            ),
+            None => ("", ""),
+        };
+        CellValue::from_edit(text, &original, column_type)
+            .map_err(|problem| anyhow!("{}: {}", name, problem))
     }
 
     pub fn move_column_left(&mut self) {
@@ -228,16 +333,7 @@ impl ResultsViewer {
             if self.selected_column < self.horizontal_scroll {
                 self.horizontal_scroll = self.selected_column;
             }
-            if self.insert_mode {
-                // Load insert row value if it exists
-                self.edit_buffer = self
-                    .insert_row
-                    .get(&self.selected_column)
-                    .cloned()
-                    .unwrap_or_default();
-            } else if let Some(value) = self.get_current_cell_value() {
-                self.edit_buffer = value;
-            }
+            self.load_edit_buffer();
         }
     }
 
@@ -252,52 +348,207 @@ impl ResultsViewer {
                         .selected_column
                         .saturating_sub(self.visible_columns - 1);
                 }
-                if self.insert_mode {
-                    // Load insert row value if it exists
-                    self.edit_buffer = self
-                        .insert_row
-                        .get(&self.selected_column)
-                        .cloned()
-                        .unwrap_or_default();
-                } else if let Some(value) = self.get_current_cell_value() {
-                    self.edit_buffer = value;
-                }
+                self.load_edit_buffer();
             }
         }
     }
 
     pub fn edit_insert_char(&mut self, c: char) {
         self.edit_buffer.push(c);
+        self.edit_typed = true;
     }
 
     pub fn edit_backspace(&mut self) {
         self.edit_buffer.pop();
+        self.edit_typed = true;
     }
 
-    pub fn save_cell_edit(&mut self) {
+    /// Keep the value typed into the selected cell; a number that doesn't fit its column is
+    /// refused and stays in the buffer.
+    pub fn save_cell_edit(&mut self) -> Result<()> {
+        if !self.edit_typed {
+            return Ok(());
+        }
         let row = self.table_state.selected().unwrap_or(0);
-        let col = self.selected_column;
-        self.modified_cells
-            .insert((row, col), self.edit_buffer.clone());
+        let text = self.edit_buffer.clone();
+        self.set_cell_text(row, self.selected_column, &text)?;
+        self.edit_typed = false;
+        Ok(())
     }
 
+    /// Set a cell from text, as if typed; nothing changes when it's the text already there.
+    pub fn set_cell_text(&mut self, row: usize, col: usize, text: &str) -> Result<()> {
+        if self.edit_text(row, col).as_deref() == Some(text) {
+            return Ok(());
+        }
+        let value = self.parse_edit(Some(row), col, text)?;
+        if value == self.original_value(row, col) {
+            self.modified_cells.remove(&(row, col));
+        } else {
+            self.modified_cells.insert((row, col), value);
+        }
+        Ok(())
+    }
+
+    /// Replace the selected cell's value, saved with the other edits on `Ctrl+S`.
+    pub fn paste_cell_value(&mut self, value: &str) -> Result<()> {
+        match self.table_state.selected() {
+            Some(row) => self.set_cell_text(row, self.selected_column, value),
+            None => Ok(()),
+        }
+    }
+
+    /// The selected cell's value as shown, edits included; NULL reads as "NULL".
     pub fn get_current_cell_value(&self) -> Option<String> {
         let row = self.table_state.selected()?;
         let col = self.selected_column;
 
         // Check if cell has been modified
         if let Some(modified_value) = self.modified_cells.get(&(row, col)) {
-            return Some(modified_value.clone());
+            return Some(modified_value.to_string());
         }
 
         // Otherwise return original value
-        self.result.as_ref()?.rows.get(row)?.get(col).cloned()
+        self.result
+            .as_ref()?
+            .rows
+            .get(row)?
+            .get(col)
+            .map(|value| value.to_string())
+    }
+
+    /// The selected cell's value as text to edit, where NULL is empty.
+    pub fn current_edit_text(&self) -> Option<String> {
+        self.edit_text(self.table_state.selected()?, self.selected_column)
+    }
+
+    fn edit_text(&self, row: usize, col: usize) -> Option<String> {
+        let value = match self.modified_cells.get(&(row, col)) {
+            Some(value) => value,
+            None => self.result.as_ref()?.rows.get(row)?.get(col)?,
+        };
+        Some(if value.is_null() {
+            String::new()
+        } else {
+            value.to_string()
+        })
     }
 
     pub fn set_table_name(&mut self, name: String) {
         self.table_name = Some(name);
     }
 
+    /// Show a query result that doesn't belong to a table: no paging and no saving edits.
+    pub fn set_query_result(&mut self, result: QueryResult) {
+        self.set_result(result);
+        self.table_name = None;
+        self.page_offset = 0;
+        self.total_rows = None;
+    }
+
+    /// Show the outcome of each statement of a multi-statement run in its own tab. The Data tab
+    /// shows the last statement that succeeded; when one failed, its tab is opened.
+    pub fn set_statement_results(&mut self, results: Vec<StatementResult>) {
+        match results.iter().rev().find_map(|s| s.result.as_ref().ok()) {
+            Some(result) => self.set_query_result(result.clone()),
+            None => {
+                self.clear();
+                self.table_name = None;
+            }
+        }
+        self.active_tab = match results.iter().position(|s| s.result.is_err()) {
+            Some(idx) => TabMode::Statement(idx),
+            None => TabMode::Data,
+        };
+        self.statement_results = results;
+        self.show_active_result();
+    }
+
+    /// Step through the Data tab and the statement tabs.
+    pub fn next_statement_tab(&mut self) {
+        let count = self.statement_results.len();
+        if count == 0 {
+            return;
+        }
+        self.active_tab = match self.active_tab {
+            TabMode::Statement(idx) if idx + 1 < count => TabMode::Statement(idx + 1),
+            TabMode::Statement(_) => TabMode::Data,
+            _ => TabMode::Statement(0),
+        };
+        self.show_active_result();
+    }
+
+    pub fn previous_statement_tab(&mut self) {
+        let count = self.statement_results.len();
+        if count == 0 {
+            return;
+        }
+        self.active_tab = match self.active_tab {
+            TabMode::Statement(0) => TabMode::Data,
+            TabMode::Statement(idx) => TabMode::Statement(idx - 1),
+            _ => TabMode::Statement(count - 1),
+        };
+        self.show_active_result();
+    }
+
+    /// Put the rows of the active statement tab, or of the Data tab, into the grid.
+    fn show_active_result(&mut self) {
+        let result = match self.active_tab {
+            TabMode::Statement(idx) => self
+                .statement_results
+                .get(idx)
+                .and_then(|s| s.result.as_ref().ok()),
+            _ => self
+                .statement_results
+                .iter()
+                .rev()
+                .find_map(|s| s.result.as_ref().ok()),
+        };
+        if let Some(result) = result.cloned() {
+            self.load_result(result);
+        }
+    }
+
+    pub fn set_page(&mut self, offset: usize, total_rows: Option<usize>) {
+        self.page_offset = offset;
+        self.total_rows = total_rows;
+    }
+
+    pub fn has_next_page(&self) -> bool {
+        match (&self.result, &self.table_name) {
+            // A full page means there may be more; the reported count can be an estimate
+            (Some(result), Some(_)) => {
+                result.rows.len() >= self.page_size
+                    || self
+                        .total_rows
+                        .is_some_and(|total| self.page_offset + result.rows.len() < total)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn has_prev_page(&self) -> bool {
+        self.table_name.is_some() && self.page_offset > 0
+    }
+
+    /// "rows 1001–2000 of 48 213" for the current page of table data.
+    fn page_label(&self, row_count: usize) -> String {
+        if row_count == 0 {
+            return "no rows".to_string();
+        }
+        let first = self.page_offset + 1;
+        let last = self.page_offset + row_count;
+        match self.total_rows {
+            Some(total) => format!(
+                "rows {}–{} of {}",
+                group_digits(first),
+                group_digits(last),
+                group_digits(total.max(last))
+            ),
+            None => format!("rows {}–{}", group_digits(first), group_digits(last)),
+        }
+    }
+
     pub fn set_status_message(&mut self, message: String) {
         self.status_message = Some(message);
     }
@@ -310,69 +561,237 @@ impl ResultsViewer {
         !self.modified_cells.is_empty()
     }
 
-    pub fn generate_update_queries(&self) -> Vec<String> {
-        let mut queries = Vec::new();
-
-        let result = match &self.result {
-            Some(r) => r,
-            None => return queries,
+    /// Build one UPDATE per modified row, identifying the row by `key_columns` (its primary
+    /// key) or, failing that, by SQLite rowid. Rows that can't be identified are refused.
+    pub fn generate_update_statements(
+        &self,
+        dialect: &DatabaseType,
+        key_columns: &[String],
+    ) -> Result<Vec<BoundStatement>> {
+        let mut statements = Vec::new();
+
+        let (result, table_name) = match (&self.result, &self.table_name) {
+            (Some(r), Some(t)) => (r, t),
+            _ => return Ok(statements),
         };
+        let key_indexes = Self::key_indexes(result, table_name, key_columns)?;
 
-        let table_name = match &self.table_name {
-            Some(t) => t,
-            None => return queries,
-        };
-
-        // Group modifications by row
-        let mut rows_to_update: HashMap<usize, Vec<(usize, String)>> = HashMap::new();
+        // Group modifications by row; rows marked for deletion are not worth updating
+        let mut rows_to_update: HashMap<usize, Vec<(usize, CellValue)>> = HashMap::new();
         for ((row, col), value) in &self.modified_cells {
+            if self.deleted_rows.contains(row) {
+                continue;
+            }
             rows_to_update
                 .entry(*row)
                 .or_default()
                 .push((*col, value.clone()));
         }
-
-        // Generate UPDATE query for each modified row
-        for (row_idx, modifications) in rows_to_update {
-            if let Some(row_data) = result.rows.get(row_idx) {
-                let mut set_clauses = Vec::new();
-                let mut where_clauses = Vec::new();
-
-                // Build SET clauses for modified columns
-                for (col_idx, new_value) in &modifications {
-                    if let Some(col_name) = result.columns.get(*col_idx) {
-                        set_clauses.push(format!(
-                            "{} = '{}'",
-                            col_name,
-                            new_value.replace("'", "''")
-                        ));
-                    }
+        let mut row_indexes: Vec<usize> = rows_to_update.keys().copied().collect();
+        row_indexes.sort_unstable();
+
+        // Generate UPDATE statement for each modified row
+        for row_idx in row_indexes {
+            let row_data = match result.rows.get(row_idx) {
+                Some(row_data) => row_data,
+                None => continue,
+            };
+            let mut modifications = rows_to_update.remove(&row_idx).unwrap_or_default();
+            modifications.sort_by_key(|(col_idx, _)| *col_idx);
+
+            let mut params = Vec::new();
+
+            // Build SET clauses for columns whose value actually changed
+            let mut set_clauses = Vec::new();
+            for (col_idx, new_value) in &modifications {
+                let (col_name, original) =
+                    match (result.columns.get(*col_idx), row_data.get(*col_idx)) {
+                        (Some(col_name), Some(original)) => (col_name, original),
+                        _ => continue,
+                    };
+                if new_value == original {
+                    continue;
                 }
+                params.push(new_value.clone());
+                set_clauses.push(format!(
+                    "{} = {}",
+                    dialect.quote_identifier(col_name),
+                    dialect.placeholder(params.len(), Self::column_type(result, *col_idx))
+                ));
+            }
+            if set_clauses.is_empty() {
+                continue;
+            }
 
-                // Build WHERE clause using all original column values
-                for (col_idx, col_name) in result.columns.iter().enumerate() {
-                    if let Some(original_value) = row_data.get(col_idx) {
-                        where_clauses.push(format!(
-                            "{} = '{}'",
-                            col_name,
-                            original_value.replace("'", "''")
-                        ));
-                    }
-                }
+            let where_clause =
+                Self::row_key_clause(result, row_idx, &key_indexes, dialect, &mut params)?;
+            statements.push(BoundStatement {
+                sql: format!(
+                    "UPDATE {} SET {} WHERE {}",
+                    dialect.quote_identifier(table_name),
+                    set_clauses.join(", "),
+                    where_clause
+                ),
+                params,
+                expected_rows: Some(1),
+            });
+        }
 
-                if !set_clauses.is_empty() && !where_clauses.is_empty() {
-                    let query = format!(
-                        "UPDATE {} SET {} WHERE {}",
-                        table_name,
-                        set_clauses.join(", "),
-                        where_clauses.join(" AND ")
-                    );
-                    queries.push(query);
-                }
+        Ok(statements)
+    }
+
+    /// Build one DELETE per row marked with `dd` or a visual-line selection, using the same
+    /// key lookup as updates.
+    pub fn generate_delete_statements(
+        &self,
+        dialect: &DatabaseType,
+        key_columns: &[String],
+    ) -> Result<Vec<BoundStatement>> {
+        let mut statements = Vec::new();
+
+        let (result, table_name) = match (&self.result, &self.table_name) {
+            (Some(r), Some(t)) => (r, t),
+            _ => return Ok(statements),
+        };
+        if self.deleted_rows.is_empty() {
+            return Ok(statements);
+        }
+        let key_indexes = Self::key_indexes(result, table_name, key_columns)?;
+
+        for &row_idx in &self.deleted_rows {
+            if row_idx >= result.rows.len() {
+                continue;
             }
+            let mut params = Vec::new();
+            let where_clause =
+                Self::row_key_clause(result, row_idx, &key_indexes, dialect, &mut params)?;
+            statements.push(BoundStatement {
+                sql: format!(
+                    "DELETE FROM {} WHERE {}",
+                    dialect.quote_identifier(table_name),
+                    where_clause
+                ),
+                params,
+                expected_rows: Some(1),
+            });
         }
 
-        queries
+        Ok(statements)
+    }
+
+    fn column_type(result: &QueryResult, col_idx: usize) -> &str {
+        result
+            .column_types
+            .get(col_idx)
+            .map(String::as_str)
+            .unwrap_or("")
+    }
+
+    /// Positions of the key columns in the result. Without a key, rows can only be
+    /// identified by SQLite rowid.
+    fn key_indexes(
+        result: &QueryResult,
+        table_name: &str,
+        key_columns: &[String],
+    ) -> Result<Vec<usize>> {
+        let key_indexes = key_columns
+            .iter()
+            .map(|key| {
+                result.columns.iter().position(|c| c == key).ok_or_else(|| {
+                    anyhow!(
+                        "Primary key column {} of {} is not in the result, cannot change rows safely",
+                        key,
+                        table_name
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if key_indexes.is_empty() && result.rowids.is_none() {
+            return Err(anyhow!(
+                "Table {} has no primary key, refusing to change rows that can't be identified",
+                table_name
+            ));
+        }
+        Ok(key_indexes)
+    }
+
+    /// WHERE clause matching exactly one row: its primary key, or the rowid when there is none.
+    /// Key values are appended to `params`.
+    fn row_key_clause(
+        result: &QueryResult,
+        row_idx: usize,
+        key_indexes: &[usize],
+        dialect: &DatabaseType,
+        params: &mut Vec<CellValue>,
+    ) -> Result<String> {
+        if key_indexes.is_empty() {
+            let rowid = result
+                .rowids
+                .as_ref()
+                .and_then(|rowids| rowids.get(row_idx))
+                .ok_or_else(|| anyhow!("Row {} has no rowid", row_idx + 1))?;
+            params.push(CellValue::Integer(*rowid));
+            return Ok(format!("rowid = {}", dialect.placeholder(params.len(), "")));
+        }
+
+        let mut where_clauses = Vec::new();
+        for &key_idx in key_indexes {
+            let key_value = &result.rows[row_idx][key_idx];
+            if key_value.is_null() {
+                return Err(anyhow!(
+                    "Row {} has a NULL primary key, refusing to change it",
+                    row_idx + 1
+                ));
+            }
+            params.push(key_value.clone());
+            where_clauses.push(format!(
+                "{} = {}",
+                dialect.quote_identifier(&result.columns[key_idx]),
+                dialect.placeholder(params.len(), Self::column_type(result, key_idx))
+            ));
+        }
+        Ok(where_clauses.join(" AND "))
+    }
+
+    pub fn has_deletions(&self) -> bool {
+        !self.deleted_rows.is_empty()
+    }
+
+    /// `dd`: mark the selected row for deletion, or unmark it if it already is.
+    pub fn toggle_row_deletion(&mut self) {
+        if let Some(row) = self.table_state.selected() {
+            if self.result.as_ref().is_some_and(|r| row < r.rows.len())
+                && !self.deleted_rows.remove(&row)
+            {
+                self.deleted_rows.insert(row);
+            }
+        }
+    }
+
+    pub fn start_row_selection(&mut self) {
+        self.row_selection_anchor = self.table_state.selected();
+    }
+
+    pub fn cancel_row_selection(&mut self) {
+        self.row_selection_anchor = None;
+    }
+
+    /// Rows between the visual-line anchor and the cursor, inclusive.
+    pub fn selected_row_range(&self) -> Option<(usize, usize)> {
+        let anchor = self.row_selection_anchor?;
+        let cursor = self.table_state.selected()?;
+        Some((anchor.min(cursor), anchor.max(cursor)))
+    }
+
+    pub fn mark_selection_for_deletion(&mut self) {
+        if let Some((start, end)) = self.selected_row_range() {
+            self.deleted_rows.extend(start..=end);
+        }
+        self.row_selection_anchor = None;
+    }
+
+    pub fn clear_deletions(&mut self) {
+        self.deleted_rows.clear();
     }
 
     pub fn clear_modifications(&mut self) {
@@ -383,7 +802,7 @@ impl ResultsViewer {
         !self.insert_row.is_empty()
     }
 
-    pub fn generate_insert_query(&self) -> Option<String> {
+    pub fn generate_insert_statement(&self, dialect: &DatabaseType) -> Option<BoundStatement> {
         let result = self.result.as_ref()?;
         let table_name = self.table_name.as_ref()?;
 
@@ -391,14 +810,24 @@ impl ResultsViewer {
             return None;
         }
 
+        let mut entered: Vec<(&usize, &CellValue)> = self.insert_row.iter().collect();
+        entered.sort_by_key(|(col_idx, _)| **col_idx);
+
         let mut columns = Vec::new();
-        let mut values = Vec::new();
+        let mut placeholders = Vec::new();
+        let mut params = Vec::new();
 
         // Build columns and values lists
-        for (col_idx, value) in &self.insert_row {
+        for (col_idx, value) in entered {
             if let Some(col_name) = result.columns.get(*col_idx) {
-                columns.push(col_name.clone());
-                values.push(format!("'{}'", value.replace("'", "''")));
+                let column_type = result
+                    .column_types
+                    .get(*col_idx)
+                    .map(String::as_str)
+                    .unwrap_or("");
+                columns.push(dialect.quote_identifier(col_name));
+                params.push(value.clone());
+                placeholders.push(dialect.placeholder(params.len(), column_type));
             }
         }
 
@@ -406,12 +835,16 @@ impl ResultsViewer {
             return None;
         }
 
-        Some(format!(
-            "INSERT INTO {} ({}) VALUES ({})",
-            table_name,
-            columns.join(", "),
-            values.join(", ")
-        ))
+        Some(BoundStatement {
+            sql: format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                dialect.quote_identifier(table_name),
+                columns.join(", "),
+                placeholders.join(", ")
+            ),
+            params,
+            expected_rows: None,
+        })
     }
 
     pub fn clear_insert_data(&mut self) {
@@ -421,6 +854,8 @@ impl ResultsViewer {
     pub fn discard_all_changes(&mut self) {
         self.modified_cells.clear();
         self.insert_row.clear();
+        self.deleted_rows.clear();
+        self.row_selection_anchor = None;
         self.edit_buffer.clear();
         if self.edit_mode {
             self.exit_edit_mode();
@@ -445,12 +880,17 @@ impl ResultsViewer {
     pub fn has_any_changes(&self) -> bool {
         !self.modified_cells.is_empty()
             || !self.insert_row.is_empty()
+            || !self.deleted_rows.is_empty()
             || !self.schema_modified_cells.is_empty()
             || !self.schema_insert_row.is_empty()
     }
 
     pub fn switch_to_data_tab(&mut self) {
+        let from_statement = matches!(self.active_tab, TabMode::Statement(_));
         self.active_tab = TabMode::Data;
+        if from_statement {
+            self.show_active_result();
+        }
     }
 
     pub fn switch_to_schema_tab(&mut self) {
@@ -592,7 +1032,12 @@ impl ResultsViewer {
             Style::default().fg(Color::DarkGray)
         };
 
-        if let Some(ref _result) = self.result {
+        if let Some(started) = self.running_since {
+            self.render_running(frame, area, border_style, started);
+            return;
+        }
+
+        if self.result.is_some() || !self.statement_results.is_empty() {
             // Split area into tabs and content
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
@@ -603,7 +1048,19 @@ impl ResultsViewer {
                 .split(area);
 
             // Render tabs
-            let tab_titles = vec!["1. Data", "2. Schema", "3. Indexes"];
+            let mut tab_titles: Vec<Line> =
+                vec!["1. Data".into(), "2. Schema".into(), "3. Indexes".into()];
+            for (idx, statement) in self.statement_results.iter().enumerate() {
+                let color = if statement.result.is_ok() {
+                    Color::Green
+                } else {
+                    Color::Red
+                };
+                tab_titles.push(Line::styled(
+                    format!("#{}", idx + 1),
+                    Style::default().fg(color),
+                ));
+            }
             let tabs = Tabs::new(tab_titles)
                 .block(
                     Block::default()
@@ -614,6 +1071,7 @@ impl ResultsViewer {
                     TabMode::Data => 0,
                     TabMode::Schema => 1,
                     TabMode::Indexes => 2,
+                    TabMode::Statement(idx) => 3 + idx,
                 })
                 .style(Style::default().fg(Color::White))
                 .highlight_style(
@@ -629,6 +1087,12 @@ impl ResultsViewer {
                 TabMode::Data => self.render_data_tab(frame, chunks[1], border_style),
                 TabMode::Schema => self.render_schema_tab(frame, chunks[1], border_style),
                 TabMode::Indexes => self.render_indexes_tab(frame, chunks[1], border_style),
+                TabMode::Statement(idx) => match self.statement_results.get(idx) {
+                    Some(StatementResult { result: Err(_), .. }) => {
+                        self.render_statement_error(frame, chunks[1], border_style, idx)
+                    }
+                    _ => self.render_data_tab(frame, chunks[1], border_style),
+                },
             }
         } else {
             let block = Block::default()
@@ -639,6 +1103,100 @@ impl ResultsViewer {
         }
     }
 
+    fn render_statement_error(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        border_style: Style,
+        idx: usize,
+    ) {
+        let statement = &self.statement_results[idx];
+        let error = statement.result.as_ref().err().cloned().unwrap_or_default();
+
+        let mut text: Vec<Line> = statement
+            .sql
+            .lines()
+            .map(|line| Line::styled(line.to_string(), Style::default().fg(Color::DarkGray)))
+            .collect();
+        text.push(Line::from(""));
+        text.extend(
+            error
+                .lines()
+                .map(|line| Line::styled(line.to_string(), Style::default().fg(Color::Red))),
+        );
+
+        let paragraph = Paragraph::new(text)
+            .wrap(ratatui::widgets::Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(
+                        " #{} Failed - {}ms - [/]: Previous/next statement ",
+                        idx + 1,
+                        statement.duration_ms
+                    ))
+                    .border_style(border_style),
+            );
+        frame.render_widget(paragraph, area);
+    }
+
+    fn render_running(&self, frame: &mut Frame, area: Rect, border_style: Style, started: Instant) {
+        let elapsed = started.elapsed();
+        let spinner = SPINNER_FRAMES[(elapsed.as_millis() / 100) as usize % SPINNER_FRAMES.len()];
+
+        let text = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{} Running query... {:.1}s", spinner, elapsed.as_secs_f64()),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(
+                "Ctrl+C: Cancel",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
+
+        let paragraph = Paragraph::new(text).alignment(Alignment::Center).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Results ")
+                .border_style(border_style),
+        );
+        frame.render_widget(paragraph, area);
+    }
+
+    /// A grid cell for an unmodified value: NULL is dimmed so it differs from the text 'NULL',
+    /// and numbers are right-aligned.
+    /// The cell being edited: the buffer, or a dimmed NULL while a NULL cell is untouched.
+    fn edit_cell(&self, is_null: bool) -> Cell<'static> {
+        let style = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+        if is_null && !self.edit_typed && self.edit_buffer.is_empty() {
+            return Cell::from(" NULL ")
+                .style(style.add_modifier(Modifier::DIM | Modifier::ITALIC));
+        }
+        Cell::from(format!(" {} ", self.edit_buffer)).style(style)
+    }
+
+    fn value_cell(value: Option<&CellValue>) -> Cell<'static> {
+        match value {
+            None => Cell::from(" "),
+            Some(CellValue::Null) => Cell::from(Span::styled(
+                " NULL ",
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC),
+            )),
+            Some(value) if value.is_numeric() => {
+                Cell::from(Line::from(format!(" {} ", value)).alignment(Alignment::Right))
+            }
+            Some(value) => Cell::from(format!(" {} ", value)),
+        }
+    }
+
     fn render_data_tab(&mut self, frame: &mut Frame, area: Rect, border_style: Style) {
         if let Some(ref result) = self.result {
             // Calculate how many columns can fit in the available width
@@ -672,6 +1230,7 @@ impl ResultsViewer {
             let header = Row::new(header_cells).height(1).bottom_margin(0);
 
             let selected_row = self.table_state.selected().unwrap_or(0);
+            let selection = self.selected_row_range();
 
             // Create rows including insert row if in insert mode
             let mut all_rows: Vec<Row> = Vec::new();
@@ -681,10 +1240,16 @@ impl ResultsViewer {
                 let insert_cells = (start_col..end_col).map(|col_idx| {
                     if col_idx == self.selected_column {
                         // Show edit buffer for currently editing cell
-                        Cell::from(format!(" {} ", self.edit_buffer.clone())).style(
+                        let is_null = self
+                            .insert_row
+                            .get(&col_idx)
+                            .is_some_and(CellValue::is_null);
+                        self.edit_cell(is_null)
+                    } else if let Some(CellValue::Null) = self.insert_row.get(&col_idx) {
+                        Cell::from(" NULL ").style(
                             Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::BOLD),
+                                .fg(Color::Cyan)
+                                .add_modifier(Modifier::ITALIC | Modifier::DIM),
                         )
                     } else if let Some(value) = self.insert_row.get(&col_idx) {
                         // Show entered value
@@ -709,25 +1274,30 @@ impl ResultsViewer {
             // Add existing data rows with horizontal scrolling
             let data_rows = result.rows.iter().enumerate().map(|(row_idx, row)| {
                 let cells = (start_col..end_col).map(|col_idx| {
-                    let cell_value = row.get(col_idx).map(String::as_str).unwrap_or("");
+                    let cell_value = row.get(col_idx);
                     let is_selected_column = col_idx == self.selected_column;
 
                     // Check if this is the currently editing cell
                     if self.edit_mode && row_idx == selected_row && col_idx == self.selected_column
                     {
                         // Show edit buffer for currently editing cell with padding
-                        Cell::from(format!(" {} ", self.edit_buffer.clone())).style(
-                            Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::BOLD),
-                        )
+                        let is_null = self
+                            .modified_cells
+                            .get(&(row_idx, col_idx))
+                            .or(cell_value)
+                            .is_some_and(CellValue::is_null);
+                        self.edit_cell(is_null)
                     } else if let Some(modified_value) =
                         self.modified_cells.get(&(row_idx, col_idx))
                     {
-                        // Show modified value for edited cells with padding
+                        // Show modified value for edited cells with padding; NULL is dimmed
+                        // so it can't be mistaken for the text 'NULL'
                         let mut style = Style::default()
                             .fg(Color::Green)
                             .add_modifier(Modifier::ITALIC);
+                        if modified_value.is_null() {
+                            style = style.add_modifier(Modifier::DIM);
+                        }
                         if is_selected_column && !self.edit_mode {
                             style = style.bg(Color::Rgb(60, 60, 80));
                         }
@@ -738,10 +1308,18 @@ impl ResultsViewer {
                         if is_selected_column && !self.edit_mode {
                             style = style.bg(Color::Rgb(60, 60, 80));
                         }
-                        Cell::from(format!(" {} ", cell_value)).style(style)
+                        Self::value_cell(cell_value).style(style)
                     }
                 });
-                Row::new(cells).height(1)
+                let mut row_style = Style::default();
+                if selection.is_some_and(|(start, end)| (start..=end).contains(&row_idx)) {
+                    row_style = row_style.bg(Color::Rgb(50, 50, 90));
+                }
+                if self.deleted_rows.contains(&row_idx) {
+                    // Rows marked for deletion are struck through until saved or discarded
+                    row_style = row_style.fg(Color::Red).add_modifier(Modifier::CROSSED_OUT);
+                }
+                Row::new(cells).height(1).style(row_style)
             });
 
             all_rows.extend(data_rows);
@@ -755,7 +1333,7 @@ impl ResultsViewer {
                         .rows
                         .iter()
                         .filter_map(|row| row.get(col_idx))
-                        .map(|cell| cell.len())
+                        .map(|cell| cell.to_string().len())
                         .max()
                         .unwrap_or(0);
 
@@ -766,11 +1344,22 @@ impl ResultsViewer {
                 })
                 .collect::<Vec<_>>();
 
-            let mut title = format!(" Results ({} rows) ", result.rows.len());
+            let mut title = if self.table_name.is_some() {
+                format!(" Results ({}) ", self.page_label(result.rows.len()))
+            } else {
+                format!(" Results ({} rows) ", result.rows.len())
+            };
             if let Some(affected) = result.rows_affected {
                 title = format!(" {} rows affected ", affected);
             }
-            if result.execution_time_ms > 0 {
+            // A statement tab shows how long the statement took, like the failed ones do
+            let statement_duration = match self.active_tab {
+                TabMode::Statement(idx) => self.statement_results.get(idx).map(|s| s.duration_ms),
+                _ => None,
+            };
+            if let Some(duration_ms) = statement_duration {
+                title.push_str(&format!("- {}ms ", duration_ms));
+            } else if result.execution_time_ms > 0 {
                 title.push_str(&format!("- {}ms ", result.execution_time_ms));
             }
 
@@ -796,11 +1385,23 @@ impl ResultsViewer {
             if !self.insert_row.is_empty() {
                 title.push_str(&format!("- {} fields ", self.insert_row.len()));
             }
+            if !self.deleted_rows.is_empty() {
+                title.push_str(&format!("- {} deletions ", self.deleted_rows.len()));
+            }
 
             // Add help hints
-            if !self.modified_cells.is_empty() || !self.insert_row.is_empty() {
+            if !self.modified_cells.is_empty()
+                || !self.insert_row.is_empty()
+                || !self.deleted_rows.is_empty()
+            {
                 title.push_str("- Ctrl+D: Discard, Ctrl+S: Save ");
             }
+            if let TabMode::Statement(idx) = self.active_tab {
+                title = format!(" #{}{}", idx + 1, title);
+            }
+            if !self.statement_results.is_empty() {
+                title.push_str("- [/]: Previous/next statement ");
+            }
             title.push_str("- R: Refresh");
 
             // Add status message if present
@@ -989,3 +1590,156 @@ impl ResultsViewer {
         frame.render_widget(paragraph, area);
     }
 }
+
+/// Group digits in threes with a space, e.g. 48213 -> "48 213".
+fn group_digits(n: usize) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(' ');
+        }
+        grouped.push(ch);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewer(columns: &[&str], rows: Vec<Vec<CellValue>>) -> ResultsViewer {
+        let mut viewer = ResultsViewer::new();
+        viewer.result = Some(QueryResult::new(
+            columns.iter().map(|c| c.to_string()).collect(),
+            rows,
+        ));
+        viewer.table_name = Some("people".to_string());
+        viewer
+    }
+
+    fn text(s: &str) -> CellValue {
+        CellValue::Text(s.to_string())
+    }
+
+    #[test]
+    fn updates_by_primary_key() {
+        let mut viewer = viewer(
+            &["id", "name"],
+            vec![
+                vec![CellValue::Integer(1), text("Ann")],
+                vec![CellValue::Integer(2), text("Bob")],
+            ],
+        );
+        viewer.set_cell_text(1, 1, "O'Brien").unwrap();
+        viewer.set_cell_text(0, 1, "Ann").unwrap(); // Unchanged, not an edit
+
+        let statements = viewer
+            .generate_update_statements(&DatabaseType::SQLite, &["id".to_string()])
+            .unwrap();
+        assert_eq!(statements.len(), 1);
+        assert_eq!(
+            statements[0].sql,
+            "UPDATE \"people\" SET \"name\" = ? WHERE \"id\" = ?"
+        );
+        assert_eq!(
+            statements[0].params,
+            vec![text("O'Brien"), CellValue::Integer(2)]
+        );
+        assert_eq!(statements[0].expected_rows, Some(1));
+
+        let postgres = viewer
+            .generate_update_statements(&DatabaseType::PostgreSQL, &["id".to_string()])
+            .unwrap();
+        assert_eq!(
+            postgres[0].sql,
+            "UPDATE \"people\" SET \"name\" = $1 WHERE \"id\" = $2"
+        );
+    }
+
+    #[test]
+    fn refuses_rows_it_cannot_identify() {
+        let mut viewer = viewer(&["id", "name"], vec![vec![CellValue::Null, text("Ann")]]);
+        viewer.set_cell_text(0, 1, "Anna").unwrap();
+
+        let err = viewer
+            .generate_update_statements(&DatabaseType::MySQL, &["id".to_string()])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Row 1 has a NULL primary key, refusing to change it"
+        );
+
+        let err = viewer
+            .generate_update_statements(&DatabaseType::MySQL, &[])
+            .unwrap_err();
+        assert!(err.to_string().contains("has no primary key"));
+
+        let err = viewer
+            .generate_update_statements(&DatabaseType::MySQL, &["uuid".to_string()])
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("uuid of people is not in the result"));
+    }
+
+    #[test]
+    fn falls_back_to_sqlite_rowid() {
+        let mut viewer = viewer(&["name"], vec![vec![text("Ann")], vec![text("Bob")]]);
+        viewer.result.as_mut().unwrap().rowids = Some(vec![10, 20]);
+        viewer.set_cell_text(1, 0, "Bea").unwrap();
+        viewer.deleted_rows.insert(0);
+
+        let updates = viewer
+            .generate_update_statements(&DatabaseType::SQLite, &[])
+            .unwrap();
+        assert_eq!(
+            updates[0].sql,
+            "UPDATE \"people\" SET \"name\" = ? WHERE rowid = ?"
+        );
+        assert_eq!(updates[0].params, vec![text("Bea"), CellValue::Integer(20)]);
+
+        let deletes = viewer
+            .generate_delete_statements(&DatabaseType::SQLite, &[])
+            .unwrap();
+        assert_eq!(deletes.len(), 1);
+        assert_eq!(deletes[0].sql, "DELETE FROM \"people\" WHERE rowid = ?");
+        assert_eq!(deletes[0].params, vec![CellValue::Integer(10)]);
+        assert_eq!(deletes[0].expected_rows, Some(1));
+    }
+
+    #[test]
+    fn null_is_set_apart_from_the_text_null() {
+        let mut viewer = viewer(
+            &["id", "age", "note"],
+            vec![vec![
+                CellValue::Integer(1),
+                CellValue::Integer(30),
+                text("x"),
+            ]],
+        );
+        viewer.set_cell_text(0, 2, "NULL").unwrap();
+        assert_eq!(viewer.modified_cells[&(0, 2)], text("NULL"));
+
+        let err = viewer.set_cell_text(0, 1, "thirty").unwrap_err();
+        assert_eq!(err.to_string(), "age: 'thirty' is not an integer");
+        assert!(!viewer.modified_cells.contains_key(&(0, 1)));
+
+        viewer.table_state.select(Some(0));
+        viewer.selected_column = 1;
+        viewer.set_null();
+        assert_eq!(viewer.modified_cells[&(0, 1)], CellValue::Null);
+
+        let statements = viewer
+            .generate_update_statements(&DatabaseType::SQLite, &["id".to_string()])
+            .unwrap();
+        assert_eq!(
+            statements[0].sql,
+            "UPDATE \"people\" SET \"age\" = ?, \"note\" = ? WHERE \"id\" = ?"
+        );
+        assert_eq!(
+            statements[0].params,
+            vec![CellValue::Null, text("NULL"), CellValue::Integer(1)]
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use super::picker::{Picker, PickerItem};
+use crate::config::SavedQuery;
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+
+/// `:queries` popup: pick a saved query by name and load it into the editor. Items are
+/// (scope, query) pairs; the scope is a connection name or "global".
+pub type SavedQueryPicker = Picker<(String, SavedQuery)>;
+
+impl PickerItem for (String, SavedQuery) {
+    const TITLE: &'static str = "Saved queries";
+    const EMPTY: &'static str = "No saved queries, use :save-query <name> to add one";
+
+    fn search_text(&self) -> &str {
+        &self.1.name
+    }
+
+    fn line(&self) -> Line<'_> {
+        let (scope, saved) = self;
+        let query = saved.query.split_whitespace().collect::<Vec<_>>().join(" ");
+        Line::from(vec![
+            Span::styled(
+                format!("{:<24.24} ", saved.name),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(
+                format!("{:<16.16} ", scope),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::styled(query, Style::default().fg(Color::DarkGray)),
+        ])
+    }
+}
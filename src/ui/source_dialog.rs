@@ -0,0 +1,273 @@
+use super::layout::centered_rect;
+use crate::sql::Statement;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph},
+    Frame,
+};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceState {
+    Ready,
+    Running,
+    Finished,
+}
+
+/// What happened when a statement of the script ran.
+#[derive(Debug, Clone)]
+pub struct StatementOutcome {
+    pub summary: Result<String, String>, // "3 rows affected" or the error
+    pub duration_ms: u64,
+}
+
+/// `:source` popup: pick stop-on-error or continue, run the script's statements one by one
+/// and list the outcome of each.
+#[derive(Debug)]
+pub struct SourceDialog {
+    pub visible: bool,
+    pub state: SourceState,
+    pub path: PathBuf,
+    pub connection_id: usize,
+    pub statements: Vec<Statement>,
+    pub outcomes: Vec<StatementOutcome>, // One per statement run so far
+    pub stop_on_error: bool,
+    pub selected: usize,
+    scroll: usize,
+}
+
+impl SourceDialog {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            state: SourceState::Ready,
+            path: PathBuf::new(),
+            connection_id: 0,
+            statements: Vec::new(),
+            outcomes: Vec::new(),
+            stop_on_error: true,
+            selected: 0,
+            scroll: 0,
+        }
+    }
+
+    pub fn show(&mut self, path: PathBuf, connection_id: usize, statements: Vec<Statement>) {
+        self.visible = true;
+        self.state = SourceState::Ready;
+        self.path = path;
+        self.connection_id = connection_id;
+        self.statements = statements;
+        self.outcomes.clear();
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.statements.clear();
+        self.outcomes.clear();
+    }
+
+    pub fn toggle_stop_on_error(&mut self) {
+        self.stop_on_error = !self.stop_on_error;
+    }
+
+    pub fn start(&mut self) {
+        self.state = SourceState::Running;
+        self.outcomes.clear();
+    }
+
+    /// Record the outcome of the statement that just ran and stop when the script is done,
+    /// or on an error when stopping on errors.
+    pub fn record(&mut self, outcome: StatementOutcome) {
+        let failed = outcome.summary.is_err();
+        self.outcomes.push(outcome);
+        self.selected = self.outcomes.len() - 1;
+        if self.outcomes.len() == self.statements.len() || (failed && self.stop_on_error) {
+            self.finish();
+        }
+    }
+
+    /// Stop running; statements without an outcome are reported as not run.
+    pub fn finish(&mut self) {
+        self.state = SourceState::Finished;
+        // Show the first failure, if any
+        if let Some(idx) = self.outcomes.iter().position(|o| o.summary.is_err()) {
+            self.selected = idx;
+        }
+    }
+
+    pub fn failures(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.summary.is_err()).count()
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.statements.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        if !self.visible {
+            return;
+        }
+
+        let popup_area = centered_rect(80, 70, area);
+        frame.render_widget(Clear, popup_area);
+
+        let file_name = self
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let block = Block::default()
+            .title(format!(
+                " Source {} ({} statements) ",
+                file_name,
+                self.statements.len()
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(2),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let header = match self.state {
+            SourceState::Ready => Line::from(vec![
+                Span::raw("On error: "),
+                Span::styled(
+                    if self.stop_on_error {
+                        "stop"
+                    } else {
+                        "continue with the next statement"
+                    },
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            _ => {
+                let failures = self.failures();
+                let mut spans = vec![Span::styled(
+                    format!("{} succeeded", self.outcomes.len() - failures),
+                    Style::default().fg(Color::Green),
+                )];
+                if failures > 0 {
+                    spans.push(Span::raw(", "));
+                    spans.push(Span::styled(
+                        format!("{} failed", failures),
+                        Style::default().fg(Color::Red),
+                    ));
+                }
+                let not_run = self.statements.len() - self.outcomes.len();
+                if not_run > 0 && self.state == SourceState::Finished {
+                    spans.push(Span::raw(format!(", {} not run", not_run)));
+                }
+                Line::from(spans)
+            }
+        };
+        frame.render_widget(Paragraph::new(header), chunks[0]);
+
+        // Keep the selection on screen
+        let height = chunks[1].height as usize;
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if height > 0 && self.selected >= self.scroll + height {
+            self.scroll = self.selected + 1 - height;
+        }
+
+        let mut lines: Vec<Line> = Vec::new();
+        for (idx, statement) in self
+            .statements
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(height)
+        {
+            let (mark, result, style) = match self.outcomes.get(idx) {
+                Some(StatementOutcome {
+                    summary: Ok(summary),
+                    duration_ms,
+                }) => (
+                    "✓",
+                    format!("{} ({} ms)", summary, duration_ms),
+                    Style::default().fg(Color::Green),
+                ),
+                Some(StatementOutcome {
+                    summary: Err(error),
+                    ..
+                }) => (
+                    "✗",
+                    error.lines().next().unwrap_or("").to_string(),
+                    Style::default().fg(Color::Red),
+                ),
+                None => (" ", String::new(), Style::default().fg(Color::DarkGray)),
+            };
+            let text = statement
+                .text
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let mut line = Line::from(vec![
+                Span::styled(format!("{} ", mark), style),
+                Span::styled(
+                    format!("{:>5} ", statement.line),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(format!("{:<40.40} ", text)),
+                Span::styled(result, style),
+            ]);
+            if idx == self.selected {
+                line = line.style(Style::default().bg(Color::Rgb(60, 60, 80)));
+            }
+            lines.push(line);
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[1]);
+
+        // Full error of the selected statement, or progress while running
+        if self.state == SourceState::Running {
+            let total = self.statements.len().max(1);
+            let gauge = Gauge::default()
+                .gauge_style(Style::default().fg(Color::Green))
+                .ratio((self.outcomes.len() as f64 / total as f64).min(1.0))
+                .label(format!(
+                    "{}/{} statements",
+                    self.outcomes.len(),
+                    self.statements.len()
+                ));
+            frame.render_widget(gauge, chunks[2]);
+        } else if let Some(Err(error)) = self.outcomes.get(self.selected).map(|o| &o.summary) {
+            let error = Paragraph::new(error.clone())
+                .style(Style::default().fg(Color::Red))
+                .wrap(ratatui::widgets::Wrap { trim: true });
+            frame.render_widget(error, chunks[2]);
+        }
+
+        let help = match self.state {
+            SourceState::Ready => {
+                "Tab: Stop/continue on error  Enter: Run  j/k: Scroll  Esc: Cancel"
+            }
+            SourceState::Running => "Esc/Ctrl+C: Cancel the current statement and stop",
+            SourceState::Finished => "j/k: Select  Esc/Enter: Close",
+        };
+        frame.render_widget(
+            Paragraph::new(help).style(Style::default().fg(Color::DarkGray)),
+            chunks[3],
+        );
+    }
+}
@@ -0,0 +1,105 @@
+/// Editor text and cursor to restore after an undo or redo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub content: Vec<String>,
+    pub cursor: (usize, usize), // (line, column)
+}
+
+#[derive(Debug)]
+struct UndoNode {
+    content: Vec<String>, // Text after the change
+    parent: Option<usize>,
+    redo_child: Option<usize>, // Child that redo goes to: the newest or last undone
+    cursor_before: (usize, usize), // Where the change started
+    cursor_after: (usize, usize),
+}
+
+/// Vim-style undo tree. Making a change after an undo starts a new branch instead of
+/// discarding the undone changes; `u`/`r` walk up and down the current branch, `g-`/`g+`
+/// step through every state in the order the changes were made.
+#[derive(Debug)]
+pub struct UndoTree {
+    nodes: Vec<UndoNode>, // Index = change number; 0 is the text before any change
+    current: usize,
+}
+
+impl UndoTree {
+    pub fn new(content: Vec<String>) -> Self {
+        Self {
+            nodes: vec![UndoNode {
+                content,
+                parent: None,
+                redo_child: None,
+                cursor_before: (0, 0),
+                cursor_after: (0, 0),
+            }],
+            current: 0,
+        }
+    }
+
+    /// The text the current state holds, to tell whether an edit changed anything.
+    pub fn content(&self) -> &[String] {
+        &self.nodes[self.current].content
+    }
+
+    pub fn record(
+        &mut self,
+        content: Vec<String>,
+        cursor_before: (usize, usize),
+        cursor_after: (usize, usize),
+    ) {
+        let idx = self.nodes.len();
+        self.nodes.push(UndoNode {
+            content,
+            parent: Some(self.current),
+            redo_child: None,
+            cursor_before,
+            cursor_after,
+        });
+        self.nodes[self.current].redo_child = Some(idx);
+        self.current = idx;
+    }
+
+    /// Go back to the state before the current change; the cursor returns to where the
+    /// change was made.
+    pub fn undo(&mut self) -> Option<Snapshot> {
+        let node = &self.nodes[self.current];
+        let parent = node.parent?;
+        let cursor = node.cursor_before;
+        self.nodes[parent].redo_child = Some(self.current);
+        self.current = parent;
+        Some(Snapshot {
+            content: self.nodes[parent].content.clone(),
+            cursor,
+        })
+    }
+
+    pub fn redo(&mut self) -> Option<Snapshot> {
+        let child = self.nodes[self.current].redo_child?;
+        self.current = child;
+        Some(self.snapshot())
+    }
+
+    /// The state made just before the current one, on any branch (`g-`).
+    pub fn older(&mut self) -> Option<Snapshot> {
+        self.current = self.current.checked_sub(1)?;
+        Some(self.snapshot())
+    }
+
+    /// The state made just after the current one, on any branch (`g+`).
+    pub fn newer(&mut self) -> Option<Snapshot> {
+        if self.current + 1 >= self.nodes.len() {
+            return None;
+        }
+        self.current += 1;
+        Some(self.snapshot())
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let node = &self.nodes[self.current];
+        Snapshot {
+            content: node.content.clone(),
+            cursor: node.cursor_after,
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod mode;
+pub mod motion;
+pub mod register;
 
 pub use mode::{VimCommand, VimMode, VimState};
@@ -1,3 +1,5 @@
+use super::motion::{Motion, Operator, Target, TextObject};
+use super::register::is_register_name;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,13 +21,22 @@ impl VimMode {
     }
 }
 
+/// What the keys typed so far in the query editor add up to.
+enum Parsed {
+    Command(VimCommand),
+    Pending,   // Needs more keys, as after `d` or `ci`
+    Invalid,   // Not a command; the keys are dropped
+    Unhandled, // Not an editor command; handled like in the other panes
+}
+
 #[derive(Debug, Clone)]
 pub struct VimState {
     pub mode: VimMode,
-    pub command_buffer: String,
-    #[allow(dead_code)]
-    pub register: Option<String>,
+    pub command_buffer: String, // Command line text, or the keys of a pending normal-mode command
+    pub register: Option<char>, // Named with `"x` for the next command
     pub count: Option<usize>,
+    pub editor_focused: bool, // Normal-mode keys are parsed as operators and motions
+    last_find: Option<(char, bool, bool)>, // Last f/F/t/T: character, forward, till
 }
 
 impl Default for VimState {
@@ -35,6 +46,8 @@ impl Default for VimState {
             command_buffer: String::new(),
             register: None,
             count: None,
+            editor_focused: false,
+            last_find: None,
         }
     }
 }
@@ -96,6 +109,37 @@ impl VimState {
         self.count.unwrap_or(1)
     }
 
+    /// Switch between parsing keys for the query editor and for the other panes; a half-typed
+    /// command is dropped.
+    pub fn set_editor_focused(&mut self, focused: bool) {
+        if self.editor_focused != focused && self.mode == VimMode::Normal {
+            self.reset_state();
+        }
+        self.editor_focused = focused;
+    }
+
+    /// The register the next command uses, if one was named; it applies to that command only.
+    pub fn take_register(&mut self) -> Option<char> {
+        self.register.take()
+    }
+
+    /// `"` and the register name before a command. Returns whether the key was used.
+    fn handle_register_key(&mut self, key: KeyEvent) -> bool {
+        if self.command_buffer == "\"" {
+            self.command_buffer.clear();
+            match key.code {
+                KeyCode::Char(c) if is_register_name(c) => self.register = Some(c),
+                _ => self.register = None,
+            }
+            return true;
+        }
+        if key.code == KeyCode::Char('"') && self.command_buffer.is_empty() {
+            self.command_buffer.push('"');
+            return true;
+        }
+        false
+    }
+
     fn reset_state(&mut self) {
         self.command_buffer.clear();
         self.count = None;
@@ -111,6 +155,29 @@ impl VimState {
     }
 
     fn handle_normal_mode(&mut self, key: KeyEvent) -> Option<VimCommand> {
+        if key.code == KeyCode::Esc {
+            self.register = None;
+        }
+        if self.handle_register_key(key) {
+            return None;
+        }
+
+        if self.editor_focused && !key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char(c) => {
+                    if let Some(command) = self.handle_editor_key(c) {
+                        return command;
+                    }
+                }
+                // Esc or any other key cancels a pending operator
+                _ if !self.command_buffer.is_empty() => {
+                    self.reset_state();
+                    return None;
+                }
+                _ => {}
+            }
+        }
+
         if key.modifiers.contains(KeyModifiers::CONTROL) {
             return match key.code {
                 KeyCode::Char('c') => Some(VimCommand::Quit),
@@ -118,6 +185,8 @@ impl VimState {
                 KeyCode::Char('r') => Some(VimCommand::ExecuteAllQueries),
                 KeyCode::Char('s') => Some(VimCommand::SaveAllEdits),
                 KeyCode::Char('n') => Some(VimCommand::EnterInsertRowMode),
+                KeyCode::Char('f') => Some(VimCommand::NextPage),
+                KeyCode::Char('b') => Some(VimCommand::PrevPage),
                 _ => None,
             };
         }
@@ -156,6 +225,10 @@ impl VimState {
                 self.enter_visual_mode();
                 Some(VimCommand::EnterVisualMode)
             }
+            KeyCode::Char('V') => {
+                self.enter_visual_mode();
+                Some(VimCommand::EnterVisualLineMode)
+            }
             KeyCode::Char(':') => {
                 self.enter_command_mode();
                 Some(VimCommand::EnterCommandMode)
@@ -163,16 +236,30 @@ impl VimState {
             KeyCode::Char('x') => Some(VimCommand::DeleteChar),
             KeyCode::Char('X') => Some(VimCommand::DeleteConnection),
             KeyCode::Char('C') => Some(VimCommand::OpenConnectionManager),
-            KeyCode::Char('d') => Some(VimCommand::Delete),
+            KeyCode::Char('d') if matches!(self.command_buffer.chars().last(), Some('d')) => {
+                self.command_buffer.clear();
+                Some(VimCommand::DeleteLine)
+            }
+            KeyCode::Char('d') => {
+                self.command_buffer.push('d');
+                None
+            }
             KeyCode::Char('y') if matches!(self.command_buffer.chars().last(), Some('y')) => {
                 self.command_buffer.clear();
                 Some(VimCommand::CopyCellValue)
             }
             KeyCode::Char('y') => {
                 self.command_buffer.push('y');
-                Some(VimCommand::Yank)
+                None
             }
-            KeyCode::Char('p') => Some(VimCommand::Paste),
+            KeyCode::Char('p') => Some(VimCommand::Paste {
+                before: false,
+                count: self.get_count(),
+            }),
+            KeyCode::Char('P') => Some(VimCommand::Paste {
+                before: true,
+                count: self.get_count(),
+            }),
             KeyCode::Char('u') => Some(VimCommand::Undo),
             KeyCode::Char('r') => Some(VimCommand::Redo),
             KeyCode::Char('R') => Some(VimCommand::RefreshData),
@@ -180,6 +267,24 @@ impl VimState {
                 self.command_buffer.clear();
                 Some(VimCommand::GotoTop)
             }
+            KeyCode::Char('-') if matches!(self.command_buffer.chars().last(), Some('g')) => {
+                self.command_buffer.clear();
+                Some(VimCommand::UndoOlder)
+            }
+            KeyCode::Char('+') if matches!(self.command_buffer.chars().last(), Some('g')) => {
+                self.command_buffer.clear();
+                Some(VimCommand::UndoNewer)
+            }
+            KeyCode::Char('t') if matches!(self.command_buffer.chars().last(), Some('g')) => {
+                self.command_buffer.clear();
+                let count = self.count.take();
+                Some(VimCommand::NextBuffer(count))
+            }
+            KeyCode::Char('T') if matches!(self.command_buffer.chars().last(), Some('g')) => {
+                self.command_buffer.clear();
+                let count = self.count.take().unwrap_or(1);
+                Some(VimCommand::PrevBuffer(count))
+            }
             KeyCode::Char('g') => {
                 self.command_buffer.push('g');
                 None
@@ -208,16 +313,209 @@ impl VimState {
         }
     }
 
+    /// Feed a normal-mode key to the editor's operator + motion parser. Returns `None` for
+    /// keys it doesn't know, which are then handled like in the other panes.
+    fn handle_editor_key(&mut self, c: char) -> Option<Option<VimCommand>> {
+        // A count before the command; 0 without a count is a motion
+        if self.command_buffer.is_empty()
+            && c.is_ascii_digit()
+            && (c != '0' || self.count.is_some())
+        {
+            self.set_count(c.to_digit(10).unwrap_or(0) as usize);
+            return Some(None);
+        }
+
+        let mut keys = self.command_buffer.clone();
+        keys.push(c);
+        match self.parse_editor_keys(&keys) {
+            Parsed::Command(command) => {
+                self.reset_state();
+                Some(Some(command))
+            }
+            Parsed::Pending => {
+                self.command_buffer = keys;
+                Some(None)
+            }
+            Parsed::Invalid => {
+                self.reset_state();
+                Some(None)
+            }
+            Parsed::Unhandled => None,
+        }
+    }
+
+    /// Parse `[operator][count](motion | text object | operator again)`, or a bare motion.
+    fn parse_editor_keys(&mut self, keys: &str) -> Parsed {
+        let mut chars = keys.chars();
+        let Some(first) = chars.next() else {
+            return Parsed::Pending;
+        };
+        let rest = chars.as_str();
+
+        let operator = match first {
+            'd' => Operator::Delete,
+            'c' => Operator::Change,
+            'y' => Operator::Yank,
+            // x, D and C are short for dl, d$ and c$
+            'x' | 'D' | 'C' => {
+                let (operator, motion) = match first {
+                    'x' => (Operator::Delete, Motion::Right),
+                    'D' => (Operator::Delete, Motion::LineEnd),
+                    _ => (Operator::Change, Motion::LineEnd),
+                };
+                return Parsed::Command(VimCommand::Operate {
+                    operator,
+                    target: Target::Motion(motion),
+                    count: self.count,
+                });
+            }
+            'p' | 'P' => {
+                return Parsed::Command(VimCommand::Paste {
+                    before: first == 'P',
+                    count: self.get_count(),
+                })
+            }
+            'g' => {
+                return match rest {
+                    "" => Parsed::Pending,
+                    "g" => Parsed::Command(VimCommand::Motion(Motion::FirstLine, self.count)),
+                    "-" => Parsed::Command(VimCommand::UndoOlder),
+                    "+" => Parsed::Command(VimCommand::UndoNewer),
+                    "t" => Parsed::Command(VimCommand::NextBuffer(self.count)),
+                    "T" => Parsed::Command(VimCommand::PrevBuffer(self.get_count())),
+                    _ => Parsed::Invalid,
+                }
+            }
+            _ => {
+                return match self.parse_motion(keys) {
+                    Ok(Some(motion)) => Parsed::Command(VimCommand::Motion(motion, self.count)),
+                    Ok(None) => Parsed::Pending,
+                    Err(()) => Parsed::Unhandled,
+                }
+            }
+        };
+
+        // A second count after the operator multiplies the first: 2d3w deletes six words
+        let digits = if rest.starts_with('0') {
+            0
+        } else {
+            rest.find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len())
+        };
+        let (count_digits, target_keys) = rest.split_at(digits);
+        let count = match (self.count, count_digits.parse::<usize>().ok()) {
+            (None, None) => None,
+            (first, second) => Some(first.unwrap_or(1) * second.unwrap_or(1)),
+        };
+
+        let mut target_chars = target_keys.chars();
+        let target = match target_chars.next() {
+            None => return Parsed::Pending,
+            Some(c) if c == first => Target::Line,
+            Some(kind @ ('i' | 'a')) => {
+                let around = kind == 'a';
+                let object = match target_chars.next() {
+                    None => return Parsed::Pending,
+                    Some('w') => TextObject::Word { big: false, around },
+                    Some('W') => TextObject::Word { big: true, around },
+                    Some(quote @ ('\'' | '"' | '`')) => TextObject::Quote { quote, around },
+                    Some('(' | ')' | 'b') => TextObject::Block {
+                        open: '(',
+                        close: ')',
+                        around,
+                    },
+                    Some('[' | ']') => TextObject::Block {
+                        open: '[',
+                        close: ']',
+                        around,
+                    },
+                    Some('{' | '}' | 'B') => TextObject::Block {
+                        open: '{',
+                        close: '}',
+                        around,
+                    },
+                    Some('<' | '>') => TextObject::Block {
+                        open: '<',
+                        close: '>',
+                        around,
+                    },
+                    Some(_) => return Parsed::Invalid,
+                };
+                Target::TextObject(object)
+            }
+            Some('g') => match target_chars.as_str() {
+                "" => return Parsed::Pending,
+                "g" => Target::Motion(Motion::FirstLine),
+                _ => return Parsed::Invalid,
+            },
+            Some(_) => match self.parse_motion(target_keys) {
+                Ok(Some(motion)) => Target::Motion(motion),
+                Ok(None) => return Parsed::Pending,
+                Err(()) => return Parsed::Invalid,
+            },
+        };
+        Parsed::Command(VimCommand::Operate {
+            operator,
+            target,
+            count,
+        })
+    }
+
+    /// A motion: `Ok(None)` while waiting for the character of f/F/t/T.
+    fn parse_motion(&mut self, keys: &str) -> Result<Option<Motion>, ()> {
+        let mut chars = keys.chars();
+        let motion = match chars.next().ok_or(())? {
+            'h' => Motion::Left,
+            'l' | ' ' => Motion::Right,
+            'j' => Motion::Down,
+            'k' => Motion::Up,
+            'w' => Motion::WordStart,
+            'b' => Motion::WordBack,
+            'e' => Motion::WordEnd,
+            'W' => Motion::BigWordStart,
+            'B' => Motion::BigWordBack,
+            'E' => Motion::BigWordEnd,
+            '0' => Motion::LineStart,
+            '^' => Motion::FirstNonBlank,
+            '$' => Motion::LineEnd,
+            'G' => Motion::LastLine,
+            find @ ('f' | 'F' | 't' | 'T') => {
+                let Some(ch) = chars.next() else {
+                    return Ok(None);
+                };
+                let forward = find.is_lowercase();
+                let till = matches!(find, 't' | 'T');
+                self.last_find = Some((ch, forward, till));
+                Motion::FindChar { ch, forward, till }
+            }
+            // Repeat the last f/t, in the same or the opposite direction
+            repeat @ (';' | ',') => {
+                let (ch, forward, till) = self.last_find.ok_or(())?;
+                Motion::FindChar {
+                    ch,
+                    forward: forward == (repeat == ';'),
+                    till,
+                }
+            }
+            _ => return Err(()),
+        };
+        Ok(Some(motion))
+    }
+
     fn handle_insert_mode(&mut self, key: KeyEvent) -> Option<VimCommand> {
         match key.code {
             KeyCode::Esc => {
                 self.enter_normal_mode();
                 Some(VimCommand::ExitInsertMode)
             }
+            KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Some(VimCommand::Complete)
+            }
             KeyCode::Char(c) => Some(VimCommand::InsertChar(c)),
             KeyCode::Backspace => Some(VimCommand::Backspace),
+            KeyCode::Delete => Some(VimCommand::SetNull),
             KeyCode::Enter => Some(VimCommand::InsertNewline),
-            KeyCode::Tab => Some(VimCommand::InsertTab),
+            KeyCode::Tab => Some(VimCommand::Complete),
             KeyCode::Left => Some(VimCommand::MoveLeft(1)),
             KeyCode::Right => Some(VimCommand::MoveRight(1)),
             KeyCode::Up => Some(VimCommand::MoveUp(1)),
@@ -227,8 +525,12 @@ impl VimState {
     }
 
     fn handle_visual_mode(&mut self, key: KeyEvent) -> Option<VimCommand> {
+        if self.handle_register_key(key) {
+            return None;
+        }
         match key.code {
             KeyCode::Esc => {
+                self.register = None;
                 self.enter_normal_mode();
                 Some(VimCommand::ExitVisualMode)
             }
@@ -279,12 +581,15 @@ impl VimState {
 #[derive(Debug, Clone, PartialEq)]
 pub enum VimCommand {
     // Movement
+    Motion(Motion, Option<usize>), // Query editor motion with its count
     MoveLeft(usize),
     MoveRight(usize),
     MoveUp(usize),
     MoveDown(usize),
     GotoTop,
     GotoBottom,
+    NextPage,
+    PrevPage,
     GotoLineStart,
     GotoLineEnd,
     NextWord,
@@ -299,23 +604,34 @@ pub enum VimCommand {
     OpenLineAbove,
     ExitInsertMode,
     EnterVisualMode,
+    EnterVisualLineMode,
     ExitVisualMode,
     EnterCommandMode,
     CancelCommand,
 
     // Editing
+    Operate {
+        operator: Operator,
+        target: Target,
+        count: Option<usize>,
+    },
     InsertChar(char),
     InsertNewline,
-    InsertTab,
+    Complete,
+    SetNull, // Delete on a grid cell being edited
     Backspace,
     DeleteChar,
-    Delete,
+    DeleteLine,
     DeleteSelection,
-    Yank,
     YankSelection,
-    Paste,
+    Paste {
+        before: bool, // P: before the cursor, or above the line
+        count: usize,
+    },
     Undo,
     Redo,
+    UndoOlder,
+    UndoNewer,
 
     // Visual mode
     ExtendLeft,
@@ -331,6 +647,8 @@ pub enum VimCommand {
     // Navigation
     NextPane,
     PrevPane,
+    NextBuffer(Option<usize>), // gt; with a count, go to that buffer
+    PrevBuffer(usize),
 
     // Actions
     Activate,
@@ -0,0 +1,659 @@
+use crate::sql::lexer;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordStart,    // w
+    WordBack,     // b
+    WordEnd,      // e
+    BigWordStart, // W
+    BigWordBack,  // B
+    BigWordEnd,   // E
+    LineStart,    // 0
+    FirstNonBlank,
+    LineEnd,
+    FirstLine,                                        // gg, or the line given as count
+    LastLine,                                         // G, or the line given as count
+    FindChar { ch: char, forward: bool, till: bool }, // f, F, t, T
+}
+
+impl Motion {
+    /// Operators on these act on whole lines.
+    pub fn is_linewise(&self) -> bool {
+        matches!(
+            self,
+            Motion::Up | Motion::Down | Motion::FirstLine | Motion::LastLine
+        )
+    }
+
+    /// Operators on these include the character the motion lands on.
+    pub fn is_inclusive(&self) -> bool {
+        matches!(
+            self,
+            Motion::WordEnd
+                | Motion::BigWordEnd
+                | Motion::LineEnd
+                | Motion::FindChar { forward: true, .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextObject {
+    Word {
+        big: bool,
+        around: bool,
+    }, // iw, aw, iW, aW
+    Quote {
+        quote: char,
+        around: bool,
+    }, // i' a' i" a" i` a`
+    Block {
+        open: char,
+        close: char,
+        around: bool,
+    }, // i( a( i[ a[ i{ a{ i< a<
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Motion(Motion),
+    TextObject(TextObject),
+    Line, // dd, cc, yy
+}
+
+/// Text taken by a delete or yank, and whether it is whole lines (pasted below the line
+/// rather than after the cursor).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YankedText {
+    pub text: String,
+    pub linewise: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Blank,
+    Word,
+    Punctuation,
+}
+
+/// Class of the grapheme cluster starting with `c`; a combining accent goes with its letter.
+fn class(c: char, big: bool) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Blank
+    } else if big || c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+/// First char of the grapheme cluster at `pos`.
+fn char_at(text: &str, pos: usize) -> Option<char> {
+    text.get(pos..).and_then(|rest| rest.chars().next())
+}
+
+/// Start of the grapheme cluster after the one at `pos`, if there is one at `pos`.
+fn next(text: &str, pos: usize) -> Option<usize> {
+    (pos < text.len()).then(|| next_grapheme(text, pos))
+}
+
+fn prev(text: &str, pos: usize) -> Option<usize> {
+    (pos > 0).then(|| prev_grapheme(text, pos))
+}
+
+/// Start of the grapheme cluster before `pos`: `é` typed as `e` and a combining accent, or a
+/// flag emoji, is stepped over as one character.
+pub fn prev_grapheme(text: &str, pos: usize) -> usize {
+    text[..pos]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(pos, |(idx, _)| idx)
+}
+
+pub fn next_grapheme(text: &str, pos: usize) -> usize {
+    text[pos..]
+        .graphemes(true)
+        .next()
+        .map_or(pos, |g| pos + g.len())
+}
+
+/// Byte offset in `line` of the grapheme drawn at screen column `width`; wide characters
+/// cover two columns.
+pub fn offset_at_width(line: &str, width: usize) -> usize {
+    let mut columns = 0;
+    for (idx, grapheme) in line.grapheme_indices(true) {
+        columns += grapheme.width();
+        if columns > width {
+            return idx;
+        }
+    }
+    line.len()
+}
+
+pub fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Offset of the `\n` ending the line, or the end of the text.
+pub fn line_end(text: &str, pos: usize) -> usize {
+    text[pos..].find('\n').map_or(text.len(), |i| pos + i)
+}
+
+fn is_empty_line(text: &str, pos: usize) -> bool {
+    line_start(text, pos) == pos && line_end(text, pos) == pos
+}
+
+/// Start of the line `lines` lines below (negative: above) the one `pos` is on.
+fn offset_line(text: &str, pos: usize, lines: isize) -> usize {
+    let mut start = line_start(text, pos);
+    if lines > 0 {
+        for _ in 0..lines {
+            match text[start..].find('\n') {
+                Some(i) => start += i + 1,
+                None => break,
+            }
+        }
+    } else {
+        for _ in 0..lines.unsigned_abs() {
+            if start == 0 {
+                break;
+            }
+            start = line_start(text, start - 1);
+        }
+    }
+    start
+}
+
+/// Start of the 0-based line `line`, or of the last line.
+fn nth_line(text: &str, line: usize) -> usize {
+    offset_line(text, 0, line as isize)
+}
+
+pub fn first_non_blank(text: &str, pos: usize) -> usize {
+    let start = line_start(text, pos);
+    let end = line_end(text, pos);
+    text[start..end]
+        .find(|c: char| !c.is_whitespace())
+        .map_or(end, |i| start + i)
+}
+
+/// Where `motion` repeated `count` times takes the cursor, or `None` when it can't move.
+/// `text` is the editor text joined with `\n`; positions are byte offsets.
+pub fn motion_target(
+    text: &str,
+    pos: usize,
+    motion: Motion,
+    count: Option<usize>,
+) -> Option<usize> {
+    let n = count.unwrap_or(1).max(1);
+    match motion {
+        Motion::Left => {
+            let start = line_start(text, pos);
+            Some(
+                text[start..pos]
+                    .grapheme_indices(true)
+                    .rev()
+                    .nth(n - 1)
+                    .map_or(start, |(idx, _)| start + idx),
+            )
+        }
+        Motion::Right => {
+            let end = line_end(text, pos);
+            Some(
+                pos + text[pos..end]
+                    .graphemes(true)
+                    .take(n)
+                    .map(str::len)
+                    .sum::<usize>(),
+            )
+        }
+        Motion::Up | Motion::Down => {
+            let start = line_start(text, pos);
+            let column = text[start..pos].width();
+            let lines = if motion == Motion::Up {
+                -(n as isize)
+            } else {
+                n as isize
+            };
+            let target = offset_line(text, pos, lines);
+            if target == start {
+                return None;
+            }
+            let end = line_end(text, target);
+            Some(target + offset_at_width(&text[target..end], column))
+        }
+        Motion::WordStart | Motion::BigWordStart => {
+            let big = motion == Motion::BigWordStart;
+            let mut pos = pos;
+            for _ in 0..n {
+                pos = word_start(text, pos, big);
+            }
+            Some(pos)
+        }
+        Motion::WordBack | Motion::BigWordBack => {
+            let big = motion == Motion::BigWordBack;
+            let mut pos = pos;
+            for _ in 0..n {
+                pos = word_back(text, pos, big);
+            }
+            Some(pos)
+        }
+        Motion::WordEnd | Motion::BigWordEnd => {
+            let big = motion == Motion::BigWordEnd;
+            let mut pos = pos;
+            for _ in 0..n {
+                pos = word_end(text, pos, big);
+            }
+            Some(pos)
+        }
+        Motion::LineStart => Some(line_start(text, pos)),
+        Motion::FirstNonBlank => Some(first_non_blank(text, pos)),
+        Motion::LineEnd => Some(line_end(text, offset_line(text, pos, n as isize - 1))),
+        Motion::FirstLine => Some(first_non_blank(
+            text,
+            nth_line(text, count.map_or(0, |c| c.saturating_sub(1))),
+        )),
+        Motion::LastLine => {
+            let line = match count {
+                Some(c) => nth_line(text, c.saturating_sub(1)),
+                None => line_start(text, text.len()),
+            };
+            Some(first_non_blank(text, line))
+        }
+        Motion::FindChar { ch, forward, till } => find_char(text, pos, ch, forward, till, n),
+    }
+}
+
+/// `w`: the start of the next word, or an empty line.
+fn word_start(text: &str, pos: usize, big: bool) -> usize {
+    let mut pos = pos;
+    if let Some(c) = char_at(text, pos) {
+        let start_class = class(c, big);
+        if start_class != CharClass::Blank {
+            while char_at(text, pos).is_some_and(|c| class(c, big) == start_class) {
+                pos = next(text, pos).unwrap_or(text.len());
+            }
+        }
+    }
+    let start = pos;
+    while let Some(c) = char_at(text, pos) {
+        if class(c, big) != CharClass::Blank || (pos != start && is_empty_line(text, pos)) {
+            break;
+        }
+        pos = next(text, pos).unwrap_or(text.len());
+    }
+    pos
+}
+
+/// `b`: the start of the word before the cursor, or of the word it is in.
+fn word_back(text: &str, pos: usize, big: bool) -> usize {
+    let Some(mut pos) = prev(text, pos) else {
+        return pos;
+    };
+    while let Some(c) = char_at(text, pos) {
+        if class(c, big) != CharClass::Blank || is_empty_line(text, pos) {
+            break;
+        }
+        match prev(text, pos) {
+            Some(p) => pos = p,
+            None => return 0,
+        }
+    }
+    let Some(c) = char_at(text, pos) else {
+        return pos;
+    };
+    let word_class = class(c, big);
+    if word_class == CharClass::Blank {
+        return pos;
+    }
+    while let Some(p) = prev(text, pos) {
+        if char_at(text, p).is_some_and(|c| class(c, big) == word_class) {
+            pos = p;
+        } else {
+            break;
+        }
+    }
+    pos
+}
+
+/// `e`: the last character of the word after the cursor, or of the word it is in.
+fn word_end(text: &str, pos: usize, big: bool) -> usize {
+    let Some(mut pos) = next(text, pos).filter(|&p| p < text.len()) else {
+        return pos;
+    };
+    while char_at(text, pos).is_some_and(|c| class(c, big) == CharClass::Blank) {
+        match next(text, pos).filter(|&p| p < text.len()) {
+            Some(p) => pos = p,
+            None => return pos,
+        }
+    }
+    let Some(c) = char_at(text, pos) else {
+        return pos;
+    };
+    let word_class = class(c, big);
+    while let Some(p) = next(text, pos) {
+        if char_at(text, p).is_some_and(|c| class(c, big) == word_class) {
+            pos = p;
+        } else {
+            break;
+        }
+    }
+    pos
+}
+
+/// `f`/`t` (forward) and `F`/`T`: the `n`th `ch` on the cursor's line.
+fn find_char(
+    text: &str,
+    pos: usize,
+    ch: char,
+    forward: bool,
+    till: bool,
+    n: usize,
+) -> Option<usize> {
+    let found = if forward {
+        let from = next(text, pos)?;
+        let end = line_end(text, pos);
+        let (idx, _) = text[from..end]
+            .grapheme_indices(true)
+            .filter(|(_, g)| g.starts_with(ch))
+            .nth(n - 1)?;
+        from + idx
+    } else {
+        let start = line_start(text, pos);
+        let (idx, _) = text[start..pos]
+            .grapheme_indices(true)
+            .rev()
+            .filter(|(_, g)| g.starts_with(ch))
+            .nth(n - 1)?;
+        start + idx
+    };
+    match (till, forward) {
+        (false, _) => Some(found),
+        (true, true) => prev(text, found),
+        (true, false) => next(text, found),
+    }
+}
+
+/// The byte range a text object covers around `pos`.
+pub fn text_object_range(text: &str, pos: usize, object: TextObject) -> Option<(usize, usize)> {
+    match object {
+        TextObject::Word { big, around } => word_object(text, pos, big, around),
+        TextObject::Quote { quote, around } => quote_object(text, pos, quote, around),
+        TextObject::Block {
+            open,
+            close,
+            around,
+        } => block_object(text, pos, open, close, around),
+    }
+}
+
+fn word_object(text: &str, pos: usize, big: bool, around: bool) -> Option<(usize, usize)> {
+    let c = char_at(text, pos).filter(|&c| c != '\n')?;
+    let word_class = class(c, big);
+    let same =
+        |p: usize| char_at(text, p).is_some_and(|c| c != '\n' && class(c, big) == word_class);
+
+    let mut start = pos;
+    while let Some(p) = prev(text, start).filter(|&p| same(p)) {
+        start = p;
+    }
+    let mut end = pos;
+    while same(end) {
+        end = next(text, end).unwrap_or(text.len());
+    }
+
+    if around && word_class != CharClass::Blank {
+        // Take the blanks after the word, or before it when there are none
+        let blank = |p: usize| char_at(text, p).is_some_and(|c| c != '\n' && c.is_whitespace());
+        if blank(end) {
+            while blank(end) {
+                end = next(text, end).unwrap_or(text.len());
+            }
+        } else {
+            while let Some(p) = prev(text, start).filter(|&p| blank(p)) {
+                start = p;
+            }
+        }
+    }
+    Some((start, end))
+}
+
+/// Quotes pair up from the start of the line, a doubled quote staying inside the string as
+/// in SQL; the pair around the cursor is used, or the next one after it.
+fn quote_object(text: &str, pos: usize, quote: char, around: bool) -> Option<(usize, usize)> {
+    let start = line_start(text, pos);
+    let line = &text[start..line_end(text, pos)];
+    let mut quotes = Vec::new();
+    let mut from = 0;
+    while let Some(found) = line[from..].find(quote) {
+        let open = from + found;
+        let len = lexer::quoted_len(&line[open..], quote, false);
+        // An unterminated string has no closing quote to pair with
+        if len <= quote.len_utf8() || !line[open..open + len].ends_with(quote) {
+            break;
+        }
+        quotes.push(start + open);
+        quotes.push(start + open + len - quote.len_utf8());
+        from = open + len;
+    }
+
+    let (open, close) = quotes
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|&(open, close)| open <= pos && pos <= close)
+        .or_else(|| {
+            quotes
+                .chunks_exact(2)
+                .map(|pair| (pair[0], pair[1]))
+                .find(|&(open, _)| open > pos)
+        })?;
+    if around {
+        Some((open, close + quote.len_utf8()))
+    } else {
+        Some((open + quote.len_utf8(), close))
+    }
+}
+
+fn block_object(
+    text: &str,
+    pos: usize,
+    open: char,
+    close: char,
+    around: bool,
+) -> Option<(usize, usize)> {
+    // The opening bracket: under the cursor, or the nearest unmatched one before it
+    let open_pos = if char_at(text, pos) == Some(open) {
+        pos
+    } else {
+        let mut depth = 0;
+        let mut found = None;
+        for (idx, c) in text[..pos].char_indices().rev() {
+            if c == close {
+                depth += 1;
+            } else if c == open {
+                if depth == 0 {
+                    found = Some(idx);
+                    break;
+                }
+                depth -= 1;
+            }
+        }
+        found?
+    };
+
+    let mut depth = 0;
+    let mut close_pos = None;
+    for (idx, c) in text[open_pos..].char_indices().skip(1) {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                close_pos = Some(open_pos + idx);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    let close_pos = close_pos?;
+    if around {
+        Some((open_pos, close_pos + close.len_utf8()))
+    } else {
+        Some((open_pos + open.len_utf8(), close_pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Offsets `motion` visits from the start of `text`, repeated until it stops moving.
+    fn stops(text: &str, motion: Motion) -> Vec<usize> {
+        let mut stops = Vec::new();
+        let mut pos = 0;
+        while let Some(next) = motion_target(text, pos, motion, None).filter(|&p| p != pos) {
+            stops.push(next);
+            pos = next;
+        }
+        stops
+    }
+
+    fn range(text: &str, pos: usize, object: TextObject) -> Option<&str> {
+        text_object_range(text, pos, object).map(|(start, end)| &text[start..end])
+    }
+
+    #[test]
+    fn word_motions() {
+        let text = "SELECT a.id, b_2 FROM t";
+        assert_eq!(
+            stops(text, Motion::WordStart),
+            vec![7, 8, 9, 11, 13, 17, 22, 23]
+        );
+        assert_eq!(stops(text, Motion::BigWordStart), vec![7, 13, 17, 22, 23]);
+        assert_eq!(
+            stops(text, Motion::WordEnd),
+            vec![5, 7, 8, 10, 11, 15, 20, 22]
+        );
+        assert_eq!(motion_target(text, 23, Motion::WordBack, None), Some(22));
+        assert_eq!(motion_target(text, 16, Motion::BigWordBack, None), Some(13));
+
+        // Counts
+        assert_eq!(motion_target(text, 0, Motion::WordStart, Some(3)), Some(9));
+        assert_eq!(motion_target(text, 0, Motion::WordEnd, Some(2)), Some(7));
+        assert_eq!(motion_target(text, 22, Motion::WordBack, Some(4)), Some(9));
+
+        // An empty line is a word of its own
+        assert_eq!(stops("a\n\nb", Motion::WordStart), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn word_motions_step_over_combining_marks() {
+        // "café" with a decomposed é: e + U+0301
+        let text = "cafe\u{301} bar";
+        let end = "cafe\u{301}".len();
+        assert_eq!(
+            motion_target(text, 0, Motion::WordStart, None),
+            Some(end + 1)
+        );
+        assert_eq!(motion_target(text, 0, Motion::WordEnd, None), Some(3));
+        assert_eq!(
+            motion_target(text, end + 1, Motion::WordBack, None),
+            Some(0)
+        );
+        assert_eq!(
+            range(
+                text,
+                3,
+                TextObject::Word {
+                    big: false,
+                    around: false
+                }
+            ),
+            Some("cafe\u{301}")
+        );
+    }
+
+    #[test]
+    fn find_char_motions() {
+        let text = "a,b,c,d\nx,y";
+        let f = |forward, till| Motion::FindChar {
+            ch: ',',
+            forward,
+            till,
+        };
+        assert_eq!(motion_target(text, 0, f(true, false), None), Some(1));
+        assert_eq!(motion_target(text, 0, f(true, false), Some(3)), Some(5));
+        assert_eq!(motion_target(text, 0, f(true, true), Some(2)), Some(2));
+        assert_eq!(motion_target(text, 6, f(false, false), None), Some(5));
+        assert_eq!(motion_target(text, 6, f(false, true), Some(2)), Some(4));
+        // Only on the cursor's line
+        assert_eq!(motion_target(text, 5, f(true, false), Some(2)), None);
+        assert_eq!(motion_target(text, 8, f(false, false), None), None);
+    }
+
+    #[test]
+    fn word_objects() {
+        let text = "SELECT  name, id";
+        let word = |around| TextObject::Word { big: false, around };
+        assert_eq!(range(text, 9, word(false)), Some("name"));
+        // No blanks after the word: take the ones before it
+        assert_eq!(range(text, 9, word(true)), Some("  name"));
+        assert_eq!(range(text, 2, word(true)), Some("SELECT  "));
+        assert_eq!(range(text, 15, word(true)), Some(" id"));
+        assert_eq!(range(text, 6, word(false)), Some("  "));
+        assert_eq!(
+            range(
+                text,
+                12,
+                TextObject::Word {
+                    big: true,
+                    around: false
+                }
+            ),
+            Some("name,")
+        );
+    }
+
+    #[test]
+    fn quote_objects_keep_doubled_quotes() {
+        let text = "SELECT 'it''s', 'x', '' FROM t";
+        let quote = |around| TextObject::Quote {
+            quote: '\'',
+            around,
+        };
+        assert_eq!(range(text, 10, quote(false)), Some("it''s"));
+        assert_eq!(range(text, 10, quote(true)), Some("'it''s'"));
+        assert_eq!(range(text, 16, quote(false)), Some("x"));
+        // From before the first string, the next one is used
+        assert_eq!(range(text, 0, quote(false)), Some("it''s"));
+        assert_eq!(range(text, 21, quote(false)), Some(""));
+        assert_eq!(range("'open", 2, quote(false)), None);
+    }
+
+    #[test]
+    fn block_objects() {
+        let text = "COUNT(a, f(b), c)";
+        let paren = |around| TextObject::Block {
+            open: '(',
+            close: ')',
+            around,
+        };
+        assert_eq!(range(text, 7, paren(false)), Some("a, f(b), c"));
+        assert_eq!(range(text, 11, paren(false)), Some("b"));
+        assert_eq!(range(text, 10, paren(true)), Some("(b)"));
+        assert_eq!(range(text, 14, paren(true)), Some("(a, f(b), c)"));
+        assert_eq!(range(text, 2, paren(false)), None);
+    }
+}
@@ -0,0 +1,75 @@
+use super::motion::YankedText;
+use std::collections::BTreeMap;
+
+/// Whether `name` can follow `"`: `a`–`z`, `A`–`Z` (append to `a`–`z`), the unnamed `"` and
+/// the clipboard registers `+` and `*`.
+pub fn is_register_name(name: char) -> bool {
+    name.is_ascii_alphabetic() || matches!(name, '"' | '+' | '*')
+}
+
+pub fn is_clipboard_register(name: char) -> bool {
+    matches!(name, '+' | '*')
+}
+
+/// Yanked and deleted text. The clipboard registers live in the system clipboard and are
+/// not kept here.
+#[derive(Debug, Default)]
+pub struct Registers {
+    unnamed: Option<YankedText>,
+    named: BTreeMap<char, YankedText>,
+}
+
+impl Registers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every write also fills the unnamed register; an uppercase name appends to its
+    /// lowercase register.
+    pub fn store(&mut self, name: Option<char>, yanked: YankedText) {
+        match name {
+            Some(name) if name.is_ascii_lowercase() => {
+                self.named.insert(name, yanked.clone());
+            }
+            Some(name) if name.is_ascii_uppercase() => {
+                let register = self
+                    .named
+                    .entry(name.to_ascii_lowercase())
+                    .or_insert_with(|| YankedText {
+                        text: String::new(),
+                        linewise: yanked.linewise,
+                    });
+                // Lines appended to characters start on a line of their own
+                if yanked.linewise && !register.linewise && !register.text.is_empty() {
+                    register.text.push('\n');
+                    register.linewise = true;
+                }
+                register.text.push_str(&yanked.text);
+                self.unnamed = Some(register.clone());
+                return;
+            }
+            _ => {}
+        }
+        self.unnamed = Some(yanked);
+    }
+
+    pub fn get(&self, name: Option<char>) -> Option<&YankedText> {
+        match name {
+            Some(name) if name.is_ascii_alphabetic() => self.named.get(&name.to_ascii_lowercase()),
+            _ => self.unnamed.as_ref(),
+        }
+    }
+
+    /// Registers holding text, the unnamed one first.
+    pub fn entries(&self) -> Vec<(char, YankedText)> {
+        self.unnamed
+            .iter()
+            .map(|yanked| ('"', yanked.clone()))
+            .chain(
+                self.named
+                    .iter()
+                    .map(|(&name, yanked)| (name, yanked.clone())),
+            )
+            .collect()
+    }
+}